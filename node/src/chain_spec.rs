@@ -1,6 +1,6 @@
 use node_template_runtime::{
-	AccessModuleConfig, AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	Signature, SudoConfig, SystemConfig, WASM_BINARY,
+	pallet_access::roles, AccessModuleConfig, AccountId, AuraConfig, BalancesConfig, GenesisConfig,
+	GrandpaConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -152,7 +152,16 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			key: Some(root_key),
 		},
-		access_module: AccessModuleConfig { roles: vec![[0u8; 32], [1u8; 32]] },
+		access_module: AccessModuleConfig {
+			roles: vec![
+				roles::DOCTOR,
+				roles::PRIVACY_ADMIN,
+				roles::COMPLIANCE,
+				roles::PATIENT_ADMIN,
+				roles::PATIENT,
+			],
+			members: vec![],
+		},
 		transaction_payment: Default::default(),
 	}
 }
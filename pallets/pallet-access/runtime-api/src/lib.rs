@@ -0,0 +1,23 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait AccessApi<RoleMetadata>
+	where
+		RoleMetadata: Codec,
+	{
+		/// Returns the label, description hash and admin role of a role, if any was set.
+		fn role_metadata(role: [u8; 32]) -> Option<RoleMetadata>;
+
+		/// Returns the roles without any active member.
+		fn empty_roles() -> Vec<[u8; 32]>;
+
+		/// Returns every existing role, in creation order.
+		fn all_roles() -> Vec<[u8; 32]>;
+
+		/// Returns whether the role exists.
+		fn role_exists(role: [u8; 32]) -> bool;
+	}
+}
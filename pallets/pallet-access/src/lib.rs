@@ -5,45 +5,156 @@
 /// <https://docs.substrate.io/reference/frame-pallets/>
 pub use pallet::*;
 
+pub mod roles;
+
+/// The all-zero role identifier is what an unset role looks like, so it can never be created or
+/// assigned.
+pub const RESERVED_ROLE: [u8; 32] = [0u8; 32];
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::pallet_prelude::*;
+	use frame_support::{pallet_prelude::*, sp_runtime::traits::Hash};
 	use frame_system::pallet_prelude::*;
 
+	/// Descriptive data attached to a role
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct RoleMetadata<T: Config> {
+		pub label: BoundedVec<u8, T::MaxMetadataLength>,
+		pub description_hash: Option<BoundedVec<u8, T::MaxMetadataLength>>,
+		pub admin_role: Option<[u8; 32]>,
+	}
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Length bound for role labels and description hashes.
+		#[pallet::constant]
+		type MaxMetadataLength: Get<u32>;
+
+		/// Maximum number of roles that can exist.
+		#[pallet::constant]
+		type MaxRoles: Get<u32>;
+
+		/// Maximum number of members moved by a single `rotate_role` call.
+		#[pallet::constant]
+		type MaxRotationBatch: Get<u32>;
+
+		/// Maximum number of roles a single account can hold.
+		#[pallet::constant]
+		type MaxRolesPerAccount: Get<u32>;
+
+		/// Origin allowed to administer roles, e.g. `EnsureRoot` or a collective.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Whether events changing an account's roles carry the hash of the account as a topic, so
+		/// clients can follow a single account's roles.
+		#[pallet::constant]
+		type RoleEventTopics: Get<bool>;
 	}
 
 	#[pallet::storage]
-	type Roles<T: Config> = StorageMap<_, Blake2_128Concat, [u8; 32], ()>;
+	pub(super) type Roles<T: Config> = StorageMap<_, Blake2_128Concat, [u8; 32], ()>;
 
 	#[pallet::storage]
-	type MemberRoles<T: Config> =
+	pub(super) type MemberRoles<T: Config> =
 		StorageDoubleMap<_, Blake2_128Concat, [u8; 32], Blake2_128Concat, T::AccountId, bool>;
 
+	/// Active roles of each account, the reverse of `MemberRoles`.
+	#[pallet::storage]
+	#[pallet::getter(fn account_roles)]
+	pub type AccountRoles<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<[u8; 32], T::MaxRolesPerAccount>,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn all_roles)]
+	pub type AllRoles<T: Config> = StorageValue<_, BoundedVec<[u8; 32], T::MaxRoles>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn role_metadata)]
+	pub type RoleMetadataOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, [u8; 32], RoleMetadata<T>, OptionQuery>;
+
+	/// Rotations that still have members left to move, keyed by the old role.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_rotation)]
+	pub type PendingRotation<T: Config> =
+		StorageMap<_, Blake2_128Concat, [u8; 32], [u8; 32], OptionQuery>;
+
+	/// Members moved so far by the rotations in `PendingRotation`, keyed by the old role.
+	#[pallet::storage]
+	#[pallet::getter(fn rotated_members)]
+	pub type RotatedMembers<T: Config> = StorageMap<_, Blake2_128Concat, [u8; 32], u32, ValueQuery>;
+
+	/// Block from which a time-limited assignment no longer grants the role. Assignments without
+	/// an entry are permanent.
+	#[pallet::storage]
+	#[pallet::getter(fn role_expiry)]
+	pub type RoleExpiry<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		[u8; 32],
+		Blake2_128Concat,
+		T::AccountId,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// Roles whose assignments never expire, whatever expiry is stored for them.
+	#[pallet::storage]
+	#[pallet::getter(fn is_expiry_exempt)]
+	pub type ExpiryExemptRoles<T: Config> =
+		StorageMap<_, Blake2_128Concat, [u8; 32], bool, ValueQuery>;
+
 	#[pallet::genesis_config]
-	pub struct GenesisConfig {
+	pub struct GenesisConfig<T: Config> {
 		pub roles: Vec<[u8; 32]>,
+		/// Role assignments made at genesis, after `roles` are created
+		pub members: Vec<(T::AccountId, [u8; 32])>,
 	}
 
 	#[cfg(feature = "std")]
-	impl Default for GenesisConfig {
+	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
-			Self { roles: Vec::new() }
+			Self { roles: Vec::new(), members: Vec::new() }
 		}
 	}
 
 	// The build of genesis for the pallet.
 	#[pallet::genesis_build]
-	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
 			for role in &self.roles {
+				assert!(role != &crate::RESERVED_ROLE, "the zero role is reserved");
 				Roles::<T>::insert(role, ());
+				AllRoles::<T>::try_append(role).expect("genesis roles exceed MaxRoles");
+			}
+
+			// Goes through `assign_role` so `MemberRoles` and `AccountRoles` stay in sync.
+			// frame_system does not record events at block zero, so no `RoleAssigned` is
+			// emitted and indexers have to read genesis assignments from `AccountRoles`.
+			for (user, role) in &self.members {
+				Pallet::<T>::assign_role(user.clone(), *role)
+					.expect("genesis members must hold distinct existing roles");
 			}
 		}
 	}
@@ -55,6 +166,15 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		RoleAssigned { user: T::AccountId, role: [u8; 32] },
 		RoleRevoked { user: T::AccountId, role: [u8; 32] },
+		RoleMetadataSet { role: [u8; 32] },
+		RoleCreated { role: [u8; 32] },
+		RoleDeleted { role: [u8; 32], members_removed: u32 },
+		RoleRotationProgress { old: [u8; 32], new: [u8; 32], members_migrated: u32 },
+		RoleRotated { old: [u8; 32], new: [u8; 32], members_migrated: u32 },
+		RolesReplaced { user: T::AccountId },
+		RoleAssignedUntil { user: T::AccountId, role: [u8; 32], expires_at: T::BlockNumber },
+		RoleExpired { user: T::AccountId, role: [u8; 32] },
+		ExpiryExemptSet { role: [u8; 32], exempt: bool },
 	}
 
 	// Errors inform users that something went wrong.
@@ -64,11 +184,36 @@ pub mod pallet {
 		AlreadyHasRole,
 		InvalidRole,
 		NotAssigned,
+		ReservedRole,
+		RoleExists,
+		TooManyRoles,
+		RotationInProgress,
+		TooManyAccountRoles,
+		ExpiryInPast,
+		NotExpired,
 	}
 
 	impl<T: Config> Pallet<T> {
-		// Generates and returns the unique_id and color
-		fn assign_role(user: T::AccountId, new_role: [u8; 32]) -> Result<(), DispatchError> {
+		/// Deposits an event about the user's roles, indexed by the hash of the user's account
+		/// when `RoleEventTopics` is set.
+		fn deposit_role_event(user: &T::AccountId, event: Event<T>) {
+			if !T::RoleEventTopics::get() {
+				return Self::deposit_event(event)
+			}
+
+			let topic = T::Hashing::hash_of(user);
+
+			frame_system::Pallet::<T>::deposit_event_indexed(
+				&[topic],
+				<T as Config>::RuntimeEvent::from(event).into(),
+			);
+		}
+
+		/// Assigns the role without any origin check. Meant for other pallets granting roles as
+		/// part of their own flows.
+		pub fn assign_role(user: T::AccountId, new_role: [u8; 32]) -> Result<(), DispatchError> {
+			ensure!(new_role != crate::RESERVED_ROLE, Error::<T>::ReservedRole);
+
 			ensure!(Roles::<T>::contains_key(&new_role), Error::<T>::InvalidRole);
 
 			if MemberRoles::<T>::contains_key(&new_role, &user) {
@@ -78,9 +223,15 @@ pub mod pallet {
 				);
 			}
 
+			AccountRoles::<T>::try_append(&user, new_role)
+				.map_err(|_| Error::<T>::TooManyAccountRoles)?;
+
 			MemberRoles::<T>::insert(new_role, user.clone(), true);
 
-			Self::deposit_event(Event::RoleAssigned { user: user.clone(), role: new_role });
+			Self::deposit_role_event(
+				&user,
+				Event::RoleAssigned { user: user.clone(), role: new_role },
+			);
 
 			Ok(())
 		}
@@ -94,20 +245,371 @@ pub mod pallet {
 
 			MemberRoles::<T>::insert(&new_role, &user, false);
 
-			Self::deposit_event(Event::RoleRevoked { user: user.clone(), role: new_role });
+			RoleExpiry::<T>::remove(&new_role, &user);
+
+			AccountRoles::<T>::mutate(&user, |roles| roles.retain(|role| role != &new_role));
+
+			Self::deposit_role_event(
+				&user,
+				Event::RoleRevoked { user: user.clone(), role: new_role },
+			);
 
 			Ok(())
 		}
 
-		fn validate_role(user: T::AccountId, new_role: [u8; 32]) -> Result<(), DispatchError> {
+		pub fn validate_role(user: T::AccountId, new_role: [u8; 32]) -> Result<(), DispatchError> {
 			ensure!(Roles::<T>::contains_key(&new_role), Error::<T>::InvalidRole);
 
 			ensure!(MemberRoles::<T>::contains_key(&new_role, &user), Error::<T>::NotAssigned);
 
 			ensure!(MemberRoles::<T>::get(&new_role, &user).unwrap(), Error::<T>::AccessDenied);
 
+			// A lapsed assignment no longer grants the role, even before it is cleaned up.
+			ensure!(!Self::has_lapsed(&user, new_role), Error::<T>::AccessDenied);
+
+			Ok(())
+		}
+
+		/// Returns whether the user's assignment of the role has passed its expiry. Assignments
+		/// of roles in `ExpiryExemptRoles` never lapse.
+		fn has_lapsed(user: &T::AccountId, role: [u8; 32]) -> bool {
+			match RoleExpiry::<T>::get(&role, user) {
+				Some(expires_at) => {
+					!ExpiryExemptRoles::<T>::get(&role) &&
+						frame_system::Pallet::<T>::block_number() >= expires_at
+				},
+				None => false,
+			}
+		}
+
+		/// Assigns the role until `expires_at`, from when it no longer grants access and can be
+		/// cleaned up with `expire_role`.
+		fn assign_role_until(
+			user: T::AccountId,
+			role: [u8; 32],
+			expires_at: T::BlockNumber,
+		) -> Result<(), DispatchError> {
+			ensure!(
+				expires_at > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::ExpiryInPast
+			);
+
+			Self::assign_role(user.clone(), role)?;
+
+			RoleExpiry::<T>::insert(&role, &user, expires_at);
+
+			Self::deposit_role_event(
+				&user,
+				Event::RoleAssignedUntil { user: user.clone(), role, expires_at },
+			);
+
+			Ok(())
+		}
+
+		/// Revokes a lapsed assignment. Assignments of exempt roles are skipped and kept.
+		fn expire(user: T::AccountId, role: [u8; 32]) -> Result<(), DispatchError> {
+			ensure!(RoleExpiry::<T>::contains_key(&role, &user), Error::<T>::NotAssigned);
+
+			ensure!(Self::has_lapsed(&user, role), Error::<T>::NotExpired);
+
+			Self::revoke_role(user.clone(), role)?;
+
+			Self::deposit_role_event(&user, Event::RoleExpired { user: user.clone(), role });
+
+			Ok(())
+		}
+
+		fn set_exempt(role: [u8; 32], exempt: bool) -> Result<(), DispatchError> {
+			ensure!(Roles::<T>::contains_key(&role), Error::<T>::InvalidRole);
+
+			if exempt {
+				ExpiryExemptRoles::<T>::insert(&role, true);
+			} else {
+				ExpiryExemptRoles::<T>::remove(&role);
+			}
+
+			Self::deposit_event(Event::ExpiryExemptSet { role, exempt });
+
+			Ok(())
+		}
+
+		/// Succeeds only if the user holds every one of `roles`.
+		pub fn validate_roles_all(
+			user: T::AccountId,
+			roles: &[[u8; 32]],
+		) -> Result<(), DispatchError> {
+			ensure!(
+				roles.iter().all(|role| Self::validate_role(user.clone(), *role).is_ok()),
+				Error::<T>::AccessDenied
+			);
+
+			Ok(())
+		}
+
+		/// Succeeds if the user holds at least one of `roles`.
+		pub fn validate_roles_any(
+			user: T::AccountId,
+			roles: &[[u8; 32]],
+		) -> Result<(), DispatchError> {
+			ensure!(
+				roles.iter().any(|role| Self::validate_role(user.clone(), *role).is_ok()),
+				Error::<T>::AccessDenied
+			);
+
+			Ok(())
+		}
+
+		fn set_metadata(role: [u8; 32], metadata: RoleMetadata<T>) -> Result<(), DispatchError> {
+			ensure!(Roles::<T>::contains_key(&role), Error::<T>::InvalidRole);
+
+			RoleMetadataOf::<T>::insert(&role, metadata);
+
+			Self::deposit_event(Event::RoleMetadataSet { role });
+
+			Ok(())
+		}
+
+		fn add_role(role: [u8; 32]) -> Result<(), DispatchError> {
+			ensure!(role != crate::RESERVED_ROLE, Error::<T>::ReservedRole);
+
+			ensure!(!Roles::<T>::contains_key(&role), Error::<T>::RoleExists);
+
+			AllRoles::<T>::try_append(role).map_err(|_| Error::<T>::TooManyRoles)?;
+
+			Roles::<T>::insert(&role, ());
+
+			Self::deposit_event(Event::RoleCreated { role });
+
+			Ok(())
+		}
+
+		/// Deletes the role along with its assignments and metadata. Roles taking part in a
+		/// rotation are kept until it completes.
+		fn remove_role(role: [u8; 32]) -> Result<(), DispatchError> {
+			ensure!(Roles::<T>::contains_key(&role), Error::<T>::InvalidRole);
+
+			ensure!(
+				!PendingRotation::<T>::iter().any(|(old, new)| old == role || new == role),
+				Error::<T>::RotationInProgress
+			);
+
+			let mut members_removed = 0u32;
+			for (user, active) in MemberRoles::<T>::drain_prefix(&role) {
+				if active {
+					AccountRoles::<T>::mutate(&user, |roles| roles.retain(|held| held != &role));
+					members_removed += 1;
+				}
+			}
+
+			let _ = RoleExpiry::<T>::clear_prefix(&role, u32::MAX, None);
+
+			Roles::<T>::remove(&role);
+			RoleMetadataOf::<T>::remove(&role);
+			ExpiryExemptRoles::<T>::remove(&role);
+			AllRoles::<T>::mutate(|roles| roles.retain(|held| held != &role));
+
+			Self::deposit_event(Event::RoleDeleted { role, members_removed });
+
 			Ok(())
 		}
+
+		/// Moves up to `MaxRotationBatch` members from `old` to `new`. The first call creates
+		/// `new` and copies the metadata, later calls with the same arguments continue the
+		/// rotation. Once no member is left `old` is deleted.
+		fn rotate(old: [u8; 32], new: [u8; 32]) -> Result<(), DispatchError> {
+			ensure!(Roles::<T>::contains_key(&old), Error::<T>::InvalidRole);
+
+			match PendingRotation::<T>::get(&old) {
+				Some(target) => ensure!(target == new, Error::<T>::RotationInProgress),
+				None => {
+					Self::add_role(new)?;
+
+					if let Some(metadata) = RoleMetadataOf::<T>::get(&old) {
+						RoleMetadataOf::<T>::insert(&new, metadata);
+					}
+
+					if ExpiryExemptRoles::<T>::get(&old) {
+						ExpiryExemptRoles::<T>::insert(&new, true);
+					}
+
+					PendingRotation::<T>::insert(&old, new);
+				},
+			}
+
+			let batch: Vec<(T::AccountId, bool)> = MemberRoles::<T>::drain_prefix(&old)
+				.take(T::MaxRotationBatch::get() as usize)
+				.collect();
+
+			// Revoked assignments are dropped rather than carried over.
+			let mut members_migrated = 0u32;
+			for (user, active) in batch {
+				let expires_at = RoleExpiry::<T>::take(&old, &user);
+
+				if active {
+					MemberRoles::<T>::insert(&new, &user, true);
+					if let Some(expires_at) = expires_at {
+						RoleExpiry::<T>::insert(&new, &user, expires_at);
+					}
+					// The member may have been assigned `new` since the rotation started.
+					AccountRoles::<T>::mutate(&user, |roles| {
+						roles.retain(|role| role != &new);
+						roles.iter_mut().filter(|role| **role == old).for_each(|role| *role = new)
+					});
+					members_migrated += 1;
+				}
+			}
+
+			if MemberRoles::<T>::iter_prefix(&old).next().is_some() {
+				RotatedMembers::<T>::mutate(&old, |total| {
+					*total = total.saturating_add(members_migrated)
+				});
+
+				Self::deposit_event(Event::RoleRotationProgress { old, new, members_migrated });
+
+				return Ok(())
+			}
+
+			// `RoleRotated` reports every member moved by the rotation, not only the last batch.
+			let members_migrated = RotatedMembers::<T>::take(&old).saturating_add(members_migrated);
+
+			Roles::<T>::remove(&old);
+			RoleMetadataOf::<T>::remove(&old);
+			ExpiryExemptRoles::<T>::remove(&old);
+			PendingRotation::<T>::remove(&old);
+			AllRoles::<T>::mutate(|roles| roles.retain(|role| role != &old));
+
+			Self::deposit_event(Event::RoleRotated { old, new, members_migrated });
+
+			Ok(())
+		}
+
+		/// Moves chains set up before the well known roles onto them. Those chains guarded
+		/// patient data and registrations on behalf of patients with the zero role, now
+		/// `RESERVED_ROLE`, so its active members are given `roles::DOCTOR` and
+		/// `roles::PATIENT_ADMIN` and the zero role is deleted. Missing well known roles are
+		/// created. Returns the storage reads and writes made.
+		fn migrate_role_ids() -> (u64, u64) {
+			let mut reads = 0u64;
+			let mut writes = 0u64;
+
+			for role in crate::roles::ALL {
+				reads += 1;
+				if !Roles::<T>::contains_key(&role) {
+					Roles::<T>::insert(&role, ());
+					let _ = AllRoles::<T>::try_append(role);
+					writes += 2;
+				}
+			}
+
+			let holders: Vec<(T::AccountId, bool)> =
+				MemberRoles::<T>::drain_prefix(&crate::RESERVED_ROLE).collect();
+
+			for (user, active) in holders {
+				reads += 1;
+				writes += 1;
+
+				if !active {
+					continue
+				}
+
+				for role in [crate::roles::DOCTOR, crate::roles::PATIENT_ADMIN] {
+					reads += 1;
+					if !MemberRoles::<T>::get(&role, &user).unwrap_or(false) {
+						MemberRoles::<T>::insert(&role, &user, true);
+						writes += 1;
+					}
+				}
+			}
+
+			Roles::<T>::remove(&crate::RESERVED_ROLE);
+			RoleMetadataOf::<T>::remove(&crate::RESERVED_ROLE);
+			AllRoles::<T>::mutate(|roles| roles.retain(|role| role != &crate::RESERVED_ROLE));
+			writes += 3;
+
+			(reads, writes)
+		}
+
+		/// Assigns the roles in `roles` the user lacks and revokes those the user holds beyond
+		/// them.
+		fn replace_roles(
+			user: T::AccountId,
+			roles: BoundedVec<[u8; 32], T::MaxRolesPerAccount>,
+		) -> Result<(), DispatchError> {
+			for role in AccountRoles::<T>::get(&user) {
+				if !roles.contains(&role) {
+					Self::revoke_role(user.clone(), role)?;
+				}
+			}
+
+			for role in roles {
+				// Re-read so that duplicates in `roles` are only assigned once.
+				if !AccountRoles::<T>::get(&user).contains(&role) {
+					Self::assign_role(user.clone(), role)?;
+				}
+			}
+
+			Self::deposit_role_event(&user, Event::RolesReplaced { user: user.clone() });
+
+			Ok(())
+		}
+
+		/// Returns whether the role was created.
+		pub fn role_exists(role: [u8; 32]) -> bool {
+			Roles::<T>::contains_key(&role)
+		}
+
+		/// Returns the roles that exist but have no active member. Walks every role and its
+		/// assignments, so it is meant for off-chain queries only.
+		pub fn empty_roles() -> Vec<[u8; 32]> {
+			Roles::<T>::iter_keys()
+				.filter(|role| !MemberRoles::<T>::iter_prefix_values(role).any(|active| active))
+				.collect()
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain = StorageVersion::get::<Pallet<T>>();
+
+			if on_chain >= 3 {
+				return Weight::zero()
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+
+			if on_chain < 1 {
+				// Roles created before AllRoles existed are copied over, up to MaxRoles.
+				let roles: Vec<[u8; 32]> = Roles::<T>::iter_keys().collect();
+				reads += roles.len() as u64;
+				writes += 1;
+
+				AllRoles::<T>::put(BoundedVec::truncate_from(roles));
+			}
+
+			if on_chain < 2 {
+				let (role_reads, role_writes) = Self::migrate_role_ids();
+				reads += role_reads;
+				writes += role_writes;
+			}
+
+			if on_chain < 3 {
+				// Active assignments made before AccountRoles existed are indexed, up to
+				// MaxRolesPerAccount per account.
+				for (role, user, active) in MemberRoles::<T>::iter() {
+					reads += 1;
+					if active {
+						let _ = AccountRoles::<T>::try_append(&user, role);
+						writes += 1;
+					}
+				}
+			}
+
+			STORAGE_VERSION.put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(reads, writes)
+		}
 	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -122,7 +624,7 @@ pub mod pallet {
 			user: T::AccountId,
 			new_role: [u8; 32],
 		) -> DispatchResult {
-			ensure_root(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 
 			Self::assign_role(user.clone(), new_role)?;
 
@@ -136,7 +638,7 @@ pub mod pallet {
 			user: T::AccountId,
 			new_role: [u8; 32],
 		) -> DispatchResult {
-			ensure_root(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 
 			Self::revoke_role(user.clone(), new_role)?;
 
@@ -156,5 +658,124 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		#[pallet::weight(0)]
+		#[pallet::call_index(4)]
+		pub fn set_role_metadata(
+			origin: OriginFor<T>,
+			role: [u8; 32],
+			label: BoundedVec<u8, T::MaxMetadataLength>,
+			description_hash: Option<BoundedVec<u8, T::MaxMetadataLength>>,
+			admin_role: Option<[u8; 32]>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Self::set_metadata(role, RoleMetadata { label, description_hash, admin_role })?;
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		#[pallet::call_index(5)]
+		pub fn create_role(origin: OriginFor<T>, role: [u8; 32]) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Self::add_role(role)?;
+
+			Ok(())
+		}
+
+		/// Deletes the role, revoking it from every member and dropping its metadata.
+		#[pallet::weight(0)]
+		#[pallet::call_index(6)]
+		pub fn delete_role(origin: OriginFor<T>, role: [u8; 32]) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Self::remove_role(role)?;
+
+			Ok(())
+		}
+
+		/// Replaces `old_role` by `new_role`, keeping its active members and metadata. Roles
+		/// with more than `MaxRotationBatch` members are moved over several calls with the same
+		/// arguments, each emitting `RoleRotationProgress` with its batch until the final
+		/// `RoleRotated` with the total.
+		#[pallet::weight(0)]
+		#[pallet::call_index(7)]
+		pub fn rotate_role(
+			origin: OriginFor<T>,
+			old_role: [u8; 32],
+			new_role: [u8; 32],
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Self::rotate(old_role, new_role)?;
+
+			Ok(())
+		}
+
+		/// Replaces the user's roles by `roles`, assigning and revoking the difference.
+		#[pallet::weight(0)]
+		#[pallet::call_index(8)]
+		pub fn set_roles(
+			origin: OriginFor<T>,
+			user: T::AccountId,
+			roles: BoundedVec<[u8; 32], T::MaxRolesPerAccount>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Self::replace_roles(user, roles)?;
+
+			Ok(())
+		}
+
+		/// Assigns the role until `expires_at`. From that block on the assignment no longer
+		/// grants the role, unless the role is exempt from expiry.
+		#[pallet::weight(0)]
+		#[pallet::call_index(9)]
+		pub fn assign_until(
+			origin: OriginFor<T>,
+			user: T::AccountId,
+			role: [u8; 32],
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Self::assign_role_until(user, role, expires_at)?;
+
+			Ok(())
+		}
+
+		/// Exempts the role from expiry, so its time-limited assignments are kept as permanent,
+		/// or lifts the exemption.
+		#[pallet::weight(0)]
+		#[pallet::call_index(10)]
+		pub fn set_expiry_exempt(
+			origin: OriginFor<T>,
+			role: [u8; 32],
+			exempt: bool,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Self::set_exempt(role, exempt)?;
+
+			Ok(())
+		}
+
+		/// Revokes an assignment past its expiry. Any signed account may clean up lapsed
+		/// assignments, those of exempt roles are skipped.
+		#[pallet::weight(0)]
+		#[pallet::call_index(11)]
+		pub fn expire_role(
+			origin: OriginFor<T>,
+			user: T::AccountId,
+			role: [u8; 32],
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			Self::expire(user, role)?;
+
+			Ok(())
+		}
 	}
 }
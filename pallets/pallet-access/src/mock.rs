@@ -0,0 +1,103 @@
+use crate as pallet_access;
+use crate::roles;
+use frame_support::{
+	ord_parameter_types, parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64, EitherOfDiverse},
+};
+use frame_system::{EnsureRoot, EnsureSignedBy};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+/// Role not created at genesis.
+pub const UNKNOWN: [u8; 32] = [0xffu8; 32];
+/// Account assigned `roles::DOCTOR` at genesis.
+pub const GENESIS_DOCTOR: u64 = 100;
+/// Account allowed to administer roles next to root.
+pub const ADMIN: u64 = 50;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		AccessModule: pallet_access,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_access::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxMetadataLength = ConstU32<32>;
+	type MaxRoles = ConstU32<8>;
+	type MaxRotationBatch = ConstU32<2>;
+	type MaxRolesPerAccount = ConstU32<4>;
+	type AdminOrigin = EitherOfDiverse<EnsureRoot<u64>, EnsureSignedBy<Admin, u64>>;
+	type RoleEventTopics = RoleEventTopics;
+}
+
+parameter_types! {
+	pub static RoleEventTopics: bool = true;
+}
+
+ord_parameter_types! {
+	pub const Admin: u64 = ADMIN;
+}
+
+/// Builds genesis storage with the `roles::DOCTOR` and `roles::COMPLIANCE` roles.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext = bootstrap_test_ext(vec![]);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Builds genesis storage with the `roles::DOCTOR` and `roles::COMPLIANCE` roles assigned to
+/// `members`. Storage is left at block zero, as genesis built it.
+pub fn bootstrap_test_ext(members: Vec<(u64, [u8; 32])>) -> sp_io::TestExternalities {
+	let t = GenesisConfig {
+		system: Default::default(),
+		access_module: pallet_access::GenesisConfig {
+			roles: vec![roles::DOCTOR, roles::COMPLIANCE],
+			members,
+		},
+	}
+	.build_storage()
+	.unwrap();
+
+	sp_io::TestExternalities::new(t)
+}
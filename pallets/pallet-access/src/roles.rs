@@ -0,0 +1,33 @@
+//! Well known role identifiers. Each role is a 32 byte identifier with every byte set to the
+//! role's number, so `[1u8; 32]` is role 1. Role 0 is `RESERVED_ROLE`.
+
+/// Doctors and other staff reading and writing patient data. Every byte is `0x01`.
+pub const DOCTOR: [u8; 32] = [1u8; 32];
+
+/// Administrators allowed to freeze records and confirm deregistrations. Every byte is `0x02`.
+pub const PRIVACY_ADMIN: [u8; 32] = [2u8; 32];
+
+/// Compliance officers, e.g. for marking patients as deceased. Every byte is `0x03`.
+pub const COMPLIANCE: [u8; 32] = [3u8; 32];
+
+/// Administrators registering patients on their behalf. Every byte is `0x04`.
+pub const PATIENT_ADMIN: [u8; 32] = [4u8; 32];
+
+/// Registered patients, assigned automatically at registration when enabled. Every byte is
+/// `0x05`.
+pub const PATIENT: [u8; 32] = [5u8; 32];
+
+/// Every well known role, in the order of their numbers.
+pub const ALL: [[u8; 32]; 5] = [DOCTOR, PRIVACY_ADMIN, COMPLIANCE, PATIENT_ADMIN, PATIENT];
+
+/// Returns the identifier of a well known role from its snake case name.
+pub fn role_from_name(name: &str) -> Option<[u8; 32]> {
+	match name {
+		"doctor" => Some(DOCTOR),
+		"privacy_admin" => Some(PRIVACY_ADMIN),
+		"compliance" => Some(COMPLIANCE),
+		"patient_admin" => Some(PATIENT_ADMIN),
+		"patient" => Some(PATIENT),
+		_ => None,
+	}
+}
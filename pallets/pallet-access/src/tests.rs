@@ -0,0 +1,576 @@
+use crate::{
+	mock::*, roles, AccountRoles, AllRoles, Error, Event, MemberRoles, RoleExpiry, RoleMetadata,
+	Roles, RotatedMembers, RESERVED_ROLE,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GetStorageVersion, Hooks, StorageVersion},
+	BoundedVec,
+};
+use sp_runtime::traits::{BadOrigin, BlakeTwo256, Hash};
+
+#[test]
+fn role_metadata_is_read_back_in_one_piece() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(AccessModule::role_metadata(roles::DOCTOR), None);
+
+		let label = BoundedVec::truncate_from(b"doctor".to_vec());
+		let description_hash = Some(BoundedVec::truncate_from(vec![7u8; 4]));
+		assert_noop!(
+			AccessModule::set_role_metadata(
+				RuntimeOrigin::signed(1),
+				roles::DOCTOR,
+				label.clone(),
+				description_hash.clone(),
+				Some(roles::COMPLIANCE)
+			),
+			BadOrigin
+		);
+		assert_ok!(AccessModule::set_role_metadata(
+			RuntimeOrigin::root(),
+			roles::DOCTOR,
+			label.clone(),
+			description_hash.clone(),
+			Some(roles::COMPLIANCE)
+		));
+
+		assert_eq!(
+			AccessModule::role_metadata(roles::DOCTOR),
+			Some(RoleMetadata { label, description_hash, admin_role: Some(roles::COMPLIANCE) })
+		);
+		assert_eq!(AccessModule::role_metadata(roles::COMPLIANCE), None);
+	});
+}
+
+#[test]
+fn metadata_of_an_unknown_role_is_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AccessModule::set_role_metadata(
+				RuntimeOrigin::root(),
+				UNKNOWN,
+				BoundedVec::truncate_from(b"unknown".to_vec()),
+				None,
+				None
+			),
+			Error::<Test>::InvalidRole
+		);
+		assert_eq!(AccessModule::role_metadata(UNKNOWN), None);
+	});
+}
+
+#[test]
+fn zero_role_is_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AccessModule::create_role(RuntimeOrigin::root(), RESERVED_ROLE),
+			Error::<Test>::ReservedRole
+		);
+		assert_noop!(
+			AccessModule::assign(RuntimeOrigin::root(), 2, RESERVED_ROLE),
+			Error::<Test>::ReservedRole
+		);
+	});
+}
+
+#[test]
+fn create_role_adds_a_new_role_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccessModule::create_role(RuntimeOrigin::root(), UNKNOWN));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 2, UNKNOWN));
+		assert_noop!(
+			AccessModule::create_role(RuntimeOrigin::root(), UNKNOWN),
+			Error::<Test>::RoleExists
+		);
+	});
+}
+
+#[test]
+fn empty_roles_lists_roles_without_active_members() {
+	new_test_ext().execute_with(|| {
+		for role in [[7u8; 32], [8u8; 32], [9u8; 32]] {
+			assert_ok!(AccessModule::create_role(RuntimeOrigin::root(), role));
+		}
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, roles::DOCTOR));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, [7u8; 32]));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 2, [8u8; 32]));
+		assert_ok!(AccessModule::revoke(RuntimeOrigin::root(), 2, [8u8; 32]));
+
+		let mut empty = AccessModule::empty_roles();
+		empty.sort();
+		assert_eq!(empty, vec![roles::COMPLIANCE, [8u8; 32], [9u8; 32]]);
+	});
+}
+
+#[test]
+fn all_of_needs_every_role_and_any_of_needs_one() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, roles::DOCTOR));
+
+		assert_noop!(
+			AccessModule::validate_roles_all(1, &[roles::DOCTOR, roles::COMPLIANCE]),
+			Error::<Test>::AccessDenied
+		);
+		assert_ok!(AccessModule::validate_roles_any(1, &[roles::DOCTOR, roles::COMPLIANCE]));
+		assert_noop!(
+			AccessModule::validate_roles_any(2, &[roles::DOCTOR, roles::COMPLIANCE]),
+			Error::<Test>::AccessDenied
+		);
+
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, roles::COMPLIANCE));
+		assert_ok!(AccessModule::validate_roles_all(1, &[roles::DOCTOR, roles::COMPLIANCE]));
+		assert_ok!(AccessModule::validate_roles_any(1, &[roles::DOCTOR, roles::COMPLIANCE]));
+	});
+}
+
+#[test]
+fn role_names_round_trip_to_their_identifiers() {
+	for (name, role) in [
+		("doctor", roles::DOCTOR),
+		("privacy_admin", roles::PRIVACY_ADMIN),
+		("compliance", roles::COMPLIANCE),
+		("patient_admin", roles::PATIENT_ADMIN),
+		("patient", roles::PATIENT),
+	] {
+		assert_eq!(roles::role_from_name(name), Some(role));
+	}
+	assert_eq!(roles::role_from_name("Doctor"), None);
+	assert_eq!(roles::role_from_name("unknown"), None);
+}
+
+#[test]
+fn all_roles_follows_genesis_creation_and_deletion() {
+	new_test_ext().execute_with(|| {
+		let role = [7u8; 32];
+		assert_eq!(AccessModule::all_roles().into_inner(), vec![roles::DOCTOR, roles::COMPLIANCE]);
+
+		assert_ok!(AccessModule::create_role(RuntimeOrigin::root(), role));
+		assert_eq!(
+			AccessModule::all_roles().into_inner(),
+			vec![roles::DOCTOR, roles::COMPLIANCE, role]
+		);
+
+		assert_ok!(AccessModule::delete_role(RuntimeOrigin::root(), roles::DOCTOR));
+		assert_eq!(AccessModule::all_roles().into_inner(), vec![roles::COMPLIANCE, role]);
+	});
+}
+
+#[test]
+fn create_role_stops_at_max_roles() {
+	new_test_ext().execute_with(|| {
+		for byte in 10..16 {
+			assert_ok!(AccessModule::create_role(RuntimeOrigin::root(), [byte; 32]));
+		}
+
+		assert_noop!(
+			AccessModule::create_role(RuntimeOrigin::root(), [16; 32]),
+			Error::<Test>::TooManyRoles
+		);
+		assert!(!Roles::<Test>::contains_key([16; 32]));
+	});
+}
+
+#[test]
+fn delete_role_drops_members_and_metadata() {
+	new_test_ext().execute_with(|| {
+		let role = [7u8; 32];
+		assert_ok!(AccessModule::create_role(RuntimeOrigin::root(), role));
+		assert_ok!(AccessModule::set_role_metadata(
+			RuntimeOrigin::root(),
+			role,
+			BoundedVec::truncate_from(b"auditor".to_vec()),
+			None,
+			None
+		));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 2, role));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 3, role));
+		assert_ok!(AccessModule::revoke(RuntimeOrigin::root(), 3, role));
+
+		assert_noop!(AccessModule::delete_role(RuntimeOrigin::signed(1), role), BadOrigin);
+		assert_ok!(AccessModule::delete_role(RuntimeOrigin::root(), role));
+
+		System::assert_last_event(Event::RoleDeleted { role, members_removed: 1 }.into());
+		assert!(!Roles::<Test>::contains_key(role));
+		assert!(AccessModule::role_metadata(role).is_none());
+		assert!(MemberRoles::<Test>::iter_prefix(role).next().is_none());
+		assert!(AccountRoles::<Test>::get(2).is_empty());
+		assert_noop!(AccessModule::validate_role(2, role), Error::<Test>::InvalidRole);
+		assert_noop!(
+			AccessModule::delete_role(RuntimeOrigin::root(), role),
+			Error::<Test>::InvalidRole
+		);
+	});
+}
+
+#[test]
+fn upgrade_copies_existing_roles_into_all_roles() {
+	new_test_ext().execute_with(|| {
+		AllRoles::<Test>::kill();
+		StorageVersion::new(0).put::<AccessModule>();
+
+		AccessModule::on_runtime_upgrade();
+
+		assert!(roles::ALL.iter().all(|role| AllRoles::<Test>::get().contains(role)));
+		assert_eq!(AllRoles::<Test>::get().len(), roles::ALL.len());
+		assert_eq!(
+			AccessModule::on_chain_storage_version(),
+			AccessModule::current_storage_version()
+		);
+	});
+}
+
+#[test]
+fn upgrade_moves_zero_role_holders_to_the_well_known_roles() {
+	new_test_ext().execute_with(|| {
+		// A chain set up before the well known roles, staff holding the zero role.
+		StorageVersion::new(1).put::<AccessModule>();
+		Roles::<Test>::insert(RESERVED_ROLE, ());
+		AllRoles::<Test>::put(BoundedVec::truncate_from(vec![RESERVED_ROLE, roles::DOCTOR]));
+		MemberRoles::<Test>::insert(RESERVED_ROLE, 2, true);
+		MemberRoles::<Test>::insert(RESERVED_ROLE, 3, false);
+
+		AccessModule::on_runtime_upgrade();
+
+		assert_eq!(
+			AccessModule::on_chain_storage_version(),
+			AccessModule::current_storage_version()
+		);
+		assert!(!Roles::<Test>::contains_key(RESERVED_ROLE));
+		assert!(!AllRoles::<Test>::get().contains(&RESERVED_ROLE));
+		assert!(roles::ALL.iter().all(|role| Roles::<Test>::contains_key(role)));
+		assert!(roles::ALL.iter().all(|role| AllRoles::<Test>::get().contains(role)));
+
+		assert_ok!(AccessModule::validate_role(2, roles::DOCTOR));
+		assert_ok!(AccessModule::validate_role(2, roles::PATIENT_ADMIN));
+		assert!(AccessModule::validate_role(3, roles::DOCTOR).is_err());
+		assert!(MemberRoles::<Test>::iter_prefix(RESERVED_ROLE).next().is_none());
+		assert_eq!(AccountRoles::<Test>::get(2).len(), 2);
+		assert!(AccountRoles::<Test>::get(2).contains(&roles::PATIENT_ADMIN));
+	});
+}
+
+#[test]
+fn rotation_carries_every_active_member_over() {
+	new_test_ext().execute_with(|| {
+		let new = [9u8; 32];
+		assert_ok!(AccessModule::set_role_metadata(
+			RuntimeOrigin::root(),
+			roles::DOCTOR,
+			BoundedVec::truncate_from(b"doctor".to_vec()),
+			None,
+			None
+		));
+		for user in 1..=5 {
+			assert_ok!(AccessModule::assign(RuntimeOrigin::root(), user, roles::DOCTOR));
+		}
+		assert_ok!(AccessModule::revoke(RuntimeOrigin::root(), 5, roles::DOCTOR));
+
+		assert_noop!(
+			AccessModule::rotate_role(RuntimeOrigin::signed(1), roles::DOCTOR, new),
+			BadOrigin
+		);
+		// Five assignments, one of them revoked, moved two at a time.
+		assert_ok!(AccessModule::rotate_role(RuntimeOrigin::root(), roles::DOCTOR, new));
+		System::assert_last_event(
+			Event::RoleRotationProgress { old: roles::DOCTOR, new, members_migrated: 2 }.into(),
+		);
+		assert_noop!(
+			AccessModule::rotate_role(RuntimeOrigin::root(), roles::DOCTOR, [10u8; 32]),
+			Error::<Test>::RotationInProgress
+		);
+		assert_ok!(AccessModule::rotate_role(RuntimeOrigin::root(), roles::DOCTOR, new));
+		assert!(AccessModule::pending_rotation(roles::DOCTOR).is_some());
+		assert_ok!(AccessModule::rotate_role(RuntimeOrigin::root(), roles::DOCTOR, new));
+
+		System::assert_last_event(
+			Event::RoleRotated { old: roles::DOCTOR, new, members_migrated: 4 }.into(),
+		);
+		assert_eq!(RotatedMembers::<Test>::get(roles::DOCTOR), 0);
+		assert!(AccessModule::pending_rotation(roles::DOCTOR).is_none());
+		assert!(!Roles::<Test>::contains_key(roles::DOCTOR));
+		assert!(!AllRoles::<Test>::get().contains(&roles::DOCTOR));
+		assert!(AllRoles::<Test>::get().contains(&new));
+		assert_eq!(
+			AccessModule::role_metadata(new).unwrap().label.into_inner(),
+			b"doctor".to_vec()
+		);
+		for user in 1..=4 {
+			assert_ok!(AccessModule::validate_role(user, new));
+			assert_eq!(AccountRoles::<Test>::get(user).into_inner(), vec![new]);
+		}
+		assert!(AccessModule::validate_role(5, new).is_err());
+	});
+}
+
+#[test]
+fn role_in_rotation_is_not_deleted() {
+	new_test_ext().execute_with(|| {
+		let new = [9u8; 32];
+		for user in 1..=3 {
+			assert_ok!(AccessModule::assign(RuntimeOrigin::root(), user, roles::DOCTOR));
+		}
+		assert_ok!(AccessModule::rotate_role(RuntimeOrigin::root(), roles::DOCTOR, new));
+
+		assert_noop!(
+			AccessModule::delete_role(RuntimeOrigin::root(), roles::DOCTOR),
+			Error::<Test>::RotationInProgress
+		);
+		assert_noop!(
+			AccessModule::delete_role(RuntimeOrigin::root(), new),
+			Error::<Test>::RotationInProgress
+		);
+	});
+}
+
+#[test]
+fn account_roles_follow_assignments_up_to_the_bound() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, roles::DOCTOR));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, roles::COMPLIANCE));
+		assert_eq!(
+			AccessModule::account_roles(1).into_inner(),
+			vec![roles::DOCTOR, roles::COMPLIANCE]
+		);
+
+		assert_ok!(AccessModule::revoke(RuntimeOrigin::root(), 1, roles::DOCTOR));
+		assert_eq!(AccessModule::account_roles(1).into_inner(), vec![roles::COMPLIANCE]);
+
+		for byte in 10..13 {
+			assert_ok!(AccessModule::create_role(RuntimeOrigin::root(), [byte; 32]));
+			assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, [byte; 32]));
+		}
+		assert_noop!(
+			AccessModule::assign(RuntimeOrigin::root(), 1, roles::DOCTOR),
+			Error::<Test>::TooManyAccountRoles
+		);
+	});
+}
+
+#[test]
+fn set_roles_assigns_and_revokes_the_difference() {
+	new_test_ext().execute_with(|| {
+		let (a, b, c) = (roles::DOCTOR, roles::COMPLIANCE, [7u8; 32]);
+		assert_ok!(AccessModule::create_role(RuntimeOrigin::root(), c));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, a));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, b));
+
+		let target = BoundedVec::truncate_from(vec![b, c]);
+		assert_noop!(
+			AccessModule::set_roles(RuntimeOrigin::signed(1), 1, target.clone()),
+			BadOrigin
+		);
+		assert_ok!(AccessModule::set_roles(RuntimeOrigin::root(), 1, target));
+
+		System::assert_has_event(Event::RoleRevoked { user: 1, role: a }.into());
+		System::assert_has_event(Event::RoleAssigned { user: 1, role: c }.into());
+		System::assert_last_event(Event::RolesReplaced { user: 1 }.into());
+		assert_eq!(AccessModule::account_roles(1).into_inner(), vec![b, c]);
+		assert!(AccessModule::validate_role(1, a).is_err());
+		assert_ok!(AccessModule::validate_role(1, b));
+		assert_ok!(AccessModule::validate_role(1, c));
+	});
+}
+
+#[test]
+fn upgrade_indexes_existing_assignments() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<AccessModule>();
+		MemberRoles::<Test>::insert(roles::DOCTOR, 1, true);
+		MemberRoles::<Test>::insert(roles::COMPLIANCE, 1, true);
+		MemberRoles::<Test>::insert(roles::DOCTOR, 2, false);
+
+		AccessModule::on_runtime_upgrade();
+
+		assert_eq!(
+			AccessModule::on_chain_storage_version(),
+			AccessModule::current_storage_version()
+		);
+		assert_eq!(AccountRoles::<Test>::get(1).len(), 2);
+		assert!(AccountRoles::<Test>::get(2).is_empty());
+	});
+}
+
+#[test]
+fn role_exists_follows_creation_and_deletion() {
+	new_test_ext().execute_with(|| {
+		let role = [7u8; 32];
+		assert!(AccessModule::role_exists(roles::DOCTOR));
+		assert!(!AccessModule::role_exists(role));
+
+		assert_ok!(AccessModule::create_role(RuntimeOrigin::root(), role));
+		assert!(AccessModule::role_exists(role));
+
+		assert_ok!(AccessModule::delete_role(RuntimeOrigin::root(), role));
+		assert!(!AccessModule::role_exists(role));
+	});
+}
+
+#[test]
+fn role_administration_follows_admin_origin() {
+	new_test_ext().execute_with(|| {
+		let role = [9u8; 32];
+		let admin = RuntimeOrigin::signed(ADMIN);
+
+		assert_ok!(AccessModule::create_role(admin.clone(), role));
+		assert_ok!(AccessModule::assign(admin.clone(), 2, role));
+		assert_ok!(AccessModule::revoke(admin.clone(), 2, role));
+		assert_ok!(AccessModule::assign_until(admin.clone(), 2, role, 5));
+		assert_ok!(AccessModule::set_expiry_exempt(admin.clone(), role, true));
+		assert_ok!(AccessModule::delete_role(admin, role));
+
+		assert_ok!(AccessModule::create_role(RuntimeOrigin::root(), role));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 2, role));
+		assert_ok!(AccessModule::revoke(RuntimeOrigin::root(), 2, role));
+		assert_ok!(AccessModule::delete_role(RuntimeOrigin::root(), role));
+	});
+}
+
+#[test]
+fn genesis_members_are_recorded_in_storage() {
+	bootstrap_test_ext(vec![(GENESIS_DOCTOR, roles::DOCTOR), (GENESIS_DOCTOR, roles::COMPLIANCE)])
+		.execute_with(|| {
+			assert_eq!(System::block_number(), 0);
+			for role in [roles::DOCTOR, roles::COMPLIANCE] {
+				assert_eq!(MemberRoles::<Test>::get(role, GENESIS_DOCTOR), Some(true));
+				assert_ok!(AccessModule::validate_role(GENESIS_DOCTOR, role));
+			}
+			assert_eq!(
+				AccountRoles::<Test>::get(GENESIS_DOCTOR).into_inner(),
+				vec![roles::DOCTOR, roles::COMPLIANCE]
+			);
+
+			// Events are not recorded at block zero, genesis assignments are only in storage.
+			assert!(System::events().is_empty());
+		});
+}
+
+#[test]
+#[should_panic(expected = "genesis members must hold distinct existing roles")]
+fn genesis_members_need_an_existing_role() {
+	bootstrap_test_ext(vec![(GENESIS_DOCTOR, UNKNOWN)]);
+}
+
+#[test]
+fn exempt_role_outlives_its_expiry_while_others_are_cleaned_up() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AccessModule::set_expiry_exempt(RuntimeOrigin::signed(1), roles::COMPLIANCE, true),
+			BadOrigin
+		);
+		assert_noop!(
+			AccessModule::set_expiry_exempt(RuntimeOrigin::root(), UNKNOWN, true),
+			Error::<Test>::InvalidRole
+		);
+		assert_ok!(AccessModule::set_expiry_exempt(RuntimeOrigin::root(), roles::COMPLIANCE, true));
+		System::assert_last_event(
+			Event::ExpiryExemptSet { role: roles::COMPLIANCE, exempt: true }.into(),
+		);
+
+		assert_noop!(
+			AccessModule::assign_until(RuntimeOrigin::root(), 1, roles::DOCTOR, 1),
+			Error::<Test>::ExpiryInPast
+		);
+		assert_ok!(AccessModule::assign_until(RuntimeOrigin::root(), 1, roles::DOCTOR, 5));
+		System::assert_last_event(
+			Event::RoleAssignedUntil { user: 1, role: roles::DOCTOR, expires_at: 5 }.into(),
+		);
+		assert_ok!(AccessModule::assign_until(RuntimeOrigin::root(), 1, roles::COMPLIANCE, 5));
+
+		System::set_block_number(4);
+		assert_ok!(AccessModule::validate_roles_all(1, &[roles::DOCTOR, roles::COMPLIANCE]));
+		assert_noop!(
+			AccessModule::expire_role(RuntimeOrigin::signed(2), 1, roles::DOCTOR),
+			Error::<Test>::NotExpired
+		);
+
+		// Past the nominal expiry only the exempt role still grants access.
+		System::set_block_number(5);
+		assert_noop!(AccessModule::validate_role(1, roles::DOCTOR), Error::<Test>::AccessDenied);
+		assert_ok!(AccessModule::validate_role(1, roles::COMPLIANCE));
+
+		assert_ok!(AccessModule::expire_role(RuntimeOrigin::signed(2), 1, roles::DOCTOR));
+		System::assert_last_event(Event::RoleExpired { user: 1, role: roles::DOCTOR }.into());
+		assert_noop!(
+			AccessModule::expire_role(RuntimeOrigin::signed(2), 1, roles::COMPLIANCE),
+			Error::<Test>::NotExpired
+		);
+		assert_eq!(AccountRoles::<Test>::get(1).into_inner(), vec![roles::COMPLIANCE]);
+		assert!(RoleExpiry::<Test>::get(roles::DOCTOR, 1).is_none());
+
+		// Lifting the exemption lets the stored expiry apply again.
+		assert_ok!(AccessModule::set_expiry_exempt(
+			RuntimeOrigin::root(),
+			roles::COMPLIANCE,
+			false
+		));
+		assert!(!AccessModule::is_expiry_exempt(roles::COMPLIANCE));
+		assert_ok!(AccessModule::expire_role(RuntimeOrigin::signed(2), 1, roles::COMPLIANCE));
+		assert!(AccountRoles::<Test>::get(1).is_empty());
+	});
+}
+
+#[test]
+fn revoking_a_time_limited_role_drops_its_expiry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccessModule::assign_until(RuntimeOrigin::root(), 1, roles::DOCTOR, 5));
+		assert_ok!(AccessModule::revoke(RuntimeOrigin::root(), 1, roles::DOCTOR));
+		assert!(RoleExpiry::<Test>::get(roles::DOCTOR, 1).is_none());
+
+		// Assigned again without expiry, the role stays past the earlier expiry.
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 1, roles::DOCTOR));
+		System::set_block_number(10);
+		assert_ok!(AccessModule::validate_role(1, roles::DOCTOR));
+		assert_noop!(
+			AccessModule::expire_role(RuntimeOrigin::signed(2), 1, roles::DOCTOR),
+			Error::<Test>::NotAssigned
+		);
+	});
+}
+
+#[test]
+fn role_administration_rejects_other_origins() {
+	new_test_ext().execute_with(|| {
+		let role = [9u8; 32];
+		let outsider = RuntimeOrigin::signed(2);
+
+		assert_noop!(AccessModule::create_role(outsider.clone(), role), BadOrigin);
+		assert_noop!(AccessModule::assign(outsider.clone(), 2, roles::DOCTOR), BadOrigin);
+		assert_noop!(
+			AccessModule::revoke(outsider.clone(), GENESIS_DOCTOR, roles::DOCTOR),
+			BadOrigin
+		);
+		assert_noop!(AccessModule::assign_until(outsider.clone(), 2, roles::DOCTOR, 5), BadOrigin);
+		assert_noop!(AccessModule::delete_role(outsider, roles::DOCTOR), BadOrigin);
+		assert_noop!(AccessModule::delete_role(RuntimeOrigin::none(), roles::DOCTOR), BadOrigin);
+
+		// Holding a role does not make an account an admin.
+		assert_noop!(
+			AccessModule::assign(RuntimeOrigin::signed(GENESIS_DOCTOR), 2, roles::DOCTOR),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn role_changes_are_indexed_by_the_affected_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), 2, roles::DOCTOR));
+
+		let record = System::events().pop().unwrap();
+		assert_eq!(
+			record.event,
+			RuntimeEvent::AccessModule(Event::RoleAssigned { user: 2, role: roles::DOCTOR })
+		);
+		assert_eq!(record.topics, vec![BlakeTwo256::hash_of(&2u64)]);
+
+		RoleEventTopics::set(false);
+		assert_ok!(AccessModule::revoke(RuntimeOrigin::root(), 2, roles::DOCTOR));
+
+		let record = System::events().pop().unwrap();
+		assert_eq!(
+			record.event,
+			RuntimeEvent::AccessModule(Event::RoleRevoked { user: 2, role: roles::DOCTOR })
+		);
+		assert!(record.topics.is_empty());
+	});
+}
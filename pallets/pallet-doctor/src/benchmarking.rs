@@ -0,0 +1,62 @@
+//! Benchmarking setup for pallet-doctor
+//!
+//! The helpers are not dispatchables, so each benchmark calls the helper directly. Lists are filled
+//! up to one below `MaxListLength`, their largest size at which the helpers still succeed.
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as DoctorModule;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::Get;
+
+/// Registers a Doctor and fills their list of the given kind with other Patients.
+fn setup_doctor<T: Config>(list_kind: ListKind) -> T::AccountId {
+	let doctor: T::AccountId = account("doctor", 0, 0);
+
+	DataMap::<T>::insert(
+		&doctor,
+		Doctors::<T> { personal_data_hash: None, specialty: None, license_expiry: None },
+	);
+
+	for i in 1..T::MaxListLength::get() {
+		let other: T::AccountId = account("patient", i, 0);
+
+		match list_kind {
+			ListKind::Request => RequestMap::<T>::try_append(&doctor, other),
+			ListKind::Approved => AprovedRequestMap::<T>::try_append(&doctor, other),
+		}
+		.unwrap();
+	}
+
+	doctor
+}
+
+benchmarks! {
+	add_request {
+		let doctor = setup_doctor::<T>(ListKind::Request);
+		let patient: T::AccountId = account("patient", 0, 0);
+	}: {
+		DoctorModule::<T>::add_request(doctor.clone(), patient.clone())?;
+	}
+	verify {
+		assert!(RequestMap::<T>::get(&doctor).contains(&patient));
+	}
+
+	// The request is taken off a full request list and added to an approved list one below full.
+	add_approved_request {
+		let doctor = setup_doctor::<T>(ListKind::Approved);
+		let patient: T::AccountId = account("patient", 0, 0);
+
+		for i in 1..T::MaxListLength::get() {
+			RequestMap::<T>::try_append(&doctor, account::<T::AccountId>("patient", i, 0)).unwrap();
+		}
+		RequestMap::<T>::try_append(&doctor, patient.clone()).unwrap();
+	}: {
+		DoctorModule::<T>::add_approved_request(patient.clone(), doctor.clone(), Scope::ReadWrite)?;
+	}
+	verify {
+		assert!(AprovedRequestMap::<T>::get(&doctor).contains(&patient));
+		assert!(!RequestMap::<T>::get(&doctor).contains(&patient));
+	}
+}
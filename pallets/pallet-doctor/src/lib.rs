@@ -2,6 +2,18 @@
 
 pub use pallet::*;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::pallet_prelude::*;
@@ -14,22 +26,90 @@ pub mod pallet {
 	#[scale_info(skip_type_params(T))]
 	pub struct Doctors<T: Config> {
 		pub personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		pub specialty: Option<[u8; 8]>,
+		/// Block at which the Doctor's license expires. Expired Doctors can no longer request or
+		/// update Patient data
+		pub license_expiry: Option<T::BlockNumber>,
 	}
 
 	impl<T: Config> Default for Doctors<T> {
 		fn default() -> Self {
-			Doctors { personal_data_hash: None }
+			Doctors { personal_data_hash: None, specialty: None, license_expiry: None }
+		}
+	}
+
+	/// Layout of `Doctors` before the specialty was added, kept for the storage migration
+	#[derive(Decode)]
+	struct DoctorsV0<T: Config> {
+		personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+	}
+
+	/// Layout of `Doctors` before the license expiry was added, kept for the storage migration
+	#[derive(Decode)]
+	struct DoctorsV1<T: Config> {
+		personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		specialty: Option<[u8; 8]>,
+	}
+
+	/// The bounded account lists kept for every Patient and Doctor
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum ListKind {
+		Request,
+		Approved,
+	}
+
+	/// Handler notified when a Doctor's record is removed, with the Patients that had approved
+	/// the Doctor and those the Doctor still had a request pending with, so their side of the
+	/// approvals and requests can be dropped
+	pub trait OnDoctorDeregistered<AccountId> {
+		fn on_deregister(
+			doctor_account_id: &AccountId,
+			approved_patient_ids: &[AccountId],
+			requested_patient_ids: &[AccountId],
+		);
+	}
+
+	impl<AccountId> OnDoctorDeregistered<AccountId> for () {
+		fn on_deregister(
+			_doctor_account_id: &AccountId,
+			_approved_patient_ids: &[AccountId],
+			_requested_patient_ids: &[AccountId],
+		) {
 		}
 	}
 
+	/// Access level granted to an approved Doctor, set by the Patient pallet. The encoded index of
+	/// each variant is fixed, so new variants must take a fresh index and stored scopes keep
+	/// decoding without a migration. Existing indices must never be reused or reordered
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum Scope {
+		#[codec(index = 0)]
+		ReadOnly,
+		#[codec(index = 1)]
+		ReadWrite,
+	}
+
+	impl Default for Scope {
+		fn default() -> Self {
+			Scope::ReadWrite
+		}
+	}
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config + pallet_access::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
+		/// Weights of the helpers other pallets call, see `add_request` and
+		/// `add_approved_request`.
+		type WeightInfo: crate::WeightInfo;
+
 		///Hash length Bound For Bounded Vector
 		#[pallet::constant]
 		type MaxHashLength: Get<u32>;
@@ -37,6 +117,26 @@ pub mod pallet {
 		///Length Bound for Request and Approved Request List Length
 		#[pallet::constant]
 		type MaxListLength: Get<u32>;
+
+		///Whether deregistration has to be confirmed by a holder of `DeregisterApproverRole`
+		#[pallet::constant]
+		type RequireRoleToDeregister: Get<bool>;
+
+		///Role required to confirm a Doctor's deregistration
+		#[pallet::constant]
+		type DeregisterApproverRole: Get<[u8; 32]>;
+
+		///Role required to set a Doctor's license expiry
+		#[pallet::constant]
+		type VerificationAdminRole: Get<[u8; 32]>;
+
+		///Role required to mark a Doctor as verified, e.g. held by a credentialing committee
+		#[pallet::constant]
+		type VerifierRole: Get<[u8; 32]>;
+
+		///Handler called when a Doctor's record is removed, set to `()` to leave the Patient side
+		/// approvals in place
+		type OnDoctorDeregistered: OnDoctorDeregistered<Self::AccountId>;
 	}
 
 	///Storage Map for Storing Doctors Data against Account Id
@@ -45,7 +145,8 @@ pub mod pallet {
 	pub type DataMap<T: Config> =
 		StorageMap<_, Twox64Concat, T::AccountId, Doctors<T>, OptionQuery>;
 
-	///Storage Map for Storing all request made by Doctor Against their Account Id
+	///Storage Map for Storing all request made by Doctor Against their Account Id, in the order
+	/// they were made
 	#[pallet::storage]
 	#[pallet::getter(fn request_list)]
 	pub type RequestMap<T: Config> = StorageMap<
@@ -56,7 +157,8 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
-	///Storage Map for Storing all approved requests for Doctors Against their Account Id
+	///Storage Map for Storing all approved requests for Doctors Against their Account Id, in the
+	/// order they were approved
 	#[pallet::storage]
 	#[pallet::getter(fn approved_request_list)]
 	pub type AprovedRequestMap<T: Config> = StorageMap<
@@ -67,15 +169,78 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	///Storage Map for Storing the scope of each entry in AprovedRequestMap, mirrored from the
+	/// Patient side approval
+	#[pallet::storage]
+	#[pallet::getter(fn approved_scope)]
+	pub type ApprovedScopes<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		Scope,
+		OptionQuery,
+	>;
+
+	///Storage Map for Storing accounts which have been registered as a Doctor at least once
+	#[pallet::storage]
+	#[pallet::getter(fn has_registered_once)]
+	pub type HasRegisteredOnce<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, bool, ValueQuery>;
+
+	///Storage Value for Counting registered Doctors
+	#[pallet::storage]
+	#[pallet::getter(fn doctor_count)]
+	pub type DoctorCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	///Storage Double Map for Storing the Doctors of each specialty code
+	#[pallet::storage]
+	#[pallet::getter(fn specialty_index)]
+	pub type SpecialtyIndex<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, [u8; 8], Twox64Concat, T::AccountId, (), OptionQuery>;
+
+	///Storage Map for Storing Doctors waiting for their deregistration to be confirmed, against
+	/// the block of the request
+	#[pallet::storage]
+	#[pallet::getter(fn pending_deregister)]
+	pub type PendingDeregister<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	///Storage Map for Storing whether a holder of `VerifierRole` verified the Doctor's credentials
+	#[pallet::storage]
+	#[pallet::getter(fn is_verified)]
+	pub type Verified<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, bool, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// When Doctor is Successfully registered or updated.
+		/// When a registered Doctor's data is updated.
 		DoctorDataUpdated { doctor_account_id: T::AccountId },
+		/// When a Doctor is Successfully registered.
+		DoctorRegistered { doctor_account_id: T::AccountId },
 		/// When a Request is Sucessfully added to the RequestQueue
 		RequestQueued { doctor_account_id: T::AccountId, patient_account_id: T::AccountId },
 		/// When a Request is Successfull Approved
 		RequestApproved { doctor_account_id: T::AccountId, patient_account_id: T::AccountId },
+		/// When a Doctor's record is removed
+		DoctorDeregistered { doctor_account_id: T::AccountId },
+		/// When a Doctor asked to be deregistered and awaits confirmation
+		DeregisterRequested { doctor_account_id: T::AccountId },
+		/// When an approver confirmed a Doctor's deregistration
+		DeregisterConfirmed { doctor_account_id: T::AccountId, approver: T::AccountId },
+		/// When a Doctor withdrew their pending deregistration request
+		DeregisterCancelled { doctor_account_id: T::AccountId },
+		/// When a Doctor's list reached `MaxListLength`, so further appends fail
+		ListFull { owner: T::AccountId, list_kind: ListKind },
+		/// When a verification admin set or cleared a Doctor's license expiry
+		LicenseExpirySet { doctor_account_id: T::AccountId, expiry: Option<T::BlockNumber> },
+		/// When a verifier marked a Doctor as verified or withdrew the verification
+		DoctorVerificationChanged {
+			doctor_account_id: T::AccountId,
+			verified: bool,
+			by: T::AccountId,
+		},
 	}
 
 	#[pallet::error]
@@ -86,6 +251,9 @@ pub mod pallet {
 		UnableToUpdate,
 		AlreadyApproved,
 		MaxListLengthReached,
+		NotRegistered,
+		DeregisterPending,
+		NoDeregisterRequest,
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -95,11 +263,15 @@ pub mod pallet {
 		) -> Result<(), DispatchError> {
 			ensure!(!DataMap::<T>::contains_key(&doctor_account_id), Error::<T>::AlreadyRegistered);
 
-			let doctor = Doctors::<T> { personal_data_hash };
+			let doctor = Doctors::<T> { personal_data_hash, specialty: None, license_expiry: None };
 
 			DataMap::<T>::insert(&doctor_account_id, doctor);
 
-			Self::deposit_event(Event::DoctorDataUpdated { doctor_account_id });
+			HasRegisteredOnce::<T>::insert(&doctor_account_id, true);
+
+			DoctorCount::<T>::mutate(|count| *count = count.saturating_add(1));
+
+			Self::deposit_event(Event::DoctorRegistered { doctor_account_id });
 
 			Ok(())
 		}
@@ -108,6 +280,8 @@ pub mod pallet {
 			requester: T::AccountId,
 			patient_account_id: T::AccountId,
 		) -> DispatchResult {
+			ensure!(DataMap::<T>::contains_key(&requester), Error::<T>::NotRegistered);
+
 			// Get the current BoundedVec associated with the requester
 			let patient_ids = RequestMap::<T>::get(&requester);
 
@@ -117,9 +291,7 @@ pub mod pallet {
 				Error::<T>::AlreadyRequested
 			);
 
-			// Append collectible to OwnerOfCollectibles map
-			RequestMap::<T>::try_append(&requester, patient_account_id.clone())
-				.map_err(|_| Error::<T>::MaxListLengthReached)?;
+			Self::bounded_push(&requester, patient_account_id.clone(), ListKind::Request)?;
 
 			Self::deposit_event(Event::RequestQueued {
 				doctor_account_id: requester,
@@ -129,7 +301,36 @@ pub mod pallet {
 			Ok(())
 		}
 
-		fn remove_request(
+		/// Appends `patient_account_id` to the Doctor's list of the given kind and emits `ListFull`
+		/// once the list reaches its bound. Events of a failed call are discarded, so the event
+		/// comes with the append that fills the list rather than with the one that overflows it.
+		fn bounded_push(
+			doctor_account_id: &T::AccountId,
+			patient_account_id: T::AccountId,
+			list_kind: ListKind,
+		) -> Result<(), DispatchError> {
+			let push = |list: &mut BoundedVec<T::AccountId, T::MaxListLength>| {
+				list.try_push(patient_account_id)
+					.map_err(|_| Error::<T>::MaxListLengthReached)?;
+				Ok::<_, DispatchError>(list.len() as u32)
+			};
+
+			let len = match list_kind {
+				ListKind::Request => RequestMap::<T>::try_mutate(doctor_account_id, push),
+				ListKind::Approved => AprovedRequestMap::<T>::try_mutate(doctor_account_id, push),
+			}?;
+
+			if len >= T::MaxListLength::get() {
+				Self::deposit_event(Event::ListFull {
+					owner: doctor_account_id.clone(),
+					list_kind,
+				});
+			}
+
+			Ok(())
+		}
+
+		pub fn remove_request(
 			requester: T::AccountId,
 			patient_account_id: T::AccountId,
 		) -> Result<(), DispatchError> {
@@ -141,7 +342,7 @@ pub mod pallet {
 			);
 
 			if let Some(ind) = patient_ids.iter().position(|id| id == &patient_account_id) {
-				patient_ids.swap_remove(ind);
+				patient_ids.remove(ind);
 			} else {
 				return Err(Error::<T>::NoRequest.into())
 			}
@@ -151,9 +352,30 @@ pub mod pallet {
 			Ok(())
 		}
 
+		pub fn remove_approved_request(
+			doctor_account_id: T::AccountId,
+			patient_account_id: T::AccountId,
+		) -> Result<(), DispatchError> {
+			let mut patient_ids = AprovedRequestMap::<T>::get(&doctor_account_id);
+
+			let ind = patient_ids
+				.iter()
+				.position(|id| id == &patient_account_id)
+				.ok_or(Error::<T>::NoRequest)?;
+
+			patient_ids.remove(ind);
+
+			AprovedRequestMap::<T>::insert(&doctor_account_id, patient_ids);
+
+			ApprovedScopes::<T>::remove(&doctor_account_id, &patient_account_id);
+
+			Ok(())
+		}
+
 		pub fn add_approved_request(
 			patient_account_id: T::AccountId,
 			requester: T::AccountId,
+			scope: Scope,
 		) -> Result<(), DispatchError> {
 			Self::remove_request(requester.clone(), patient_account_id.clone())?;
 
@@ -164,8 +386,9 @@ pub mod pallet {
 				Error::<T>::AlreadyApproved
 			);
 
-			AprovedRequestMap::<T>::try_append(&requester, patient_account_id.clone())
-				.map_err(|_| Error::<T>::MaxListLengthReached)?;
+			Self::bounded_push(&requester, patient_account_id.clone(), ListKind::Approved)?;
+
+			ApprovedScopes::<T>::insert(&requester, &patient_account_id, scope);
 
 			Self::deposit_event(Event::RequestApproved {
 				doctor_account_id: requester,
@@ -174,6 +397,336 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Points the Doctor's list of the given kind at `target` instead of `source`, e.g. when
+		/// two Patient records are merged. If `target` is already listed `source` is only removed.
+		pub fn replace_patient(
+			doctor_account_id: T::AccountId,
+			source: T::AccountId,
+			target: T::AccountId,
+			list_kind: ListKind,
+		) {
+			let replace = |patient_ids: &mut BoundedVec<T::AccountId, T::MaxListLength>| {
+				if patient_ids.contains(&target) {
+					patient_ids.retain(|id| id != &source);
+				} else if let Some(id) = patient_ids.iter_mut().find(|id| **id == source) {
+					*id = target.clone();
+				}
+			};
+
+			match list_kind {
+				ListKind::Request => RequestMap::<T>::mutate(&doctor_account_id, replace),
+				ListKind::Approved => {
+					AprovedRequestMap::<T>::mutate(&doctor_account_id, replace);
+
+					if let Some(scope) = ApprovedScopes::<T>::take(&doctor_account_id, &source) {
+						if !ApprovedScopes::<T>::contains_key(&doctor_account_id, &target) {
+							ApprovedScopes::<T>::insert(&doctor_account_id, &target, scope);
+						}
+					}
+				},
+			}
+		}
+
+		/// Mirrors a changed approval scope onto the Doctor's approved entry.
+		pub fn set_approved_scope(
+			doctor_account_id: T::AccountId,
+			patient_account_id: T::AccountId,
+			scope: Scope,
+		) -> Result<(), DispatchError> {
+			ensure!(
+				AprovedRequestMap::<T>::get(&doctor_account_id).contains(&patient_account_id),
+				Error::<T>::NoRequest
+			);
+
+			ApprovedScopes::<T>::insert(&doctor_account_id, &patient_account_id, scope);
+
+			Ok(())
+		}
+
+		/// Returns the Patients that approved the Doctor together with the scope each granted, in
+		/// the order they approved.
+		pub fn approved_patients_with_scope(
+			doctor_account_id: T::AccountId,
+		) -> Vec<(T::AccountId, Scope)> {
+			AprovedRequestMap::<T>::get(&doctor_account_id)
+				.into_iter()
+				.map(|patient_account_id| {
+					let scope = ApprovedScopes::<T>::get(&doctor_account_id, &patient_account_id)
+						.unwrap_or_default();
+					(patient_account_id, scope)
+				})
+				.collect()
+		}
+
+		/// Returns up to `limit` Doctors whose license expires within the next `within_blocks`
+		/// blocks with their expiry, soonest first. Licenses already expired are left out. Walks
+		/// every Doctor, so it is meant for off-chain queries only.
+		pub fn expiring_doctors(
+			within_blocks: T::BlockNumber,
+			limit: u32,
+		) -> Vec<(T::AccountId, T::BlockNumber)> {
+			let now = frame_system::Pallet::<T>::block_number();
+			let until = now.saturating_add(within_blocks);
+
+			let mut doctors: Vec<(T::AccountId, T::BlockNumber)> = DataMap::<T>::iter()
+				.filter_map(|(doctor_account_id, doctor)| {
+					doctor
+						.license_expiry
+						.filter(|expiry| *expiry > now && *expiry <= until)
+						.map(|expiry| (doctor_account_id, expiry))
+				})
+				.collect();
+
+			doctors.sort_by_key(|(_, expiry)| *expiry);
+			doctors.truncate(limit as usize);
+
+			doctors
+		}
+
+		/// Checks that no Patient is listed twice for a Doctor, that a Patient is never both
+		/// requested and approved by the same Doctor and that every stored scope decodes.
+		#[cfg(feature = "try-runtime")]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			for (doctor_account_id, patient_ids) in RequestMap::<T>::iter() {
+				let approved_patient_ids = AprovedRequestMap::<T>::get(&doctor_account_id);
+
+				for (ind, patient_account_id) in patient_ids.iter().enumerate() {
+					ensure!(
+						!patient_ids[..ind].contains(patient_account_id),
+						"Doctor RequestMap lists the same patient twice"
+					);
+					ensure!(
+						!approved_patient_ids.contains(patient_account_id),
+						"Doctor RequestMap lists a patient that is already approved"
+					);
+				}
+			}
+
+			for (_, patient_ids) in AprovedRequestMap::<T>::iter() {
+				for (ind, patient_account_id) in patient_ids.iter().enumerate() {
+					ensure!(
+						!patient_ids[..ind].contains(patient_account_id),
+						"Doctor AprovedRequestMap lists the same patient twice"
+					);
+				}
+			}
+
+			// Entries failing to decode are skipped by `iter`, e.g. a scope of an unknown variant.
+			ensure!(
+				ApprovedScopes::<T>::iter_keys().count() == ApprovedScopes::<T>::iter().count(),
+				"ApprovedScopes holds a scope of an unknown variant"
+			);
+
+			Ok(())
+		}
+
+		fn deregister_self(doctor_account_id: T::AccountId) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&doctor_account_id), Error::<T>::NotRegistered);
+
+			if T::RequireRoleToDeregister::get() {
+				ensure!(
+					!PendingDeregister::<T>::contains_key(&doctor_account_id),
+					Error::<T>::DeregisterPending
+				);
+
+				PendingDeregister::<T>::insert(
+					&doctor_account_id,
+					frame_system::Pallet::<T>::block_number(),
+				);
+
+				Self::deposit_event(Event::DeregisterRequested { doctor_account_id });
+
+				return Ok(())
+			}
+
+			Self::remove_doctor(doctor_account_id);
+
+			Ok(())
+		}
+
+		fn confirm(
+			approver: T::AccountId,
+			doctor_account_id: T::AccountId,
+		) -> Result<(), DispatchError> {
+			pallet_access::Pallet::<T>::validate_role(
+				approver.clone(),
+				T::DeregisterApproverRole::get(),
+			)?;
+
+			PendingDeregister::<T>::take(&doctor_account_id)
+				.ok_or(Error::<T>::NoDeregisterRequest)?;
+
+			Self::deposit_event(Event::DeregisterConfirmed {
+				doctor_account_id: doctor_account_id.clone(),
+				approver,
+			});
+
+			Self::remove_doctor(doctor_account_id);
+
+			Ok(())
+		}
+
+		fn cancel(doctor_account_id: T::AccountId) -> Result<(), DispatchError> {
+			PendingDeregister::<T>::take(&doctor_account_id)
+				.ok_or(Error::<T>::NoDeregisterRequest)?;
+
+			Self::deposit_event(Event::DeregisterCancelled { doctor_account_id });
+
+			Ok(())
+		}
+
+		fn remove_doctor(doctor_account_id: T::AccountId) {
+			let doctor = DataMap::<T>::take(&doctor_account_id);
+
+			if let Some(specialty) = doctor.and_then(|doctor| doctor.specialty) {
+				SpecialtyIndex::<T>::remove(specialty, &doctor_account_id);
+			}
+
+			// A request filed before the record was removed must not be confirmable later.
+			PendingDeregister::<T>::remove(&doctor_account_id);
+
+			// A Doctor registering again has to be verified again.
+			Verified::<T>::remove(&doctor_account_id);
+
+			DoctorCount::<T>::mutate(|count| {
+				debug_assert!(*count > 0, "DoctorCount decremented below zero");
+				*count = count.saturating_sub(1);
+			});
+
+			let approved_patient_ids = AprovedRequestMap::<T>::take(&doctor_account_id);
+			let requested_patient_ids = RequestMap::<T>::take(&doctor_account_id);
+			let _ = ApprovedScopes::<T>::clear_prefix(
+				&doctor_account_id,
+				T::MaxListLength::get(),
+				None,
+			);
+
+			T::OnDoctorDeregistered::on_deregister(
+				&doctor_account_id,
+				&approved_patient_ids,
+				&requested_patient_ids,
+			);
+
+			Self::deposit_event(Event::DoctorDeregistered { doctor_account_id });
+		}
+
+		/// Returns whether the Doctor's license expiry has been reached. Doctors without an expiry
+		/// are never expired.
+		pub fn license_expired(doctor_account_id: &T::AccountId) -> bool {
+			DataMap::<T>::get(doctor_account_id)
+				.and_then(|doctor| doctor.license_expiry)
+				.map_or(false, |expiry| frame_system::Pallet::<T>::block_number() >= expiry)
+		}
+
+		fn set_expiry(
+			admin: T::AccountId,
+			doctor_account_id: T::AccountId,
+			expiry: Option<T::BlockNumber>,
+		) -> Result<(), DispatchError> {
+			pallet_access::Pallet::<T>::validate_role(admin, T::VerificationAdminRole::get())?;
+
+			let mut doctor =
+				DataMap::<T>::get(&doctor_account_id).ok_or(Error::<T>::NotRegistered)?;
+
+			doctor.license_expiry = expiry;
+
+			DataMap::<T>::insert(&doctor_account_id, doctor);
+
+			Self::deposit_event(Event::LicenseExpirySet { doctor_account_id, expiry });
+
+			Ok(())
+		}
+
+		fn set_verification(
+			verifier: T::AccountId,
+			doctor_account_id: T::AccountId,
+			verified: bool,
+		) -> Result<(), DispatchError> {
+			pallet_access::Pallet::<T>::validate_role(verifier.clone(), T::VerifierRole::get())?;
+
+			ensure!(DataMap::<T>::contains_key(&doctor_account_id), Error::<T>::NotRegistered);
+
+			if verified {
+				Verified::<T>::insert(&doctor_account_id, true);
+			} else {
+				Verified::<T>::remove(&doctor_account_id);
+			}
+
+			Self::deposit_event(Event::DoctorVerificationChanged {
+				doctor_account_id,
+				verified,
+				by: verifier,
+			});
+
+			Ok(())
+		}
+
+		fn update_specialty(
+			doctor_account_id: T::AccountId,
+			specialty: Option<[u8; 8]>,
+		) -> Result<(), DispatchError> {
+			let mut doctor =
+				DataMap::<T>::get(&doctor_account_id).ok_or(Error::<T>::NotRegistered)?;
+
+			if let Some(old) = doctor.specialty {
+				SpecialtyIndex::<T>::remove(old, &doctor_account_id);
+			}
+
+			if let Some(new) = specialty {
+				SpecialtyIndex::<T>::insert(new, &doctor_account_id, ());
+			}
+
+			doctor.specialty = specialty;
+
+			DataMap::<T>::insert(&doctor_account_id, doctor);
+
+			Self::deposit_event(Event::DoctorDataUpdated { doctor_account_id });
+
+			Ok(())
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain = StorageVersion::get::<Pallet<T>>();
+
+			if on_chain >= 2 {
+				return Weight::zero()
+			}
+
+			let mut translated = 0u64;
+
+			if on_chain < 1 {
+				DataMap::<T>::translate::<DoctorsV0<T>, _>(|_, old| {
+					translated += 1;
+					Some(Doctors {
+						personal_data_hash: old.personal_data_hash,
+						specialty: None,
+						license_expiry: None,
+					})
+				});
+			} else {
+				DataMap::<T>::translate::<DoctorsV1<T>, _>(|_, old| {
+					translated += 1;
+					Some(Doctors {
+						personal_data_hash: old.personal_data_hash,
+						specialty: old.specialty,
+						license_expiry: None,
+					})
+				});
+			}
+
+			STORAGE_VERSION.put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: T::BlockNumber) -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
 	}
 
 	#[pallet::call]
@@ -183,11 +736,96 @@ pub mod pallet {
 		pub fn register(
 			origin: OriginFor<T>,
 			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-		) -> DispatchResult {
+		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 
+			// The first registration of an account is free of charge.
+			let first_registration = !HasRegisteredOnce::<T>::get(&sender);
+
 			Self::register_self(sender, personal_data_hash)?;
 
+			if first_registration {
+				return Ok(Pays::No.into())
+			}
+
+			Ok(().into())
+		}
+
+		/// Removes the caller's Doctor record. When `RequireRoleToDeregister` is set this only
+		/// files the request, which a holder of `DeregisterApproverRole` has to confirm.
+		#[pallet::weight(0)]
+		#[pallet::call_index(2)]
+		pub fn deregister(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::deregister_self(sender)?;
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		#[pallet::call_index(3)]
+		pub fn confirm_deregister(
+			origin: OriginFor<T>,
+			doctor_account_id: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::confirm(sender, doctor_account_id)?;
+
+			Ok(())
+		}
+
+		/// Withdraws the caller's pending deregistration request.
+		#[pallet::weight(0)]
+		#[pallet::call_index(4)]
+		pub fn cancel_deregister(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::cancel(sender)?;
+
+			Ok(())
+		}
+
+		/// Sets or clears the Doctor's specialty code, keeping `SpecialtyIndex` in sync.
+		#[pallet::weight(0)]
+		#[pallet::call_index(5)]
+		pub fn set_specialty(origin: OriginFor<T>, specialty: Option<[u8; 8]>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::update_specialty(sender, specialty)?;
+
+			Ok(())
+		}
+
+		/// Sets or clears the block at which a Doctor's license expires. Requires
+		/// `VerificationAdminRole`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(6)]
+		pub fn set_license_expiry(
+			origin: OriginFor<T>,
+			doctor_account_id: T::AccountId,
+			expiry: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::set_expiry(sender, doctor_account_id, expiry)?;
+
+			Ok(())
+		}
+
+		/// Marks a Doctor as verified or withdraws the verification. Requires `VerifierRole`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(7)]
+		pub fn set_verified(
+			origin: OriginFor<T>,
+			doctor_account_id: T::AccountId,
+			verified: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::set_verification(sender, doctor_account_id, verified)?;
+
 			Ok(())
 		}
 	}
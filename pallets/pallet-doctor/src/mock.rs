@@ -0,0 +1,115 @@
+use crate as pallet_doctor;
+use frame_support::{
+	assert_ok, parameter_types,
+	traits::{ConstBool, ConstU16, ConstU32, ConstU64},
+};
+use pallet_access::roles;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+/// Account holding `roles::PRIVACY_ADMIN`, which approves deregistrations in the mock.
+pub const PRIVACY_ADMIN: u64 = 10;
+
+/// Account holding `roles::COMPLIANCE`, which verifies Doctors in the mock.
+pub const VERIFIER: u64 = 13;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		AccessModule: pallet_access,
+		DoctorModule: pallet_doctor,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_access::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxMetadataLength = ConstU32<32>;
+	type MaxRoles = ConstU32<8>;
+	type MaxRotationBatch = ConstU32<8>;
+	type MaxRolesPerAccount = ConstU32<4>;
+	type AdminOrigin = frame_system::EnsureRoot<u64>;
+	type RoleEventTopics = ConstBool<false>;
+}
+
+parameter_types! {
+	pub const AdminRole: [u8; 32] = roles::PRIVACY_ADMIN;
+	pub const VerifierRole: [u8; 32] = roles::COMPLIANCE;
+	pub static RequireRoleToDeregister: bool = false;
+}
+
+impl pallet_doctor::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type MaxHashLength = ConstU32<64>;
+	type MaxListLength = ConstU32<4>;
+	type RequireRoleToDeregister = RequireRoleToDeregister;
+	type DeregisterApproverRole = AdminRole;
+	type VerificationAdminRole = AdminRole;
+	type VerifierRole = VerifierRole;
+	type OnDoctorDeregistered = ();
+}
+
+/// Builds genesis storage with `roles::PRIVACY_ADMIN` held by `PRIVACY_ADMIN` and
+/// `roles::COMPLIANCE` held by `VERIFIER`.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = GenesisConfig {
+		system: Default::default(),
+		access_module: pallet_access::GenesisConfig {
+			roles: vec![roles::PRIVACY_ADMIN, roles::COMPLIANCE],
+			members: vec![],
+		},
+	}
+	.build_storage()
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(AccessModule::assign(
+			RuntimeOrigin::root(),
+			PRIVACY_ADMIN,
+			roles::PRIVACY_ADMIN
+		));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), VERIFIER, roles::COMPLIANCE));
+	});
+	ext
+}
@@ -0,0 +1,374 @@
+use crate::{
+	mock::*, ApprovedScopes, DataMap, DoctorCount, Error, Event, ListKind, PendingDeregister,
+	RequestMap, Scope, SpecialtyIndex, Verified,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::unhashed,
+	traits::{GetStorageVersion, Hooks, StorageVersion},
+	BoundedVec,
+};
+
+fn last_event() -> Event<Test> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::DoctorModule(event) => Some(event),
+			_ => None,
+		})
+		.last()
+		.unwrap()
+}
+
+#[test]
+fn registration_and_edits_emit_distinct_events() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+		assert_eq!(last_event(), Event::DoctorRegistered { doctor_account_id: 1 });
+
+		assert_ok!(DoctorModule::set_specialty(RuntimeOrigin::signed(1), Some(*b"cardiolo")));
+		assert_eq!(last_event(), Event::DoctorDataUpdated { doctor_account_id: 1 });
+	});
+}
+
+#[test]
+fn doctor_count_follows_registrations() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(2), None));
+		assert_eq!(DoctorCount::<Test>::get(), 2);
+
+		assert_ok!(DoctorModule::deregister(RuntimeOrigin::signed(1)));
+		assert_eq!(DoctorCount::<Test>::get(), 1);
+		assert!(!DataMap::<Test>::contains_key(1));
+	});
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "DoctorCount decremented below zero"))]
+fn doctor_count_stays_at_zero() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+
+		// Lets the counter fall behind the registration.
+		DoctorCount::<Test>::kill();
+
+		assert_ok!(DoctorModule::deregister(RuntimeOrigin::signed(1)));
+
+		assert_eq!(DoctorCount::<Test>::get(), 0);
+	});
+}
+
+#[test]
+fn ungated_deregistration_removes_the_record_at_once() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			DoctorModule::deregister(RuntimeOrigin::signed(1)),
+			Error::<Test>::NotRegistered
+		);
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+
+		assert_ok!(DoctorModule::deregister(RuntimeOrigin::signed(1)));
+
+		assert!(!DataMap::<Test>::contains_key(1));
+		assert!(!PendingDeregister::<Test>::contains_key(1));
+		assert_eq!(last_event(), Event::DoctorDeregistered { doctor_account_id: 1 });
+	});
+}
+
+#[test]
+fn gated_deregistration_waits_for_the_approver() {
+	new_test_ext().execute_with(|| {
+		RequireRoleToDeregister::set(true);
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+
+		assert_ok!(DoctorModule::deregister(RuntimeOrigin::signed(1)));
+		assert_eq!(last_event(), Event::DeregisterRequested { doctor_account_id: 1 });
+		assert!(DataMap::<Test>::contains_key(1));
+		assert_noop!(
+			DoctorModule::deregister(RuntimeOrigin::signed(1)),
+			Error::<Test>::DeregisterPending
+		);
+
+		assert_noop!(
+			DoctorModule::confirm_deregister(RuntimeOrigin::signed(2), 1),
+			pallet_access::Error::<Test>::NotAssigned
+		);
+		assert_ok!(DoctorModule::confirm_deregister(RuntimeOrigin::signed(PRIVACY_ADMIN), 1));
+
+		assert!(!DataMap::<Test>::contains_key(1));
+		assert!(!PendingDeregister::<Test>::contains_key(1));
+		assert_eq!(last_event(), Event::DoctorDeregistered { doctor_account_id: 1 });
+	});
+}
+
+#[test]
+fn pending_deregistration_can_be_cancelled() {
+	new_test_ext().execute_with(|| {
+		RequireRoleToDeregister::set(true);
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+		assert_noop!(
+			DoctorModule::cancel_deregister(RuntimeOrigin::signed(1)),
+			Error::<Test>::NoDeregisterRequest
+		);
+
+		assert_ok!(DoctorModule::deregister(RuntimeOrigin::signed(1)));
+		assert_ok!(DoctorModule::cancel_deregister(RuntimeOrigin::signed(1)));
+
+		assert_eq!(last_event(), Event::DeregisterCancelled { doctor_account_id: 1 });
+		assert!(!PendingDeregister::<Test>::contains_key(1));
+		assert!(DataMap::<Test>::contains_key(1));
+		assert_noop!(
+			DoctorModule::confirm_deregister(RuntimeOrigin::signed(PRIVACY_ADMIN), 1),
+			Error::<Test>::NoDeregisterRequest
+		);
+	});
+}
+
+#[test]
+fn filling_a_list_reports_which_list_is_full() {
+	new_test_ext().execute_with(|| {
+		for patient in 11..=14 {
+			assert_ok!(DoctorModule::add_request(1, patient));
+		}
+		System::assert_has_event(Event::ListFull { owner: 1, list_kind: ListKind::Request }.into());
+		assert_noop!(DoctorModule::add_request(1, 15), Error::<Test>::MaxListLengthReached);
+
+		for patient in 11..=14 {
+			assert_ok!(DoctorModule::add_approved_request(patient, 1, Scope::ReadWrite));
+		}
+		System::assert_has_event(
+			Event::ListFull { owner: 1, list_kind: ListKind::Approved }.into(),
+		);
+	});
+}
+
+#[test]
+fn requests_of_unregistered_accounts_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(DoctorModule::add_request(1, 2), Error::<Test>::NotRegistered);
+		assert!(RequestMap::<Test>::get(1).is_empty());
+
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+		assert_ok!(DoctorModule::add_request(1, 2));
+		assert_eq!(RequestMap::<Test>::get(1).into_inner(), vec![2]);
+	});
+}
+
+#[test]
+fn set_specialty_moves_the_doctor_between_buckets() {
+	new_test_ext().execute_with(|| {
+		let (cardiology, neurology) = (*b"cardiolo", *b"neurolog");
+		assert_noop!(
+			DoctorModule::set_specialty(RuntimeOrigin::signed(1), Some(cardiology)),
+			Error::<Test>::NotRegistered
+		);
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+
+		assert_ok!(DoctorModule::set_specialty(RuntimeOrigin::signed(1), Some(cardiology)));
+		assert_eq!(last_event(), Event::DoctorDataUpdated { doctor_account_id: 1 });
+		assert!(SpecialtyIndex::<Test>::contains_key(cardiology, 1));
+
+		assert_ok!(DoctorModule::set_specialty(RuntimeOrigin::signed(1), Some(neurology)));
+		assert!(!SpecialtyIndex::<Test>::contains_key(cardiology, 1));
+		assert!(SpecialtyIndex::<Test>::contains_key(neurology, 1));
+		assert_eq!(DataMap::<Test>::get(1).unwrap().specialty, Some(neurology));
+
+		assert_ok!(DoctorModule::set_specialty(RuntimeOrigin::signed(1), None));
+		assert!(SpecialtyIndex::<Test>::iter_prefix(neurology).next().is_none());
+		assert_eq!(DataMap::<Test>::get(1).unwrap().specialty, None);
+	});
+}
+
+#[test]
+fn deregistration_leaves_the_specialty_bucket() {
+	new_test_ext().execute_with(|| {
+		let cardiology = *b"cardiolo";
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+		assert_ok!(DoctorModule::set_specialty(RuntimeOrigin::signed(1), Some(cardiology)));
+
+		assert_ok!(DoctorModule::deregister(RuntimeOrigin::signed(1)));
+
+		assert!(!SpecialtyIndex::<Test>::contains_key(cardiology, 1));
+	});
+}
+
+#[test]
+fn upgrade_adds_an_empty_specialty_to_old_records() {
+	new_test_ext().execute_with(|| {
+		let personal_data_hash: Option<BoundedVec<u8, <Test as crate::Config>::MaxHashLength>> =
+			Some(BoundedVec::truncate_from(vec![7u8; 4]));
+		unhashed::put_raw(&DataMap::<Test>::hashed_key_for(1), &personal_data_hash.encode());
+		StorageVersion::new(0).put::<DoctorModule>();
+
+		DoctorModule::on_runtime_upgrade();
+
+		let doctor = DataMap::<Test>::get(1).unwrap();
+		assert_eq!(doctor.personal_data_hash, Some(BoundedVec::truncate_from(vec![7u8; 4])));
+		assert_eq!(doctor.specialty, None);
+		assert_eq!(doctor.license_expiry, None);
+		assert_eq!(DoctorModule::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn upgrade_adds_an_empty_license_expiry_to_specialty_records() {
+	new_test_ext().execute_with(|| {
+		let old_record = (None::<Vec<u8>>, Some(*b"cardiol_")).encode();
+		unhashed::put_raw(&DataMap::<Test>::hashed_key_for(1), &old_record);
+		StorageVersion::new(1).put::<DoctorModule>();
+
+		DoctorModule::on_runtime_upgrade();
+
+		let doctor = DataMap::<Test>::get(1).unwrap();
+		assert_eq!(doctor.specialty, Some(*b"cardiol_"));
+		assert_eq!(doctor.license_expiry, None);
+		assert_eq!(DoctorModule::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn expiring_doctors_lists_the_soonest_expiries_in_the_window() {
+	new_test_ext().execute_with(|| {
+		for (doctor, expiry) in [(1, Some(5)), (2, Some(20)), (3, Some(8)), (4, None)] {
+			assert_ok!(DoctorModule::register(RuntimeOrigin::signed(doctor), None));
+			DataMap::<Test>::mutate(doctor, |record| {
+				record.as_mut().unwrap().license_expiry = expiry;
+			});
+		}
+
+		assert_eq!(DoctorModule::expiring_doctors(10, 10), vec![(1, 5), (3, 8)]);
+		assert_eq!(DoctorModule::expiring_doctors(30, 10), vec![(1, 5), (3, 8), (2, 20)]);
+		assert_eq!(DoctorModule::expiring_doctors(30, 2), vec![(1, 5), (3, 8)]);
+		assert!(DoctorModule::expiring_doctors(3, 10).is_empty());
+
+		// Expired licenses are no longer listed.
+		System::set_block_number(5);
+		assert_eq!(DoctorModule::expiring_doctors(10, 10), vec![(3, 8)]);
+	});
+}
+
+#[test]
+fn verification_admin_sets_the_license_expiry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+
+		assert_noop!(
+			DoctorModule::set_license_expiry(RuntimeOrigin::signed(2), 1, Some(5)),
+			pallet_access::Error::<Test>::NotAssigned
+		);
+		assert_noop!(
+			DoctorModule::set_license_expiry(RuntimeOrigin::signed(PRIVACY_ADMIN), 2, Some(5)),
+			Error::<Test>::NotRegistered
+		);
+
+		assert_ok!(DoctorModule::set_license_expiry(
+			RuntimeOrigin::signed(PRIVACY_ADMIN),
+			1,
+			Some(5)
+		));
+		assert_eq!(last_event(), Event::LicenseExpirySet { doctor_account_id: 1, expiry: Some(5) });
+		assert_eq!(DataMap::<Test>::get(1).unwrap().license_expiry, Some(5));
+		assert!(!DoctorModule::license_expired(&1));
+
+		System::set_block_number(5);
+		assert!(DoctorModule::license_expired(&1));
+
+		assert_ok!(DoctorModule::set_license_expiry(RuntimeOrigin::signed(PRIVACY_ADMIN), 1, None));
+		assert!(!DoctorModule::license_expired(&1));
+	});
+}
+
+#[test]
+fn only_verifiers_change_the_verification() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+
+		assert_noop!(
+			DoctorModule::set_verified(RuntimeOrigin::signed(PRIVACY_ADMIN), 1, true),
+			pallet_access::Error::<Test>::NotAssigned
+		);
+		assert_noop!(
+			DoctorModule::set_verified(RuntimeOrigin::signed(VERIFIER), 2, true),
+			Error::<Test>::NotRegistered
+		);
+
+		assert_ok!(DoctorModule::set_verified(RuntimeOrigin::signed(VERIFIER), 1, true));
+		assert_eq!(
+			last_event(),
+			Event::DoctorVerificationChanged { doctor_account_id: 1, verified: true, by: VERIFIER }
+		);
+		assert!(DoctorModule::is_verified(1));
+
+		assert_ok!(DoctorModule::set_verified(RuntimeOrigin::signed(VERIFIER), 1, false));
+		assert!(!Verified::<Test>::contains_key(1));
+	});
+}
+
+#[test]
+fn deregistration_drops_the_verification() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+		assert_ok!(DoctorModule::set_verified(RuntimeOrigin::signed(VERIFIER), 1, true));
+
+		assert_ok!(DoctorModule::deregister(RuntimeOrigin::signed(1)));
+		assert_ok!(DoctorModule::register(RuntimeOrigin::signed(1), None));
+
+		assert!(!DoctorModule::is_verified(1));
+	});
+}
+
+#[test]
+fn scope_encoding_is_pinned() {
+	assert_eq!(Scope::ReadOnly.encode(), vec![0u8]);
+	assert_eq!(Scope::ReadWrite.encode(), vec![1u8]);
+
+	assert_eq!(Scope::decode(&mut &[0u8][..]).ok(), Some(Scope::ReadOnly));
+	assert_eq!(Scope::decode(&mut &[1u8][..]).ok(), Some(Scope::ReadWrite));
+	assert!(Scope::decode(&mut &[2u8][..]).is_err());
+}
+
+/// `Scope` as it would look with a variant added at a fresh index.
+#[derive(Encode, Decode, PartialEq, Debug)]
+enum ExtendedScope {
+	#[codec(index = 0)]
+	ReadOnly,
+	#[codec(index = 1)]
+	ReadWrite,
+	#[codec(index = 2)]
+	Append,
+}
+
+#[test]
+fn stored_scopes_decode_after_a_variant_is_added() {
+	new_test_ext().execute_with(|| {
+		ApprovedScopes::<Test>::insert(1, 2, Scope::ReadOnly);
+		ApprovedScopes::<Test>::insert(1, 3, Scope::ReadWrite);
+
+		let stored = |patient: u64| {
+			let raw = unhashed::get_raw(&ApprovedScopes::<Test>::hashed_key_for(1, patient));
+			ExtendedScope::decode(&mut &raw.unwrap()[..]).unwrap()
+		};
+
+		assert_eq!(stored(2), ExtendedScope::ReadOnly);
+		assert_eq!(stored(3), ExtendedScope::ReadWrite);
+		assert_eq!(Scope::decode(&mut &ExtendedScope::Append.encode()[..]).ok(), None);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_rejects_a_scope_of_an_unknown_variant() {
+	new_test_ext().execute_with(|| {
+		ApprovedScopes::<Test>::insert(1, 2, Scope::ReadOnly);
+		assert_ok!(DoctorModule::do_try_state());
+
+		unhashed::put_raw(&ApprovedScopes::<Test>::hashed_key_for(1, 3), &[2u8]);
+
+		assert_eq!(
+			DoctorModule::do_try_state(),
+			Err("ApprovedScopes holds a scope of an unknown variant")
+		);
+	});
+}
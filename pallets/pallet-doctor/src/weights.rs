@@ -0,0 +1,39 @@
+//! Weights of the Doctor pallet helpers called by other pallets.
+//!
+//! The helpers are not dispatchables. Their benchmarks call them directly, and pallets calling
+//! them add these weights to their own. Until the benchmarks are run on reference hardware the
+//! weights below count the storage accesses of each helper, with lists at their largest size.
+
+use core::marker::PhantomData;
+use frame_support::{traits::Get, weights::Weight};
+
+/// Weight functions needed by the Doctor pallet helpers.
+pub trait WeightInfo {
+	fn add_request() -> Weight;
+	fn add_approved_request() -> Weight;
+}
+
+/// Weights derived from the storage accesses of each helper.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: DoctorModule DataMap (r:1 w:0)
+	// Storage: DoctorModule RequestMap (r:1 w:1)
+	fn add_request() -> Weight {
+		T::DbWeight::get().reads_writes(2, 1)
+	}
+	// Storage: DoctorModule RequestMap (r:1 w:1)
+	// Storage: DoctorModule AprovedRequestMap (r:1 w:1)
+	// Storage: DoctorModule ApprovedScopes (r:0 w:1)
+	fn add_approved_request() -> Weight {
+		T::DbWeight::get().reads_writes(2, 3)
+	}
+}
+
+impl WeightInfo for () {
+	fn add_request() -> Weight {
+		Weight::zero()
+	}
+	fn add_approved_request() -> Weight {
+		Weight::zero()
+	}
+}
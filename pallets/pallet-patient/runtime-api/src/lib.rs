@@ -0,0 +1,89 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_patient::{DoctorLoad, EventKind, ProtocolStats, Scope};
+
+sp_api::decl_runtime_apis! {
+	pub trait PatientApi<AccountId, BlockNumber, PatientExport, Hash>
+	where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		PatientExport: Codec,
+		Hash: Codec,
+	{
+		/// Returns the most recent patient affecting events, oldest first.
+		fn recent_events(patient_account_id: AccountId) -> Vec<(BlockNumber, EventKind)>;
+
+		/// Returns the patients the doctor currently holds active read-write access to.
+		fn writable_patients(doctor_account_id: AccountId) -> Vec<AccountId>;
+
+		/// Returns a snapshot of the patient's full state for off-chain backup.
+		fn export_patient(patient_account_id: AccountId) -> Option<PatientExport>;
+
+		/// Returns for each account whether it is a registered patient, or `None` if more than
+		/// `MaxBatch` accounts are given.
+		fn which_registered(accounts: Vec<AccountId>) -> Option<Vec<bool>>;
+
+		/// Returns whether the patient's data changed since they last acknowledged it.
+		fn has_unacknowledged(patient_account_id: AccountId) -> bool;
+
+		/// Returns the scope and expiry of the doctor's approval, if the patient approved them.
+		fn approval_details(
+			patient_account_id: AccountId,
+			doctor_account_id: AccountId,
+		) -> Option<(Scope, Option<BlockNumber>)>;
+
+		/// Returns the doctor's pending, approved and currently writable patient counts.
+		fn doctor_load(doctor_account_id: AccountId) -> DoctorLoad;
+
+		/// Returns every patient the account may access through approvals or care teams, without
+		/// duplicates.
+		fn accessible_patients(account: AccountId) -> Vec<AccountId>;
+
+		/// Returns whether the account may read the patient's record.
+		fn is_approved(patient_account_id: AccountId, account: AccountId) -> bool;
+
+		/// Returns whether the doctor's request to the patient is still queued.
+		fn is_request_pending(patient_account_id: AccountId, doctor_account_id: AccountId) -> bool;
+
+		/// Returns a page of at most `len` care team patients starting at `start`.
+		fn care_team_patients_page(team_id: u32, start: u32, len: u32) -> Vec<AccountId>;
+
+		/// Returns the number of patients covered by the care team.
+		fn care_team_size(team_id: u32) -> u32;
+
+		/// Returns whether the patient's data was written after `block`.
+		fn has_changed_since(patient_account_id: AccountId, block: BlockNumber) -> bool;
+
+		/// Returns the data hash replaced by the patient's latest change of it.
+		fn previous_hash(patient_account_id: AccountId) -> Option<Vec<u8>>;
+
+		/// Returns the (patient, doctor) approval pairs of the given patients, reading at most
+		/// `MaxBatch` patients. Meant for RPC queries only.
+		fn approval_edges(patients: Vec<AccountId>) -> Vec<(AccountId, AccountId)>;
+
+		/// Returns up to `limit` doctors whose license expires within `within_blocks` blocks,
+		/// soonest first. Meant for RPC queries only.
+		fn expiring_doctors(within_blocks: BlockNumber, limit: u32) -> Vec<(AccountId, BlockNumber)>;
+
+		/// Returns a hash of the patient's approved doctors and their approval terms, which
+		/// changes whenever an approval is granted, revoked or changed.
+		fn consent_fingerprint(patient_account_id: AccountId) -> Hash;
+
+		/// Returns the patient's request sequence number, which changes whenever a request is
+		/// added or removed.
+		fn request_seq(patient_account_id: AccountId) -> u32;
+
+		/// Returns the doctors the patient approved with the given scope.
+		fn approved_by_scope(patient_account_id: AccountId, scope: Scope) -> Vec<AccountId>;
+
+		/// Returns the protocol wide totals of patients, doctors, roles, active approvals and
+		/// pending requests.
+		fn protocol_stats() -> ProtocolStats;
+
+		/// Returns the doctors the patient approved with each doctor's personal data hash.
+		fn approved_doctor_profiles(patient_account_id: AccountId) -> Vec<(AccountId, Option<Vec<u8>>)>;
+	}
+}
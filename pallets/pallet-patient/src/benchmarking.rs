@@ -0,0 +1,66 @@
+//! Benchmarking setup for pallet-patient
+//!
+//! Both calls are benchmarked on their most expensive path: the Patient's request list is one entry
+//! short of full and the approval is recorded on both sides in the same call.
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as PatientModule;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::Get;
+use frame_system::RawOrigin;
+
+/// Registers a Patient and a Doctor holding every data access role.
+fn setup_accounts<T: Config>() -> (T::AccountId, T::AccountId) {
+	let patient: T::AccountId = account("patient", 0, 0);
+	let doctor: T::AccountId = account("doctor", 0, 0);
+
+	DataMap::<T>::insert(&patient, Patients::<T>::default());
+	pallet_doctor::DataMap::<T>::insert(
+		&doctor,
+		pallet_doctor::Doctors::<T> {
+			personal_data_hash: None,
+			specialty: None,
+			license_expiry: None,
+		},
+	);
+
+	for role in T::DataAccessRoles::get() {
+		// Roles created at genesis already exist.
+		let _ = pallet_access::Pallet::<T>::create_role(RawOrigin::Root.into(), role);
+		pallet_access::Pallet::<T>::assign_role(doctor.clone(), role).unwrap();
+	}
+
+	// Other Doctors' requests leave room for the benchmarked one only.
+	for i in 1..T::MaxListLength::get() {
+		RequestMap::<T>::try_append(&patient, account::<T::AccountId>("doctor", i, 0)).unwrap();
+	}
+
+	(patient, doctor)
+}
+
+benchmarks! {
+	request_patient_data {
+		let (patient, doctor) = setup_accounts::<T>();
+	}: _(RawOrigin::Signed(doctor.clone()), patient.clone())
+	verify {
+		assert!(RequestMap::<T>::get(&patient).contains(&doctor));
+	}
+
+	// Runs the whole approval, including the write to the Doctor pallet's approved list.
+	approve_request {
+		let (patient, doctor) = setup_accounts::<T>();
+		PatientModule::<T>::request_patient_data(
+			RawOrigin::Signed(doctor.clone()).into(),
+			patient.clone(),
+		)
+		.unwrap();
+	}: _(RawOrigin::Signed(patient.clone()), doctor.clone(), Scope::ReadWrite, None)
+	verify {
+		assert!(
+			AprovedRequestMap::<T>::get(&patient).contains(&doctor) ||
+				PendingDoctorAcceptance::<T>::contains_key(&patient, &doctor)
+		);
+	}
+}
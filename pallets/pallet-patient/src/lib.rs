@@ -2,13 +2,32 @@
 
 pub use pallet::*;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
 #[frame_support::pallet]
 pub mod pallet {
+	use codec::DecodeAll;
 	use frame_support::pallet_prelude::*;
+	use frame_support::sp_runtime::traits::{Hash, IdentifyAccount, Verify};
 	use frame_system::pallet_prelude::*;
+	use sp_std::vec::Vec;
+
+	use crate::weights::WeightInfo;
+	use pallet_access::roles;
 
 	pub use pallet_access;
 	pub use pallet_doctor;
+	pub use pallet_doctor::Scope;
 
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
@@ -16,283 +35,4383 @@ pub mod pallet {
 		pub personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
 		pub data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
 		pub loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+		pub backup_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		pub deceased: bool,
+		/// Content type of the data behind `data_hash`, e.g. a MIME type. All zero when unknown
+		pub content_type: [u8; 16],
+		pub emergency_contact_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
 	}
 
 	impl<T: Config> Default for Patients<T> {
 		fn default() -> Self {
-			Patients { personal_data_hash: None, data_hash: None, loinc_code: None }
+			Patients {
+				personal_data_hash: None,
+				data_hash: None,
+				loinc_code: None,
+				backup_data_hash: None,
+				deceased: false,
+				content_type: [0u8; 16],
+				emergency_contact_hash: None,
+			}
+		}
+	}
+
+	/// Layout of `Patients` before the backup data hash was added, kept for the storage migration
+	#[derive(Decode)]
+	struct PatientsV0<T: Config> {
+		personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+	}
+
+	/// Layout of `Patients` before the deceased flag was added, kept for the storage migration
+	#[derive(Decode)]
+	struct PatientsV1<T: Config> {
+		personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+		backup_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+	}
+
+	/// Layout of `Patients` before the content type was added, kept for the storage migration
+	#[derive(Decode)]
+	struct PatientsV2<T: Config> {
+		personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+		backup_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		deceased: bool,
+	}
+
+	/// Layout of `Patients` before the emergency contact was added, kept for the storage
+	/// migration
+	#[derive(Decode)]
+	struct PatientsV3<T: Config> {
+		personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+		backup_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		deceased: bool,
+		content_type: [u8; 16],
+	}
+
+	/// Number of `Patients` layouts so far, the current one included. Records marked with a
+	/// lower number in `RecordLayout` are rewritten by `migrate_patients_batch`
+	pub(crate) const PATIENTS_LAYOUT: u16 = 5;
+
+	/// Handler notified whenever a Doctor loses access to a Patient's record
+	pub trait OnAccessRevoked<AccountId> {
+		fn on_revoke(patient_account_id: &AccountId, doctor_account_id: &AccountId);
+	}
+
+	impl<AccountId> OnAccessRevoked<AccountId> for () {
+		fn on_revoke(_patient_account_id: &AccountId, _doctor_account_id: &AccountId) {}
+	}
+
+	/// Terms under which a Patient approved a Doctor
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Approval<T: Config> {
+		pub scope: Scope,
+		pub expires_at: Option<T::BlockNumber>,
+		pub effective_at: T::BlockNumber,
+	}
+
+	impl<T: Config> Default for Approval<T> {
+		fn default() -> Self {
+			Approval { scope: Scope::default(), expires_at: None, effective_at: Default::default() }
+		}
+	}
+
+	/// Entry of a Patient's access log
+	#[derive(
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	pub struct AccessLogEntry<T: Config> {
+		/// Account that signed the logged call
+		pub signer: T::AccountId,
+		/// Doctor the signer acted for, when the signer is one of their delegated updaters
+		pub on_behalf_of: Option<T::AccountId>,
+		pub at: T::BlockNumber,
+		/// Attestation hash of an attested view, or the hash of the written data hash
+		pub hash: T::Hash,
+	}
+
+	/// Layout of `Approval` before the effective block was added, kept for the storage migration
+	#[derive(Decode)]
+	struct ApprovalV0<T: Config> {
+		scope: Scope,
+		expires_at: Option<T::BlockNumber>,
+	}
+
+	/// Snapshot of a Patient's on-chain state. Built on request for off-chain backups and never
+	/// stored on chain
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct PatientExport<T: Config> {
+		pub record: Patients<T>,
+		pub pending_requests: Vec<T::AccountId>,
+		pub approvals: Vec<(T::AccountId, Approval<T>)>,
+		pub frozen: bool,
+		pub private: bool,
+	}
+
+	/// Caseload figures of a Doctor
+	#[derive(Clone, Copy, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct DoctorLoad {
+		pub pending_requests: u32,
+		pub approved_patients: u32,
+		pub writable_patients: u32,
+	}
+
+	/// Protocol wide totals, read from the counters the pallets maintain
+	#[derive(Clone, Copy, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct ProtocolStats {
+		pub patients: u32,
+		pub doctors: u32,
+		pub roles: u32,
+		pub active_approvals: u32,
+		pub pending_requests: u32,
+	}
+
+	/// Kind of a patient affecting event kept in the RecentEvents buffer
+	#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum EventKind {
+		Registered,
+		RequestQueued,
+		AcceptancePending,
+		RequestApproved,
+		RequestDeclined,
+		DataUpdated,
+	}
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(10);
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_access::Config + pallet_doctor::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		///Weights of the dispatchables. Named through `<T as Config>` as `pallet_doctor::Config`
+		/// has a `WeightInfo` of its own
+		type WeightInfo: crate::WeightInfo;
+
+		#[pallet::constant]
+		type MaxLoincCodeLength: Get<u32>;
+
+		///Length Bound for the list of accounts accepted by batch operations, and for the number of
+		/// records `migrate_patients_batch` handles per call. A batch costs about `MaxBatch` times
+		/// the weight of a single entry, so the bound has to keep a full batch well inside the
+		/// block weight limit
+		#[pallet::constant]
+		type MaxBatch: Get<u32>;
+
+		///Whether an approved doctor has to accept the patient before the approval takes effect
+		#[pallet::constant]
+		type TwoSidedConsent: Get<bool>;
+
+		///Length Bound for the per patient buffer of recent events
+		#[pallet::constant]
+		type MaxRecentEvents: Get<u32>;
+
+		///Role required to freeze and unfreeze patient records
+		#[pallet::constant]
+		type PrivacyAdminRole: Get<[u8; 32]>;
+
+		///Role required to mark a Patient as deceased
+		#[pallet::constant]
+		type ComplianceRole: Get<[u8; 32]>;
+
+		///Whether accounts may register themselves as Patients. When disabled only the role gated
+		/// `register_patient` path is available
+		#[pallet::constant]
+		type AllowPatientSelfRegistration: Get<bool>;
+
+		///Whether newly registered Patients are assigned `PatientRole`
+		#[pallet::constant]
+		type AutoAssignPatientRole: Get<bool>;
+
+		///Role assigned to newly registered Patients when `AutoAssignPatientRole` is set
+		#[pallet::constant]
+		type PatientRole: Get<[u8; 32]>;
+
+		///Minimum number of blocks between two writes to a Patient's data
+		#[pallet::constant]
+		type UpdateCooldown: Get<Self::BlockNumber>;
+
+		///Length in blocks of the window in which a Patient's approvals and revocations are
+		/// counted
+		#[pallet::constant]
+		type ApprovalActionWindow: Get<Self::BlockNumber>;
+
+		///Maximum number of approvals and revocations a Patient can make within one
+		/// `ApprovalActionWindow`
+		#[pallet::constant]
+		type MaxApprovalActionsPerWindow: Get<u32>;
+
+		///Number of blocks after approval before a Doctor's access takes effect
+		#[pallet::constant]
+		type ApprovalDelay: Get<Self::BlockNumber>;
+
+		///Maximum number of Doctors a Patient can have approved at once. Should not exceed
+		/// `MaxListLength`, which bounds the approved list anyway
+		#[pallet::constant]
+		type MaxApprovalsPerPatient: Get<u32>;
+
+		///Number of approved Doctors at which a Patient is reported with `HighApprovalCount`. Zero
+		/// disables the alert
+		#[pallet::constant]
+		type ApprovalAlertThreshold: Get<u32>;
+
+		///Number of idempotency keys remembered per account. Once full the oldest key is evicted
+		#[pallet::constant]
+		type MaxSeenKeys: Get<u32>;
+
+		///Number of distinct compliance officers that must approve before a frozen record is
+		/// unfrozen. Freezing needs a single privacy admin
+		#[pallet::constant]
+		type UnfreezeQuorum: Get<u32>;
+
+		///Roles checked before a Doctor may request, update or swap Patient data
+		#[pallet::constant]
+		type DataAccessRoles: Get<Vec<[u8; 32]>>;
+
+		///Whether every role in `DataAccessRoles` is required, rather than any one of them
+		#[pallet::constant]
+		type RequireAllDataAccessRoles: Get<bool>;
+
+		///Prefix every submitted data hash must start with, e.g. the multibase prefix of a CIDv1.
+		/// An empty prefix disables the check
+		#[pallet::constant]
+		type RequireCidPrefix: Get<Vec<u8>>;
+
+		///Whether an update setting `data_hash` and `personal_data_hash` to the same hash is
+		/// rejected
+		#[pallet::constant]
+		type RejectDuplicateHashes: Get<bool>;
+
+		///Whether an update leaving the Patient's data exactly as stored is rejected with
+		/// `NoChange`, rather than skipped without a write or event
+		#[pallet::constant]
+		type RejectUnchangedUpdates: Get<bool>;
+
+		///Whether `ViewAttested` is emitted for attested views. Attested views are appended to
+		/// the `AccessLog` either way
+		#[pallet::constant]
+		type EmitViewEvents: Get<bool>;
+
+		///Whether an approval expiry beyond the Patient's `MaxApprovalDuration` is clamped to it,
+		/// rather than rejected with `DurationTooLong`
+		#[pallet::constant]
+		type ClampApprovalDuration: Get<bool>;
+
+		///Length Bound for the per patient access log. Once full the oldest entry is evicted and
+		/// reported through `AccessLogEntryEvicted`
+		#[pallet::constant]
+		type MaxLogLength: Get<u32>;
+
+		///Maximum number of hash prefixes in `AllowedHashPrefixes`
+		#[pallet::constant]
+		type MaxAllowedPrefixes: Get<u32>;
+
+		///Maximum number of delegated updaters a single Doctor can authorize
+		#[pallet::constant]
+		type MaxDelegates: Get<u32>;
+
+		///Maximum number of specialties a single Patient can pre-consent to
+		#[pallet::constant]
+		type MaxSpecialtyConsents: Get<u32>;
+
+		///Maximum number of care teams a single clinician can be a member of
+		#[pallet::constant]
+		type MaxTeamsPerClinician: Get<u32>;
+
+		///Number of blocks a deleted Patient record is kept as a tombstone, and can be restored by
+		/// root, before it is purged
+		#[pallet::constant]
+		type RetentionPeriod: Get<Self::BlockNumber>;
+
+		///Number of blocks the block a Doctor signed an attestation for may lie in the past when
+		/// it is submitted
+		#[pallet::constant]
+		type MaxAttestationAge: Get<Self::BlockNumber>;
+
+		///Number of blocks a confirmed renewal adds to an approval's expiry. Renewals can be asked
+		/// for once the approval expires within this many blocks
+		#[pallet::constant]
+		type RenewalPeriod: Get<Self::BlockNumber>;
+
+		///Handler called once for every revoked Patient and Doctor pair, set to `()` to ignore
+		/// revocations
+		type OnAccessRevoked: OnAccessRevoked<Self::AccountId>;
+
+		///Signature Doctors attest viewed data with, verified against the Doctor's account
+		type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+
+		///Public key recovering the Doctor's account from an `OffchainSignature`
+		type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn patient_data)]
+	pub type DataMap<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, Patients<T>, OptionQuery>;
+
+	///Storage Map for Storing all doctors who made request to view or update data against patient
+	/// AccountId, in the order the requests were made
+	#[pallet::storage]
+	#[pallet::getter(fn request_list)]
+	pub type RequestMap<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<T::AccountId, T::MaxListLength>,
+		ValueQuery,
+	>;
+
+	///Storage Map for Storing all approved requests for Patients Against their Account Id, in the
+	/// order they were approved
+	#[pallet::storage]
+	#[pallet::getter(fn approved_request_list)]
+	pub type AprovedRequestMap<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<T::AccountId, T::MaxListLength>,
+		ValueQuery,
+	>;
+
+	///Storage Double Map for Storing the block at which a doctor's request was queued, kept through
+	/// approval until the doctor's first data update
+	#[pallet::storage]
+	#[pallet::getter(fn requested_at)]
+	pub type RequestedAt<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	///Storage Double Map for Storing the terms of each approval given by a Patient to a Doctor.
+	/// Approvals without an entry are treated as read-write without expiry
+	#[pallet::storage]
+	#[pallet::getter(fn approval)]
+	pub type ApprovalDetails<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		Approval<T>,
+		OptionQuery,
+	>;
+
+	///Storage Double Map for Storing patient approvals waiting for the doctor's acceptance,
+	/// against the block of approval, the granted scope and the expiry of the approval
+	#[pallet::storage]
+	#[pallet::getter(fn pending_doctor_acceptance)]
+	pub type PendingDoctorAcceptance<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		(T::BlockNumber, Scope, Option<T::BlockNumber>),
+		OptionQuery,
+	>;
+
+	///Storage Map for Storing the most recent events affecting a Patient, oldest first. Once full
+	/// the oldest entry is evicted
+	#[pallet::storage]
+	#[pallet::getter(fn recent_events)]
+	pub type RecentEvents<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<(T::BlockNumber, EventKind), T::MaxRecentEvents>,
+		ValueQuery,
+	>;
+
+	///Storage Value for Counting registered Patients
+	#[pallet::storage]
+	#[pallet::getter(fn patient_count)]
+	pub type PatientCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	///Storage Value for Counting active approvals across all Patients
+	#[pallet::storage]
+	#[pallet::getter(fn total_active_approvals)]
+	pub type TotalActiveApprovals<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	///Storage Value for Storing the raw key of the last record handled by `migrate_patients_batch`
+	/// and the number of records handled so far. Empty when no batch migration is running
+	#[pallet::storage]
+	#[pallet::getter(fn migration_cursor)]
+	pub type MigrationCursor<T: Config> =
+		StorageValue<_, (BoundedVec<u8, ConstU32<128>>, u32), OptionQuery>;
+
+	///Storage Map for Storing the `Patients` layout each record was last migrated to
+	#[pallet::storage]
+	#[pallet::getter(fn record_layout)]
+	pub type RecordLayout<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u16, ValueQuery>;
+
+	///Storage Value for Storing the raw key of the last Patient checked by
+	/// `prune_dangling_approvals`. Empty when no pruning is running
+	#[pallet::storage]
+	#[pallet::getter(fn prune_cursor)]
+	pub type PruneCursor<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<128>>, OptionQuery>;
+
+	///Storage Value for Counting requests waiting for a Patient's approval
+	#[pallet::storage]
+	#[pallet::getter(fn pending_request_count)]
+	pub type PendingRequestCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	///Storage Map for Storing a per Patient sequence number bumped whenever a request is added to
+	/// or removed from the Patient's RequestMap. It wraps around on overflow
+	#[pallet::storage]
+	#[pallet::getter(fn request_seq)]
+	pub type RequestSeq<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	///Storage Map for Storing accounts which have been registered as a Patient at least once
+	#[pallet::storage]
+	#[pallet::getter(fn has_registered_once)]
+	pub type HasRegisteredOnce<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, bool, ValueQuery>;
+
+	///Storage Map for Storing the block of the last write to a Patient's data
+	#[pallet::storage]
+	#[pallet::getter(fn last_update_block)]
+	pub type LastUpdateBlock<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	///Storage Map for Storing the first block of a Patient's current approval action window and
+	/// the approvals and revocations they made in it
+	#[pallet::storage]
+	#[pallet::getter(fn approval_action_count)]
+	pub type ApprovalActionCount<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, (T::BlockNumber, u32), ValueQuery>;
+
+	///Storage Map for Storing the data hash a Patient's latest write replaced
+	#[pallet::storage]
+	pub type PreviousDataHash<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, BoundedVec<u8, T::MaxHashLength>, OptionQuery>;
+
+	///Storage Map for Storing the number of writes made to a Patient's data
+	#[pallet::storage]
+	#[pallet::getter(fn update_count)]
+	pub type UpdateCount<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	///Storage Map for Storing the `UpdateCount` a Patient last acknowledged. Counting writes rather
+	/// than comparing blocks catches a write made in the same block as the acknowledgement
+	#[pallet::storage]
+	#[pallet::getter(fn last_acknowledged)]
+	pub type LastAcknowledged<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	///Storage Map for Storing the approved list length at which a Patient was last reported with
+	/// `HighApprovalCount`
+	#[pallet::storage]
+	#[pallet::getter(fn last_alerted_count)]
+	pub type LastAlertedCount<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	///Storage Map for Storing the idempotency keys of the most recent updates made by an account,
+	/// oldest first
+	#[pallet::storage]
+	#[pallet::getter(fn seen_keys)]
+	pub type SeenKeys<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, BoundedVec<[u8; 16], T::MaxSeenKeys>, ValueQuery>;
+
+	///Storage Map for Storing the Patients covered by each care team
+	#[pallet::storage]
+	#[pallet::getter(fn care_team_patients)]
+	pub type CareTeams<T: Config> =
+		StorageMap<_, Twox64Concat, u32, BoundedVec<T::AccountId, T::MaxListLength>, ValueQuery>;
+
+	///Storage Map for Storing the clinicians of each care team. Members get read-only access to
+	/// every Patient of their team
+	#[pallet::storage]
+	#[pallet::getter(fn care_team_members)]
+	pub type TeamMembers<T: Config> =
+		StorageMap<_, Twox64Concat, u32, BoundedVec<T::AccountId, T::MaxListLength>, ValueQuery>;
+
+	///Storage Map for Storing the care teams each clinician is a member of
+	#[pallet::storage]
+	#[pallet::getter(fn clinician_teams)]
+	pub type ClinicianTeams<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<u32, T::MaxTeamsPerClinician>,
+		ValueQuery,
+	>;
+
+	///Storage Map for Storing the call indices of dispatchables disabled by root
+	#[pallet::storage]
+	#[pallet::getter(fn is_call_disabled)]
+	pub type DisabledCalls<T: Config> = StorageMap<_, Twox64Concat, u8, bool, ValueQuery>;
+
+	///Storage Map for Storing whether a Patient record is frozen. Frozen records reject new
+	/// requests, approvals and updates
+	#[pallet::storage]
+	#[pallet::getter(fn is_frozen)]
+	pub type Frozen<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, bool, ValueQuery>;
+
+	///Storage Map for Storing the views Doctors attested for a Patient and the updates delegates
+	/// submitted, oldest first. Once full the oldest entry is evicted and emitted in
+	/// `AccessLogEntryEvicted`
+	#[pallet::storage]
+	#[pallet::getter(fn access_log)]
+	pub type AccessLog<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<AccessLogEntry<T>, T::MaxLogLength>,
+		ValueQuery,
+	>;
+
+	///Storage Map for Storing the accounts a Doctor authorized to submit updates on their behalf
+	#[pallet::storage]
+	#[pallet::getter(fn delegated_updaters)]
+	pub type DelegatedUpdaters<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<T::AccountId, T::MaxDelegates>,
+		ValueQuery,
+	>;
+
+	///Storage Map for Storing whether a Patient has opted out of receiving new requests
+	#[pallet::storage]
+	#[pallet::getter(fn is_private)]
+	pub type Private<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, bool, ValueQuery>;
+
+	///Storage Map for Storing the consent receipt of each active approval. The receipt hashes the
+	/// Patient, the Doctor, the approval block and the scope
+	#[pallet::storage]
+	#[pallet::getter(fn approval_receipt)]
+	pub type ApprovalReceipts<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		T::Hash,
+		OptionQuery,
+	>;
+
+	///Storage Map for Storing deleted Patient records with their deletion block until they are
+	/// purged
+	#[pallet::storage]
+	#[pallet::getter(fn tombstone)]
+	pub type Tombstones<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, (T::BlockNumber, Patients<T>), OptionQuery>;
+
+	///Storage Value for Storing the raw key of the last tombstone checked by `on_idle`. Empty when
+	/// the next check starts from the first tombstone
+	#[pallet::storage]
+	#[pallet::getter(fn purge_cursor)]
+	pub type PurgeCursor<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<128>>, OptionQuery>;
+
+	///Storage Map for Storing the specialties a Patient pre-consented to, with the scope granted.
+	/// Requests from Doctors of these specialties are approved right away
+	#[pallet::storage]
+	#[pallet::getter(fn specialty_consent)]
+	pub type SpecialtyConsent<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<([u8; 8], Scope), T::MaxSpecialtyConsents>,
+		ValueQuery,
+	>;
+
+	///Storage Map for Storing the scope a Patient grants when approving without naming one
+	#[pallet::storage]
+	#[pallet::getter(fn default_scope)]
+	pub type DefaultScope<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, Scope, ValueQuery>;
+
+	///Storage Value for Storing the hash prefixes of the storage networks data may be kept on.
+	/// Every hash is accepted while it is empty
+	#[pallet::storage]
+	#[pallet::getter(fn allowed_hash_prefixes)]
+	pub type AllowedHashPrefixes<T: Config> = StorageValue<
+		_,
+		BoundedVec<BoundedVec<u8, T::MaxHashLength>, T::MaxAllowedPrefixes>,
+		ValueQuery,
+	>;
+
+	///Storage Double Map for Storing the renewals Doctors asked for, against the block of the
+	/// request, until the Patient confirms them
+	#[pallet::storage]
+	#[pallet::getter(fn pending_renewal)]
+	pub type PendingRenewals<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	///Storage Double Map for Storing the hash of the feedback a Patient left on a Doctor, keyed
+	/// by Doctor first
+	#[pallet::storage]
+	#[pallet::getter(fn doctor_feedback)]
+	pub type DoctorFeedback<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<u8, T::MaxHashLength>,
+		OptionQuery,
+	>;
+
+	///Storage Double Map for Storing the Doctors each Patient left feedback on, the reverse of
+	/// `DoctorFeedback`
+	#[pallet::storage]
+	pub type FeedbackGiven<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	///Storage Map for Storing the number of Patients that left feedback on a Doctor
+	#[pallet::storage]
+	#[pallet::getter(fn feedback_count)]
+	pub type FeedbackCount<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	///Storage Map for Storing the lower approval cap a Patient set for themselves. Patients
+	/// without an entry are capped at `MaxApprovalsPerPatient`
+	#[pallet::storage]
+	#[pallet::getter(fn approval_cap)]
+	pub type ApprovalCap<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, OptionQuery>;
+
+	///Storage Map for Storing the longest duration in blocks a Patient allows any approval to
+	/// last. Patients without an entry allow approvals without expiry
+	#[pallet::storage]
+	#[pallet::getter(fn max_approval_duration)]
+	pub type MaxApprovalDuration<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	///Storage Map for Storing whether a Patient requires a reason hash on every update
+	#[pallet::storage]
+	#[pallet::getter(fn requires_update_reason)]
+	pub type RequireUpdateReason<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, bool, ValueQuery>;
+
+	///Storage Map for Storing the compliance officers that approved unfreezing a Patient record
+	#[pallet::storage]
+	#[pallet::getter(fn unfreeze_approvals)]
+	pub type UnfreezeApprovals<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<T::AccountId, T::UnfreezeQuorum>,
+		ValueQuery,
+	>;
+
+	///Storage Map for Storing the Doctors a Patient has blocked. Blocked Doctors can not be
+	/// approved by that Patient
+	#[pallet::storage]
+	#[pallet::getter(fn is_blocked)]
+	pub type BlockList<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		bool,
+		ValueQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// When Patient Data is Successfully registered. Later edits emit `DataUpdated`
+		PatientRegistered { patient_account_id: T::AccountId },
+		/// When a Request is Sucessfully added to the RequestQueue. `specialty` is the
+		/// requester's specialty, if they set one
+		RequestQueued {
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+			requested_at: T::BlockNumber,
+			specialty: Option<[u8; 8]>,
+		},
+		/// When a Request is Successfully Approved
+		RequestApproved {
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+			requested_at: T::BlockNumber,
+			scope: Scope,
+			specialty: Option<[u8; 8]>,
+			receipt: T::Hash,
+			expires_at: Option<T::BlockNumber>,
+		},
+		/// When a request is successfully executed. `requested_at` is only set on the first
+		/// update following an approval.
+		DataUpdated {
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+			requested_at: Option<T::BlockNumber>,
+			content_type: [u8; 16],
+			reason_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		},
+		/// When requests without a matching doctor side entry are removed from a RequestMap key
+		OrphanedRequestsPruned { key: T::AccountId, removed: u32 },
+		/// When a Patient approved a Request which now awaits the doctor's acceptance
+		DoctorAcceptancePending { requester: T::AccountId, patient_account_id: T::AccountId },
+		/// When a Doctor accepted a Patient's approval
+		DoctorAccepted { requester: T::AccountId, patient_account_id: T::AccountId },
+		/// When a Doctor declined a Patient's approval
+		DoctorDeclined { requester: T::AccountId, patient_account_id: T::AccountId },
+		/// When a Patient record is frozen or unfrozen
+		FrozenStateChanged { patient_account_id: T::AccountId, frozen: bool },
+		/// When a Patient changes their privacy setting
+		PrivacyChanged { patient_account_id: T::AccountId, private: bool },
+		/// When a Patient changes whether updates to their data must carry a reason hash
+		UpdateReasonRequirementChanged { patient_account_id: T::AccountId, required: bool },
+		/// When a Patient sets or clears their backup data hash
+		BackupHashSet { patient_account_id: T::AccountId },
+		/// When a Patient changes the scope of an existing approval
+		ApprovalScopeChanged {
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+			scope: Scope,
+		},
+		/// When a Patient rejected every pending request at once
+		AllRequestsCleared { patient_account_id: T::AccountId, count: u32 },
+		/// When a Patient's list reached `MaxListLength`, so further appends fail
+		ListFull { owner: T::AccountId, list_kind: pallet_doctor::ListKind },
+		/// When a Patient record is marked as deceased, leaving it read-only
+		PatientMarkedDeceased { patient_account_id: T::AccountId },
+		/// When a Patient acknowledged the updates made to their data so far
+		UpdateAcknowledged { patient_account_id: T::AccountId, update_count: u32 },
+		/// When a batch registration finished, with the number of new and already registered
+		/// Patients
+		BatchRegisterResult { registered: u32, skipped: u32 },
+		/// When root enables or disables a dispatchable
+		CallEnabledChanged { call_index: u8, enabled: bool },
+		/// When root changes the Patients or members of a care team
+		CareTeamUpdated { team_id: u32 },
+		/// When the number of Doctors approved by a Patient reaches `ApprovalAlertThreshold`
+		HighApprovalCount { patient_account_id: T::AccountId, count: u32 },
+		/// When a Patient revoked the access of every approved Doctor at once
+		AllAccessRevoked { patient_account_id: T::AccountId, count: u32 },
+		/// When a compliance officer approved unfreezing a record that has not reached the quorum
+		UnfreezeProgress { patient_account_id: T::AccountId, approvals: u32, quorum: u32 },
+		/// When a Doctor confirmed they hold an approval from the Patient and can read the data
+		/// right away
+		AlreadyHasAccess { doctor_account_id: T::AccountId, patient_account_id: T::AccountId },
+		/// When a compliance officer revoked a Doctor's access without the Patient's action
+		AccessForceRevoked {
+			patient_account_id: T::AccountId,
+			doctor_account_id: T::AccountId,
+			by: T::AccountId,
+		},
+		/// When a Patient deleted their record. It can be restored until it is purged
+		PatientRecordDeleted { patient_account_id: T::AccountId },
+		/// When root restored a deleted Patient record
+		RecordRestored { patient_account_id: T::AccountId },
+		/// When a deleted Patient record was purged after the retention period
+		RecordPurged { patient_account_id: T::AccountId },
+		/// When a Patient changed the scope granted by approvals without an explicit scope
+		DefaultScopeChanged { patient_account_id: T::AccountId, scope: Scope },
+		/// When a call to `migrate_patients_batch` finished. `remaining` is zero once every
+		/// record was handled
+		MigrationProgress { done: u32, remaining: u32 },
+		/// When a Patient sets or clears their emergency contact hash
+		EmergencyContactSet { patient_account_id: T::AccountId },
+		/// When root allowed hashes starting with a prefix
+		AllowedPrefixAdded { prefix: BoundedVec<u8, T::MaxHashLength> },
+		/// When root removed a prefix from the allowed hash prefixes
+		AllowedPrefixRemoved { prefix: BoundedVec<u8, T::MaxHashLength> },
+		/// When a Doctor attested viewing a Patient's data. `attestation` hashes the signed payload
+		/// and the signature
+		ViewAttested {
+			patient_account_id: T::AccountId,
+			doctor_account_id: T::AccountId,
+			attestation: T::Hash,
+		},
+		/// When a Patient gave, changed or withdrew their consent to a specialty
+		SpecialtyConsentChanged {
+			patient_account_id: T::AccountId,
+			specialty: [u8; 8],
+			scope: Option<Scope>,
+		},
+		/// When a request was approved right away as the Patient pre-consented to the Doctor's
+		/// specialty
+		SpecialtyAutoApproved {
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+			specialty: [u8; 8],
+			scope: Scope,
+		},
+		/// When a Doctor asked the Patient to renew an expiring approval
+		RenewalRequested { requester: T::AccountId, patient_account_id: T::AccountId },
+		/// When a Patient confirmed a renewal, moving the approval's expiry to `expires_at`
+		ApprovalRenewed {
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+			expires_at: T::BlockNumber,
+		},
+		/// When a Patient set or cleared their own approval cap
+		ApprovalCapChanged { patient_account_id: T::AccountId, cap: Option<u32> },
+		/// When root removed `fixed` approvals recorded on only one of the Patient and Doctor sides
+		ConsistencyRepaired { patient_account_id: T::AccountId, fixed: u32 },
+		/// When a Patient left or replaced their feedback on a Doctor
+		FeedbackLeft {
+			doctor_account_id: T::AccountId,
+			patient_account_id: T::AccountId,
+			feedback_hash: BoundedVec<u8, T::MaxHashLength>,
+		},
+		/// When a Patient set or cleared the longest duration they allow an approval to last
+		MaxApprovalDurationSet {
+			patient_account_id: T::AccountId,
+			max_duration: Option<T::BlockNumber>,
+		},
+		/// When an approval of a Doctor without a record was removed by
+		/// `prune_dangling_approvals`
+		DanglingApprovalPruned { patient_account_id: T::AccountId, doctor_account_id: T::AccountId },
+		/// When the `source` Patient record was merged into `target` and removed
+		RecordsMerged { source: T::AccountId, target: T::AccountId },
+		/// When a full access log dropped its oldest entry to make room for a new one
+		AccessLogEntryEvicted { patient_account_id: T::AccountId, evicted_entry: AccessLogEntry<T> },
+		/// When a Doctor authorized or deauthorized an account to submit updates on their behalf
+		DelegatedUpdaterChanged {
+			doctor_account_id: T::AccountId,
+			delegate: T::AccountId,
+			authorized: bool,
+		},
+		/// When a delegated updater wrote a Patient's data on the authority of an approved Doctor
+		DelegatedUpdate {
+			delegate: T::AccountId,
+			doctor_account_id: T::AccountId,
+			patient_account_id: T::AccountId,
+		},
+		/// When a Patient blocked or unblocked a Doctor
+		BlockListChanged {
+			patient_account_id: T::AccountId,
+			doctor_account_id: T::AccountId,
+			blocked: bool,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		BoundsOverflow,
+		AlreadyRegistered,
+		AlreadyRequested,
+		AlreadyApproved,
+		NoRequest,
+		UnableToUpdate,
+		MaxListLengthReached,
+		NotApproved,
+		NoPatient,
+		AcceptancePending,
+		NoPendingAcceptance,
+		RecordFrozen,
+		PatientPrivate,
+		ReadOnlyAccess,
+		ApprovalExpired,
+		UpdateTooSoon,
+		RecordFrozenDeceased,
+		ApprovalNotYetEffective,
+		CallDisabled,
+		RequesterBlocked,
+		NotFrozen,
+		AlreadyApprovedUnfreeze,
+		InvalidHashFormat,
+		TooManyTeams,
+		ReasonRequired,
+		NoTombstone,
+		DuplicateHash,
+		NoChange,
+		DisallowedBackend,
+		PrefixAlreadyAllowed,
+		PrefixNotAllowed,
+		TooManyPrefixes,
+		InvalidSignature,
+		AttestationInFuture,
+		AttestationTooOld,
+		MergeConflict,
+		LicenseExpired,
+		TooManySpecialtyConsents,
+		ApprovalNotExpiring,
+		RenewalNotDue,
+		NoRenewal,
+		ApprovalCapReached,
+		TooManyDelegates,
+		NotDelegate,
+		DurationTooLong,
+		TooManyApprovalActions,
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn specialty_of(doctor_account_id: &T::AccountId) -> Option<[u8; 8]> {
+			pallet_doctor::Pallet::<T>::patient_data(doctor_account_id)
+				.and_then(|doctor| doctor.specialty)
+		}
+
+		fn ensure_hash_format(
+			hashes: &[Option<&BoundedVec<u8, T::MaxHashLength>>],
+		) -> Result<(), DispatchError> {
+			let prefix = T::RequireCidPrefix::get();
+
+			ensure!(
+				hashes.iter().flatten().all(|hash| hash.starts_with(&prefix)),
+				Error::<T>::InvalidHashFormat
+			);
+
+			let allowed = AllowedHashPrefixes::<T>::get();
+
+			ensure!(
+				allowed.is_empty() ||
+					hashes.iter().flatten().all(|hash| {
+						allowed.iter().any(|allowed_prefix| hash.starts_with(allowed_prefix))
+					}),
+				Error::<T>::DisallowedBackend
+			);
+
+			Ok(())
+		}
+
+		fn ensure_data_access(requester: &T::AccountId) -> Result<(), DispatchError> {
+			let roles = T::DataAccessRoles::get();
+
+			if T::RequireAllDataAccessRoles::get() {
+				pallet_access::Pallet::<T>::validate_roles_all(requester.clone(), &roles)?;
+			} else {
+				pallet_access::Pallet::<T>::validate_roles_any(requester.clone(), &roles)?;
+			}
+
+			ensure!(
+				!pallet_doctor::Pallet::<T>::license_expired(requester),
+				Error::<T>::LicenseExpired
+			);
+
+			Ok(())
+		}
+
+		fn register(
+			patient_account_id: T::AccountId,
+			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+		) -> Result<(), DispatchError> {
+			ensure!(
+				!DataMap::<T>::contains_key(&patient_account_id),
+				Error::<T>::AlreadyRegistered
+			);
+
+			Self::ensure_hash_format(&[personal_data_hash.as_ref(), data_hash.as_ref()])?;
+
+			let patient = Patients::<T> {
+				personal_data_hash,
+				data_hash,
+				loinc_code,
+				backup_data_hash: None,
+				deceased: false,
+				content_type: [0u8; 16],
+				emergency_contact_hash: None,
+			};
+
+			DataMap::<T>::insert(&patient_account_id, patient);
+
+			HasRegisteredOnce::<T>::insert(&patient_account_id, true);
+
+			if T::AutoAssignPatientRole::get() {
+				let patient_role = T::PatientRole::get();
+
+				// Accounts that already hold the role keep it untouched.
+				if pallet_access::Pallet::<T>::validate_role(
+					patient_account_id.clone(),
+					patient_role,
+				)
+				.is_err()
+				{
+					pallet_access::Pallet::<T>::assign_role(
+						patient_account_id.clone(),
+						patient_role,
+					)?;
+				}
+			}
+
+			PatientCount::<T>::mutate(|count| *count = count.saturating_add(1));
+
+			Self::record_event(&patient_account_id, EventKind::Registered);
+
+			Self::deposit_event(Event::PatientRegistered { patient_account_id });
+
+			Ok(())
+		}
+
+		/// Lowers `PendingRequestCount` by `by`, saturating at zero. Panics in debug builds if the
+		/// counter would drop below zero, i.e. increments and decrements diverged.
+		fn dec_pending_requests(by: u32) {
+			PendingRequestCount::<T>::mutate(|count| {
+				debug_assert!(*count >= by, "PendingRequestCount decremented below zero");
+				*count = count.saturating_sub(by);
+			});
+		}
+
+		/// Lowers `PatientCount` by one, saturating at zero. Panics in debug builds if the counter
+		/// would drop below zero.
+		fn dec_patient_count() {
+			PatientCount::<T>::mutate(|count| {
+				debug_assert!(*count > 0, "PatientCount decremented below zero");
+				*count = count.saturating_sub(1);
+			});
+		}
+
+		/// Lowers `TotalActiveApprovals` by `by`, saturating at zero. Panics in debug builds if the
+		/// counter would drop below zero.
+		fn dec_active_approvals(by: u32) {
+			TotalActiveApprovals::<T>::mutate(|total| {
+				debug_assert!(*total >= by, "TotalActiveApprovals decremented below zero");
+				*total = total.saturating_sub(by);
+			});
+		}
+
+		fn ensure_enabled(call_index: u8) -> Result<(), DispatchError> {
+			ensure!(!DisabledCalls::<T>::get(call_index), Error::<T>::CallDisabled);
+
+			Ok(())
+		}
+
+		fn set_call_state(call_index: u8, enabled: bool) -> Result<(), DispatchError> {
+			if enabled {
+				DisabledCalls::<T>::remove(call_index);
+			} else {
+				DisabledCalls::<T>::insert(call_index, true);
+			}
+
+			Self::deposit_event(Event::CallEnabledChanged { call_index, enabled });
+
+			Ok(())
+		}
+
+		fn set_team(
+			team_id: u32,
+			patients: BoundedVec<T::AccountId, T::MaxListLength>,
+			members: BoundedVec<T::AccountId, T::MaxListLength>,
+		) -> Result<(), DispatchError> {
+			if patients.is_empty() {
+				CareTeams::<T>::remove(team_id);
+			} else {
+				CareTeams::<T>::insert(team_id, patients);
+			}
+
+			let previous_members = TeamMembers::<T>::get(team_id);
+
+			for member in previous_members.iter().filter(|member| !members.contains(member)) {
+				ClinicianTeams::<T>::mutate(member, |teams| teams.retain(|team| *team != team_id));
+			}
+
+			for member in members.iter().filter(|member| !previous_members.contains(member)) {
+				ClinicianTeams::<T>::try_mutate(member, |teams| teams.try_push(team_id))
+					.map_err(|_| Error::<T>::TooManyTeams)?;
+			}
+
+			if members.is_empty() {
+				TeamMembers::<T>::remove(team_id);
+			} else {
+				TeamMembers::<T>::insert(team_id, members);
+			}
+
+			Self::deposit_event(Event::CareTeamUpdated { team_id });
+
+			Ok(())
+		}
+
+		fn register_batch(
+			entries: BoundedVec<
+				(
+					T::AccountId,
+					Option<BoundedVec<u8, T::MaxHashLength>>,
+					Option<BoundedVec<u8, T::MaxHashLength>>,
+				),
+				T::MaxBatch,
+			>,
+		) -> Result<(), DispatchError> {
+			let mut registered = 0u32;
+			let mut skipped = 0u32;
+
+			for (patient_account_id, personal_data_hash, data_hash) in entries {
+				if DataMap::<T>::contains_key(&patient_account_id) {
+					skipped += 1;
+					continue
+				}
+
+				Self::register(patient_account_id, personal_data_hash, data_hash, None)?;
+
+				registered += 1;
+			}
+
+			Self::deposit_event(Event::BatchRegisterResult { registered, skipped });
+
+			Ok(())
+		}
+
+		fn bump_request_seq(patient_account_id: &T::AccountId) {
+			RequestSeq::<T>::mutate(patient_account_id, |seq| *seq = seq.wrapping_add(1));
+		}
+
+		fn record_event(patient_account_id: &T::AccountId, kind: EventKind) {
+			let entry = (frame_system::Pallet::<T>::block_number(), kind);
+
+			RecentEvents::<T>::mutate(patient_account_id, |events| {
+				if let Err(entry) = events.try_push(entry) {
+					if !events.is_empty() {
+						events.remove(0);
+						let _ = events.try_push(entry);
+					}
+				}
+			});
+		}
+
+		fn ensure_not_frozen(patient_account_id: &T::AccountId) -> Result<(), DispatchError> {
+			ensure!(!Frozen::<T>::get(patient_account_id), Error::<T>::RecordFrozen);
+
+			ensure!(
+				!DataMap::<T>::get(patient_account_id).map_or(false, |patient| patient.deceased),
+				Error::<T>::RecordFrozenDeceased
+			);
+
+			Ok(())
+		}
+
+		fn request(
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+		) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+
+			// Checked ahead of any write, `add_request` would only fail once the Patient's list
+			// was already extended.
+			ensure!(
+				pallet_doctor::DataMap::<T>::contains_key(&requester),
+				pallet_doctor::Error::<T>::NotRegistered
+			);
+
+			Self::ensure_not_frozen(&patient_account_id)?;
+
+			ensure!(!Private::<T>::get(&patient_account_id), Error::<T>::PatientPrivate);
+
+			let doctors_ids = RequestMap::<T>::get(&patient_account_id);
+
+			ensure!(
+				!doctors_ids.iter().any(|account_id| account_id == &requester),
+				Error::<T>::AlreadyRequested
+			);
+
+			let approved_doctor_ids = AprovedRequestMap::<T>::get(&patient_account_id);
+
+			ensure!(
+				!approved_doctor_ids.iter().any(|account_id| account_id == &requester),
+				Error::<T>::AlreadyApproved
+			);
+
+			ensure!(
+				!PendingDoctorAcceptance::<T>::contains_key(&patient_account_id, &requester),
+				Error::<T>::AcceptancePending
+			);
+
+			Self::bounded_push(
+				&patient_account_id,
+				requester.clone(),
+				pallet_doctor::ListKind::Request,
+			)?;
+
+			pallet_doctor::Pallet::<T>::add_request(requester.clone(), patient_account_id.clone())?;
+
+			Self::bump_request_seq(&patient_account_id);
+
+			let requested_at = frame_system::Pallet::<T>::block_number();
+
+			RequestedAt::<T>::insert(&patient_account_id, &requester, requested_at);
+
+			PendingRequestCount::<T>::mutate(|count| *count = count.saturating_add(1));
+
+			Self::record_event(&patient_account_id, EventKind::RequestQueued);
+
+			let specialty = Self::specialty_of(&requester);
+
+			Self::deposit_event(Event::RequestQueued {
+				requester: requester.clone(),
+				patient_account_id: patient_account_id.clone(),
+				requested_at,
+				specialty,
+			});
+
+			// Blocked Doctors stay queued, the Patient's block list overrides the consent.
+			let consent = specialty.and_then(|specialty| {
+				SpecialtyConsent::<T>::get(&patient_account_id)
+					.into_iter()
+					.find(|(consented, _)| consented == &specialty)
+			});
+
+			if let Some((specialty, scope)) = consent {
+				if !BlockList::<T>::get(&patient_account_id, &requester) {
+					// If the auto-approval fails, on a full approved list for instance, its writes are
+					// rolled back and the request stays queued for the Patient to decide.
+					let approved = frame_support::storage::with_storage_layer(|| {
+						Self::approve(patient_account_id.clone(), requester.clone(), scope, None)
+					});
+
+					// Under two-sided consent the approval only waits for the Doctor's acceptance.
+					if approved.is_ok() &&
+						AprovedRequestMap::<T>::get(&patient_account_id).contains(&requester)
+					{
+						Self::deposit_event(Event::SpecialtyAutoApproved {
+							requester,
+							patient_account_id,
+							specialty,
+							scope,
+						});
+					}
+				}
+			}
+
+			Ok(())
+		}
+
+		/// Appends `requester` to the Patient's list of the given kind and emits `ListFull` once
+		/// the list reaches its bound, as the append that overflows it fails and drops its events.
+		fn bounded_push(
+			patient_account_id: &T::AccountId,
+			requester: T::AccountId,
+			list_kind: pallet_doctor::ListKind,
+		) -> Result<(), DispatchError> {
+			let push = |list: &mut BoundedVec<T::AccountId, T::MaxListLength>| {
+				list.try_push(requester).map_err(|_| Error::<T>::MaxListLengthReached)?;
+				Ok::<_, DispatchError>(list.len() as u32)
+			};
+
+			let len = match list_kind {
+				pallet_doctor::ListKind::Request =>
+					RequestMap::<T>::try_mutate(patient_account_id, push),
+				pallet_doctor::ListKind::Approved =>
+					AprovedRequestMap::<T>::try_mutate(patient_account_id, push),
+			}?;
+
+			if len >= T::MaxListLength::get() {
+				Self::deposit_event(Event::ListFull {
+					owner: patient_account_id.clone(),
+					list_kind,
+				});
+			}
+
+			Ok(())
+		}
+
+		fn remove_request(
+			patient_account_id: T::AccountId,
+			requester: T::AccountId,
+		) -> Result<(), DispatchError> {
+			let mut doctor_ids = RequestMap::<T>::get(&patient_account_id);
+
+			ensure!(
+				doctor_ids.iter().any(|account_id| account_id == &requester),
+				Error::<T>::NoRequest
+			);
+
+			if let Some(ind) = doctor_ids.iter().position(|id| id == &requester) {
+				doctor_ids.remove(ind);
+			} else {
+				return Err(Error::<T>::NoRequest.into())
+			}
+
+			RequestMap::<T>::insert(&patient_account_id, doctor_ids);
+
+			Self::bump_request_seq(&patient_account_id);
+
+			Self::dec_pending_requests(1);
+
+			Ok(())
+		}
+
+		fn prune_orphaned_requests(key: T::AccountId) -> u32 {
+			let is_patient = DataMap::<T>::contains_key(&key);
+
+			let mut doctor_ids = RequestMap::<T>::get(&key);
+			let stored = doctor_ids.len();
+
+			// A request is only genuine if it was made against a registered patient and the doctor
+			// still has the matching pending entry on their side.
+			doctor_ids.retain(|requester| {
+				let genuine = is_patient &&
+					pallet_doctor::Pallet::<T>::request_list(requester).contains(&key);
+
+				if !genuine {
+					RequestedAt::<T>::remove(&key, requester);
+				}
+
+				genuine
+			});
+
+			let removed = (stored - doctor_ids.len()) as u32;
+
+			if removed > 0 {
+				Self::bump_request_seq(&key);
+			}
+
+			if doctor_ids.is_empty() {
+				RequestMap::<T>::remove(&key);
+			} else {
+				RequestMap::<T>::insert(&key, doctor_ids);
+			}
+
+			Self::deposit_event(Event::OrphanedRequestsPruned { key, removed });
+
+			removed
+		}
+
+		/// Approves the Doctor's request. Under `TwoSidedConsent` the expiry, bound by the Patient's
+		/// `MaxApprovalDuration`, is kept with the pending acceptance and applied once the Doctor
+		/// accepts.
+		fn approve(
+			patient_account_id: T::AccountId,
+			requester: T::AccountId,
+			scope: Scope,
+			expires_at: Option<T::BlockNumber>,
+		) -> Result<(), DispatchError> {
+			Self::ensure_not_frozen(&patient_account_id)?;
+
+			ensure!(
+				!BlockList::<T>::get(&patient_account_id, &requester),
+				Error::<T>::RequesterBlocked
+			);
+
+			Self::remove_request(patient_account_id.clone(), requester.clone())?;
+
+			// Drops any further entry of the Doctor so the request and approved lists stay
+			// disjoint even if an earlier removal was incomplete.
+			let duplicates = RequestMap::<T>::mutate(&patient_account_id, |doctor_ids| {
+				let stored = doctor_ids.len();
+				doctor_ids.retain(|account_id| account_id != &requester);
+				(stored - doctor_ids.len()) as u32
+			});
+
+			Self::dec_pending_requests(duplicates);
+
+			// A request held next to an existing approval is stale. Its removal above normalizes
+			// the lists, so it is kept instead of failing with AlreadyApproved.
+			if AprovedRequestMap::<T>::get(&patient_account_id).contains(&requester) {
+				let _ = pallet_doctor::Pallet::<T>::remove_request(
+					requester,
+					patient_account_id.clone(),
+				);
+
+				Self::assert_disjoint(&patient_account_id);
+
+				return Ok(())
+			}
+
+			Self::ensure_below_approval_cap(&patient_account_id)?;
+
+			let expires_at = Self::effective_expiry(&patient_account_id, expires_at)?;
+
+			if T::TwoSidedConsent::get() {
+				PendingDoctorAcceptance::<T>::insert(
+					&patient_account_id,
+					&requester,
+					(frame_system::Pallet::<T>::block_number(), scope, expires_at),
+				);
+
+				Self::record_event(&patient_account_id, EventKind::AcceptancePending);
+
+				Self::deposit_event(Event::DoctorAcceptancePending {
+					requester,
+					patient_account_id,
+				});
+
+				return Ok(())
+			}
+
+			Self::add_approval(patient_account_id, requester, scope, expires_at)
+		}
+
+		/// Returns the expiry an approval requested to end at `expires_at` gets under the
+		/// Patient's `MaxApprovalDuration`, counted from now. An expiry past the ceiling is
+		/// clamped to it or rejected, depending on `ClampApprovalDuration`.
+		fn effective_expiry(
+			patient_account_id: &T::AccountId,
+			expires_at: Option<T::BlockNumber>,
+		) -> Result<Option<T::BlockNumber>, DispatchError> {
+			let max_duration = match MaxApprovalDuration::<T>::get(patient_account_id) {
+				Some(max_duration) => max_duration,
+				None => return Ok(expires_at),
+			};
+
+			let ceiling = frame_system::Pallet::<T>::block_number().saturating_add(max_duration);
+
+			match expires_at {
+				Some(expires_at) if expires_at <= ceiling => Ok(Some(expires_at)),
+				_ => {
+					ensure!(T::ClampApprovalDuration::get(), Error::<T>::DurationTooLong);
+
+					Ok(Some(ceiling))
+				},
+			}
+		}
+
+		/// Returns the Patient's approval cap, which is their own `ApprovalCap` if set, never
+		/// exceeding `MaxApprovalsPerPatient`.
+		fn effective_approval_cap(patient_account_id: &T::AccountId) -> u32 {
+			let max = T::MaxApprovalsPerPatient::get();
+
+			ApprovalCap::<T>::get(patient_account_id).map_or(max, |cap| cap.min(max))
+		}
+
+		/// Checks that one more approval stays within the Patient's cap.
+		fn ensure_below_approval_cap(
+			patient_account_id: &T::AccountId,
+		) -> Result<(), DispatchError> {
+			let count = AprovedRequestMap::<T>::decode_len(patient_account_id).unwrap_or(0) as u32;
+
+			ensure!(
+				count < Self::effective_approval_cap(patient_account_id),
+				Error::<T>::ApprovalCapReached
+			);
+
+			Ok(())
+		}
+
+		fn add_approval(
+			patient_account_id: T::AccountId,
+			requester: T::AccountId,
+			scope: Scope,
+			expires_at: Option<T::BlockNumber>,
+		) -> Result<(), DispatchError> {
+			Self::ensure_below_approval_cap(&patient_account_id)?;
+
+			let expires_at = Self::effective_expiry(&patient_account_id, expires_at)?;
+
+			Self::bounded_push(
+				&patient_account_id,
+				requester.clone(),
+				pallet_doctor::ListKind::Approved,
+			)?;
+
+			TotalActiveApprovals::<T>::mutate(|total| *total = total.saturating_add(1));
+
+			Self::assert_disjoint(&patient_account_id);
+
+			let now = frame_system::Pallet::<T>::block_number();
+
+			ApprovalDetails::<T>::insert(
+				&patient_account_id,
+				&requester,
+				Approval::<T> {
+					scope,
+					expires_at,
+					effective_at: now.saturating_add(T::ApprovalDelay::get()),
+				},
+			);
+
+			let receipt = T::Hashing::hash_of(&(&patient_account_id, &requester, now, scope));
+
+			ApprovalReceipts::<T>::insert(&patient_account_id, &requester, receipt);
+
+			pallet_doctor::Pallet::<T>::add_approved_request(
+				patient_account_id.clone(),
+				requester.clone(),
+				scope,
+			)?;
+
+			let requested_at =
+				RequestedAt::<T>::get(&patient_account_id, &requester).unwrap_or_default();
+
+			Self::check_approval_count(&patient_account_id);
+
+			Self::record_event(&patient_account_id, EventKind::RequestApproved);
+
+			let specialty = Self::specialty_of(&requester);
+
+			Self::deposit_event(Event::RequestApproved {
+				requester,
+				patient_account_id,
+				requested_at,
+				scope,
+				specialty,
+				receipt,
+				expires_at,
+			});
+
+			Ok(())
+		}
+
+		/// Reports the Patient the first time their approved list reaches `ApprovalAlertThreshold`.
+		/// Later approvals are not reported again until the list dropped below the threshold.
+		fn check_approval_count(patient_account_id: &T::AccountId) {
+			let threshold = T::ApprovalAlertThreshold::get();
+			let count = AprovedRequestMap::<T>::decode_len(patient_account_id).unwrap_or(0) as u32;
+
+			if threshold == 0 ||
+				count < threshold ||
+				LastAlertedCount::<T>::get(patient_account_id) >= threshold
+			{
+				return
+			}
+
+			LastAlertedCount::<T>::insert(patient_account_id, count);
+
+			Self::deposit_event(Event::HighApprovalCount {
+				patient_account_id: patient_account_id.clone(),
+				count,
+			});
+		}
+
+		/// Clears the Patient's `HighApprovalCount` report once their approved list is back below
+		/// `ApprovalAlertThreshold`, so reaching it again is reported anew.
+		fn rearm_approval_alert(patient_account_id: &T::AccountId) {
+			let count = AprovedRequestMap::<T>::decode_len(patient_account_id).unwrap_or(0) as u32;
+
+			if count < T::ApprovalAlertThreshold::get() {
+				LastAlertedCount::<T>::remove(patient_account_id);
+			}
+		}
+
+		fn accept(
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+		) -> Result<(), DispatchError> {
+			let (_, scope, expires_at) =
+				PendingDoctorAcceptance::<T>::get(&patient_account_id, &requester)
+					.ok_or(Error::<T>::NoPendingAcceptance)?;
+
+			Self::ensure_not_frozen(&patient_account_id)?;
+
+			ensure!(
+				!BlockList::<T>::get(&patient_account_id, &requester),
+				Error::<T>::RequesterBlocked
+			);
+
+			PendingDoctorAcceptance::<T>::remove(&patient_account_id, &requester);
+
+			Self::add_approval(patient_account_id.clone(), requester.clone(), scope, expires_at)?;
+
+			Self::deposit_event(Event::DoctorAccepted { requester, patient_account_id });
+
+			Ok(())
+		}
+
+		fn decline(
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+		) -> Result<(), DispatchError> {
+			ensure!(
+				PendingDoctorAcceptance::<T>::contains_key(&patient_account_id, &requester),
+				Error::<T>::NoPendingAcceptance
+			);
+
+			PendingDoctorAcceptance::<T>::remove(&patient_account_id, &requester);
+			RequestedAt::<T>::remove(&patient_account_id, &requester);
+
+			pallet_doctor::Pallet::<T>::remove_request(
+				requester.clone(),
+				patient_account_id.clone(),
+			)?;
+
+			Self::record_event(&patient_account_id, EventKind::RequestDeclined);
+
+			Self::deposit_event(Event::DoctorDeclined { requester, patient_account_id });
+
+			Ok(())
+		}
+
+		fn set_frozen_state(
+			patient_account_id: T::AccountId,
+			frozen: bool,
+		) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+
+			// A new freeze discards approvals collected for an earlier one.
+			UnfreezeApprovals::<T>::remove(&patient_account_id);
+
+			Frozen::<T>::insert(&patient_account_id, frozen);
+
+			Self::deposit_event(Event::FrozenStateChanged { patient_account_id, frozen });
+
+			Ok(())
+		}
+
+		fn approve_unfreeze(
+			officer: T::AccountId,
+			patient_account_id: T::AccountId,
+		) -> Result<(), DispatchError> {
+			ensure!(Frozen::<T>::get(&patient_account_id), Error::<T>::NotFrozen);
+
+			let mut approvals = UnfreezeApprovals::<T>::get(&patient_account_id);
+
+			ensure!(!approvals.contains(&officer), Error::<T>::AlreadyApprovedUnfreeze);
+
+			let quorum = T::UnfreezeQuorum::get();
+
+			// The list is bounded by the quorum, so it can only be full once the quorum is met.
+			if approvals.try_push(officer).is_err() || approvals.len() as u32 >= quorum {
+				UnfreezeApprovals::<T>::remove(&patient_account_id);
+
+				Frozen::<T>::insert(&patient_account_id, false);
+
+				Self::deposit_event(Event::FrozenStateChanged {
+					patient_account_id,
+					frozen: false,
+				});
+
+				return Ok(())
+			}
+
+			let approval_count = approvals.len() as u32;
+
+			UnfreezeApprovals::<T>::insert(&patient_account_id, approvals);
+
+			Self::deposit_event(Event::UnfreezeProgress {
+				patient_account_id,
+				approvals: approval_count,
+				quorum,
+			});
+
+			Ok(())
+		}
+
+		fn set_deceased(patient_account_id: T::AccountId) -> Result<(), DispatchError> {
+			let mut patient_data =
+				DataMap::<T>::get(&patient_account_id).ok_or(Error::<T>::NoPatient)?;
+
+			ensure!(!patient_data.deceased, Error::<T>::RecordFrozenDeceased);
+
+			patient_data.deceased = true;
+
+			DataMap::<T>::insert(&patient_account_id, patient_data);
+
+			Self::deposit_event(Event::PatientMarkedDeceased { patient_account_id });
+
+			Ok(())
+		}
+
+		fn acknowledge(patient_account_id: T::AccountId) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+
+			let update_count = UpdateCount::<T>::get(&patient_account_id);
+
+			LastAcknowledged::<T>::insert(&patient_account_id, update_count);
+
+			Self::deposit_event(Event::UpdateAcknowledged { patient_account_id, update_count });
+
+			Ok(())
+		}
+
+		fn set_private_state(
+			patient_account_id: T::AccountId,
+			private: bool,
+		) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+
+			Private::<T>::insert(&patient_account_id, private);
+
+			Self::deposit_event(Event::PrivacyChanged { patient_account_id, private });
+
+			Ok(())
+		}
+
+		fn set_update_reason_requirement(
+			patient_account_id: T::AccountId,
+			required: bool,
+		) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+
+			RequireUpdateReason::<T>::insert(&patient_account_id, required);
+
+			Self::deposit_event(Event::UpdateReasonRequirementChanged {
+				patient_account_id,
+				required,
+			});
+
+			Ok(())
+		}
+
+		fn set_default(
+			patient_account_id: T::AccountId,
+			scope: Scope,
+		) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+
+			DefaultScope::<T>::insert(&patient_account_id, scope);
+
+			Self::deposit_event(Event::DefaultScopeChanged { patient_account_id, scope });
+
+			Ok(())
+		}
+
+		fn set_emergency_contact_hash(
+			patient_account_id: T::AccountId,
+			emergency_contact_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		) -> Result<(), DispatchError> {
+			let mut patient_data =
+				DataMap::<T>::get(&patient_account_id).ok_or(Error::<T>::NoPatient)?;
+
+			Self::ensure_not_frozen(&patient_account_id)?;
+
+			Self::ensure_hash_format(&[emergency_contact_hash.as_ref()])?;
+
+			patient_data.emergency_contact_hash = emergency_contact_hash;
+
+			DataMap::<T>::insert(&patient_account_id, patient_data);
+
+			Self::deposit_event(Event::EmergencyContactSet { patient_account_id });
+
+			Ok(())
+		}
+
+		fn set_backup(
+			patient_account_id: T::AccountId,
+			backup_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		) -> Result<(), DispatchError> {
+			let mut patient_data =
+				DataMap::<T>::get(&patient_account_id).ok_or(Error::<T>::NoPatient)?;
+
+			Self::ensure_not_frozen(&patient_account_id)?;
+
+			Self::ensure_hash_format(&[backup_data_hash.as_ref()])?;
+
+			patient_data.backup_data_hash = backup_data_hash;
+
+			DataMap::<T>::insert(&patient_account_id, patient_data);
+
+			Self::deposit_event(Event::BackupHashSet { patient_account_id });
+
+			Ok(())
+		}
+
+		fn clear_requests(patient_account_id: T::AccountId) -> Result<(), DispatchError> {
+			let doctor_ids = RequestMap::<T>::take(&patient_account_id);
+			let count = doctor_ids.len() as u32;
+
+			for requester in doctor_ids {
+				RequestedAt::<T>::remove(&patient_account_id, &requester);
+
+				// An orphaned request has no doctor side entry left to remove.
+				let _ = pallet_doctor::Pallet::<T>::remove_request(
+					requester,
+					patient_account_id.clone(),
+				);
+			}
+
+			Self::dec_pending_requests(count);
+
+			// An empty queue has nothing declined to show in the recent events.
+			if count > 0 {
+				Self::bump_request_seq(&patient_account_id);
+
+				Self::record_event(&patient_account_id, EventKind::RequestDeclined);
+			}
+
+			Self::deposit_event(Event::AllRequestsCleared { patient_account_id, count });
+
+			Ok(())
+		}
+
+		fn revoke_all(patient_account_id: T::AccountId) -> Result<(), DispatchError> {
+			let doctor_ids = AprovedRequestMap::<T>::take(&patient_account_id);
+			let count = doctor_ids.len() as u32;
+
+			for requester in doctor_ids {
+				ApprovalDetails::<T>::remove(&patient_account_id, &requester);
+				ApprovalReceipts::<T>::remove(&patient_account_id, &requester);
+				PendingRenewals::<T>::remove(&patient_account_id, &requester);
+				RequestedAt::<T>::remove(&patient_account_id, &requester);
+
+				// A missing doctor side entry must not keep the remaining access in place.
+				let _ = pallet_doctor::Pallet::<T>::remove_approved_request(
+					requester.clone(),
+					patient_account_id.clone(),
+				);
+
+				T::OnAccessRevoked::on_revoke(&patient_account_id, &requester);
+			}
+
+			Self::rearm_approval_alert(&patient_account_id);
+
+			Self::dec_active_approvals(count);
+
+			Self::deposit_event(Event::AllAccessRevoked { patient_account_id, count });
+
+			Ok(())
+		}
+
+		/// Removes the Patient side of an approval, e.g. of a Doctor whose record was removed.
+		/// Returns whether the Doctor was approved.
+		fn drop_approval(
+			patient_account_id: &T::AccountId,
+			doctor_account_id: &T::AccountId,
+		) -> bool {
+			let mut doctor_ids = AprovedRequestMap::<T>::get(patient_account_id);
+
+			let ind = match doctor_ids.iter().position(|id| id == doctor_account_id) {
+				Some(ind) => ind,
+				None => return false,
+			};
+
+			doctor_ids.remove(ind);
+
+			AprovedRequestMap::<T>::insert(patient_account_id, doctor_ids);
+
+			Self::rearm_approval_alert(patient_account_id);
+
+			Self::dec_active_approvals(1);
+
+			ApprovalDetails::<T>::remove(patient_account_id, doctor_account_id);
+			ApprovalReceipts::<T>::remove(patient_account_id, doctor_account_id);
+			PendingRenewals::<T>::remove(patient_account_id, doctor_account_id);
+			RequestedAt::<T>::remove(patient_account_id, doctor_account_id);
+
+			T::OnAccessRevoked::on_revoke(patient_account_id, doctor_account_id);
+
+			true
+		}
+
+		/// Removes the Patient side of a pending request or pending acceptance, e.g. of a Doctor
+		/// whose record was removed. Returns whether anything was pending.
+		fn drop_request(
+			patient_account_id: &T::AccountId,
+			doctor_account_id: &T::AccountId,
+		) -> bool {
+			RequestedAt::<T>::remove(patient_account_id, doctor_account_id);
+
+			// The queued request was already counted off when the acceptance was created.
+			if PendingDoctorAcceptance::<T>::take(patient_account_id, doctor_account_id).is_some() {
+				return true
+			}
+
+			let mut doctor_ids = RequestMap::<T>::get(patient_account_id);
+
+			let ind = match doctor_ids.iter().position(|id| id == doctor_account_id) {
+				Some(ind) => ind,
+				None => return false,
+			};
+
+			doctor_ids.remove(ind);
+
+			if doctor_ids.is_empty() {
+				RequestMap::<T>::remove(patient_account_id);
+			} else {
+				RequestMap::<T>::insert(patient_account_id, doctor_ids);
+			}
+
+			Self::bump_request_seq(patient_account_id);
+
+			Self::dec_pending_requests(1);
+
+			true
+		}
+
+		fn force_revoke(
+			by: T::AccountId,
+			patient_account_id: T::AccountId,
+			doctor_account_id: T::AccountId,
+		) -> Result<(), DispatchError> {
+			let mut doctor_ids = AprovedRequestMap::<T>::get(&patient_account_id);
+
+			let ind = doctor_ids
+				.iter()
+				.position(|id| id == &doctor_account_id)
+				.ok_or(Error::<T>::NotApproved)?;
+
+			doctor_ids.remove(ind);
+
+			AprovedRequestMap::<T>::insert(&patient_account_id, doctor_ids);
+
+			Self::dec_active_approvals(1);
+
+			ApprovalDetails::<T>::remove(&patient_account_id, &doctor_account_id);
+			ApprovalReceipts::<T>::remove(&patient_account_id, &doctor_account_id);
+			PendingRenewals::<T>::remove(&patient_account_id, &doctor_account_id);
+			RequestedAt::<T>::remove(&patient_account_id, &doctor_account_id);
+
+			Self::rearm_approval_alert(&patient_account_id);
+
+			// A missing doctor side entry must not keep the access in place.
+			let _ = pallet_doctor::Pallet::<T>::remove_approved_request(
+				doctor_account_id.clone(),
+				patient_account_id.clone(),
+			);
+
+			T::OnAccessRevoked::on_revoke(&patient_account_id, &doctor_account_id);
+
+			Self::deposit_event(Event::AccessForceRevoked {
+				patient_account_id,
+				doctor_account_id,
+				by,
+			});
+
+			Ok(())
+		}
+
+		fn set_scope(
+			patient_account_id: T::AccountId,
+			requester: T::AccountId,
+			scope: Scope,
+		) -> Result<(), DispatchError> {
+			ensure!(
+				AprovedRequestMap::<T>::get(&patient_account_id).contains(&requester),
+				Error::<T>::NotApproved
+			);
+
+			ApprovalDetails::<T>::mutate(&patient_account_id, &requester, |approval| {
+				approval.get_or_insert_with(Default::default).scope = scope;
+			});
+
+			pallet_doctor::Pallet::<T>::set_approved_scope(
+				requester.clone(),
+				patient_account_id.clone(),
+				scope,
+			)?;
+
+			Self::deposit_event(Event::ApprovalScopeChanged {
+				requester,
+				patient_account_id,
+				scope,
+			});
+
+			Ok(())
+		}
+
+		/// Checks that the Doctor currently holds an active read-write approval for the Patient's
+		/// record.
+		pub fn can_update(
+			patient_account_id: &T::AccountId,
+			requester: &T::AccountId,
+		) -> Result<(), DispatchError> {
+			let approved_doctor_ids = AprovedRequestMap::<T>::get(patient_account_id);
+
+			ensure!(
+				approved_doctor_ids.iter().any(|account_id| account_id == requester),
+				Error::<T>::NotApproved
+			);
+
+			Self::ensure_not_frozen(patient_account_id)?;
+
+			let approval =
+				ApprovalDetails::<T>::get(patient_account_id, requester).unwrap_or_default();
+
+			ensure!(approval.scope == Scope::ReadWrite, Error::<T>::ReadOnlyAccess);
+
+			ensure!(
+				frame_system::Pallet::<T>::block_number() >= approval.effective_at,
+				Error::<T>::ApprovalNotYetEffective
+			);
+
+			if let Some(expires_at) = approval.expires_at {
+				ensure!(
+					frame_system::Pallet::<T>::block_number() < expires_at,
+					Error::<T>::ApprovalExpired
+				);
+			}
+
+			Ok(())
+		}
+
+		/// Lists the Patients the Doctor may currently update. The result is bounded by the
+		/// Doctor's approved list, so at most `MaxListLength` entries are returned.
+		pub fn writable_patients(requester: T::AccountId) -> Vec<T::AccountId> {
+			pallet_doctor::Pallet::<T>::approved_request_list(&requester)
+				.into_iter()
+				.filter(|patient_account_id| {
+					Self::can_update(patient_account_id, &requester).is_ok()
+				})
+				.collect()
+		}
+
+		/// Returns whether the Doctor's request to the Patient is still queued. A request that was
+		/// approved, declined or never made is not pending.
+		pub fn is_request_pending(
+			patient_account_id: T::AccountId,
+			doctor_account_id: T::AccountId,
+		) -> bool {
+			RequestMap::<T>::get(&patient_account_id).contains(&doctor_account_id)
+		}
+
+		/// Returns whether the account may read the Patient's record, either through an explicit
+		/// approval or as a member of a care team covering the Patient.
+		pub fn is_approved(patient_account_id: T::AccountId, account: T::AccountId) -> bool {
+			AprovedRequestMap::<T>::get(&patient_account_id).contains(&account) ||
+				ClinicianTeams::<T>::get(&account)
+					.into_iter()
+					.any(|team_id| CareTeams::<T>::get(team_id).contains(&patient_account_id))
+		}
+
+		/// Returns up to `len` Patients of the care team starting at `start`, or nothing if `start`
+		/// is past the end.
+		pub fn care_team_patients_page(team_id: u32, start: u32, len: u32) -> Vec<T::AccountId> {
+			CareTeams::<T>::get(team_id)
+				.into_iter()
+				.skip(start as usize)
+				.take(len as usize)
+				.collect()
+		}
+
+		/// Returns the number of Patients covered by the care team.
+		pub fn care_team_size(team_id: u32) -> u32 {
+			CareTeams::<T>::decode_len(team_id).unwrap_or(0) as u32
+		}
+
+		/// Lists the Patients the account may access through explicit approvals and care teams.
+		/// Meant for off-chain queries only.
+		pub fn accessible_patients(account: T::AccountId) -> Vec<T::AccountId> {
+			let mut patients: Vec<T::AccountId> = Vec::new();
+
+			let team_patients = ClinicianTeams::<T>::get(&account)
+				.into_iter()
+				.flat_map(|team_id| CareTeams::<T>::get(team_id));
+
+			for patient_account_id in pallet_doctor::Pallet::<T>::approved_request_list(&account)
+				.into_iter()
+				.chain(team_patients)
+			{
+				if !patients.contains(&patient_account_id) {
+					patients.push(patient_account_id);
+				}
+			}
+
+			patients
+		}
+
+		/// Counts the Doctor's pending requests, approved Patients and Patients they may currently
+		/// update.
+		pub fn doctor_load(requester: T::AccountId) -> DoctorLoad {
+			DoctorLoad {
+				pending_requests: pallet_doctor::Pallet::<T>::request_list(&requester).len() as u32,
+				approved_patients: pallet_doctor::Pallet::<T>::approved_request_list(&requester)
+					.len() as u32,
+				writable_patients: Self::writable_patients(requester).len() as u32,
+			}
+		}
+
+		/// Collects the protocol wide totals of Patients, Doctors, roles, active approvals and
+		/// pending requests.
+		pub fn protocol_stats() -> ProtocolStats {
+			ProtocolStats {
+				patients: PatientCount::<T>::get(),
+				doctors: pallet_doctor::DoctorCount::<T>::get(),
+				roles: pallet_access::AllRoles::<T>::decode_len().unwrap_or(0) as u32,
+				active_approvals: TotalActiveApprovals::<T>::get(),
+				pending_requests: PendingRequestCount::<T>::get(),
+			}
+		}
+
+		/// Checks that `UpdateCooldown` blocks have passed since the last write to the Patient's
+		/// data, records the current block as the latest write and counts the write.
+		fn note_write(patient_account_id: &T::AccountId) -> Result<(), DispatchError> {
+			let now = frame_system::Pallet::<T>::block_number();
+
+			if let Some(last) = LastUpdateBlock::<T>::get(patient_account_id) {
+				ensure!(
+					now.saturating_sub(last) >= T::UpdateCooldown::get(),
+					Error::<T>::UpdateTooSoon
+				);
+			}
+
+			LastUpdateBlock::<T>::insert(patient_account_id, now);
+
+			UpdateCount::<T>::mutate(patient_account_id, |count| *count = count.saturating_add(1));
+
+			Ok(())
+		}
+
+		/// Counts an approval or revocation by the Patient against `MaxApprovalActionsPerWindow`,
+		/// starting a new window once `ApprovalActionWindow` blocks have passed.
+		fn note_approval_action(patient_account_id: &T::AccountId) -> Result<(), DispatchError> {
+			let now = frame_system::Pallet::<T>::block_number();
+
+			let (mut window_start, mut count) = ApprovalActionCount::<T>::get(patient_account_id);
+
+			if count == 0 || now.saturating_sub(window_start) >= T::ApprovalActionWindow::get() {
+				window_start = now;
+				count = 0;
+			}
+
+			ensure!(
+				count < T::MaxApprovalActionsPerWindow::get(),
+				Error::<T>::TooManyApprovalActions
+			);
+
+			ApprovalActionCount::<T>::insert(patient_account_id, (window_start, count + 1));
+
+			Ok(())
+		}
+
+		fn note_previous_hash(
+			patient_account_id: &T::AccountId,
+			old: &Option<BoundedVec<u8, T::MaxHashLength>>,
+			new: &Option<BoundedVec<u8, T::MaxHashLength>>,
+		) {
+			if old == new {
+				return
+			}
+
+			if let Some(previous) = old {
+				PreviousDataHash::<T>::insert(patient_account_id, previous);
+			}
+		}
+
+		/// Returns whether the account already used the key, remembering it otherwise.
+		fn key_seen(requester: &T::AccountId, key: [u8; 16]) -> bool {
+			SeenKeys::<T>::mutate(requester, |keys| {
+				if keys.contains(&key) {
+					return true
+				}
+
+				if keys.try_push(key).is_err() && !keys.is_empty() {
+					keys.remove(0);
+					let _ = keys.try_push(key);
+				}
+
+				false
+			})
+		}
+
+		/// Writes the Patient's data, returning whether anything was written. An update identical
+		/// to the stored data is skipped or rejected, depending on `RejectUnchangedUpdates`.
+		fn update(
+			patient_account_id: T::AccountId,
+			requester: T::AccountId,
+			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+			content_type: [u8; 16],
+			reason_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		) -> Result<bool, DispatchError> {
+			Self::can_update(&patient_account_id, &requester)?;
+
+			ensure!(
+				reason_hash.is_some() || !RequireUpdateReason::<T>::get(&patient_account_id),
+				Error::<T>::ReasonRequired
+			);
+
+			if let Some(stored) = DataMap::<T>::get(&patient_account_id) {
+				if stored.data_hash == data_hash &&
+					stored.personal_data_hash == personal_data_hash &&
+					stored.loinc_code == loinc_code &&
+					stored.content_type == content_type
+				{
+					ensure!(!T::RejectUnchangedUpdates::get(), Error::<T>::NoChange);
+
+					return Ok(false)
+				}
+			}
+
+			Self::note_write(&patient_account_id)?;
+
+			Self::ensure_hash_format(&[data_hash.as_ref(), personal_data_hash.as_ref()])?;
+
+			ensure!(
+				!T::RejectDuplicateHashes::get() ||
+					data_hash.is_none() ||
+					data_hash != personal_data_hash,
+				Error::<T>::DuplicateHash
+			);
+
+			let mut patient_data = DataMap::<T>::get(&patient_account_id).unwrap_or_default();
+
+			Self::note_previous_hash(&patient_account_id, &patient_data.data_hash, &data_hash);
+
+			patient_data.data_hash = data_hash;
+			patient_data.personal_data_hash = personal_data_hash;
+			patient_data.loinc_code = loinc_code;
+			patient_data.content_type = content_type;
+
+			DataMap::<T>::insert(&patient_account_id, patient_data);
+
+			let requested_at = RequestedAt::<T>::take(&patient_account_id, &requester);
+
+			Self::record_event(&patient_account_id, EventKind::DataUpdated);
+
+			Self::deposit_event(Event::DataUpdated {
+				requester,
+				patient_account_id,
+				requested_at,
+				content_type,
+				reason_hash,
+			});
+
+			Ok(true)
+		}
+
+		fn swap(
+			patient_account_id: T::AccountId,
+			requester: T::AccountId,
+			reason_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		) -> Result<(), DispatchError> {
+			let patient_data =
+				DataMap::<T>::get(&patient_account_id).ok_or(Error::<T>::NoPatient)?;
+
+			// A swap is an update with the two hashes exchanged, so it goes through the same checks.
+			Self::update(
+				patient_account_id,
+				requester,
+				patient_data.personal_data_hash,
+				patient_data.data_hash,
+				patient_data.loinc_code,
+				patient_data.content_type,
+				reason_hash,
+			)
+		}
+
+		/// Bundles the Patient's record, pending requests, approvals and flags for off-chain
+		/// backup. Returns `None` for unregistered accounts.
+		pub fn export_patient(patient_account_id: T::AccountId) -> Option<PatientExport<T>> {
+			let record = DataMap::<T>::get(&patient_account_id)?;
+
+			let approvals = AprovedRequestMap::<T>::get(&patient_account_id)
+				.into_iter()
+				.map(|requester| {
+					let approval = ApprovalDetails::<T>::get(&patient_account_id, &requester)
+						.unwrap_or_default();
+					(requester, approval)
+				})
+				.collect();
+
+			Some(PatientExport {
+				record,
+				pending_requests: RequestMap::<T>::get(&patient_account_id).into_inner(),
+				approvals,
+				frozen: Frozen::<T>::get(&patient_account_id),
+				private: Private::<T>::get(&patient_account_id),
+			})
+		}
+
+		/// Returns the scope and expiry of the Doctor's approval, or `None` if the Doctor is not
+		/// approved by the Patient.
+		pub fn approval_details(
+			patient_account_id: T::AccountId,
+			requester: T::AccountId,
+		) -> Option<(Scope, Option<T::BlockNumber>)> {
+			if !AprovedRequestMap::<T>::get(&patient_account_id).contains(&requester) {
+				return None
+			}
+
+			let approval =
+				ApprovalDetails::<T>::get(&patient_account_id, &requester).unwrap_or_default();
+
+			Some((approval.scope, approval.expires_at))
+		}
+
+		/// Returns whether the Patient's data was written after `block`.
+		pub fn has_changed_since(patient_account_id: T::AccountId, block: T::BlockNumber) -> bool {
+			LastUpdateBlock::<T>::get(&patient_account_id)
+				.map_or(false, |updated_at| updated_at > block)
+		}
+
+		/// Returns the data hash replaced by the Patient's latest change of it, if any.
+		pub fn previous_hash(
+			patient_account_id: T::AccountId,
+		) -> Option<BoundedVec<u8, T::MaxHashLength>> {
+			PreviousDataHash::<T>::get(&patient_account_id)
+		}
+
+		/// Returns for each account whether it is a registered Patient. Returns `None` if more
+		/// than `MaxBatch` accounts are given.
+		pub fn which_registered(accounts: Vec<T::AccountId>) -> Option<Vec<bool>> {
+			if accounts.len() > T::MaxBatch::get() as usize {
+				return None
+			}
+
+			Some(accounts.iter().map(DataMap::<T>::contains_key).collect())
+		}
+
+		/// Returns whether the Patient's data was written after their last acknowledgement.
+		pub fn has_unacknowledged(patient_account_id: T::AccountId) -> bool {
+			UpdateCount::<T>::get(&patient_account_id) >
+				LastAcknowledged::<T>::get(&patient_account_id)
+		}
+
+		/// Returns the (Patient, Doctor) approval pairs of the given Patients. Only the first
+		/// `MaxBatch` Patients are read, each approving at most `MaxListLength` Doctors, which
+		/// bounds the result to `MaxBatch * MaxListLength` pairs. Meant for off-chain queries
+		/// only.
+		pub fn approval_edges(patients: Vec<T::AccountId>) -> Vec<(T::AccountId, T::AccountId)> {
+			patients
+				.into_iter()
+				.take(T::MaxBatch::get() as usize)
+				.flat_map(|patient_account_id| {
+					AprovedRequestMap::<T>::get(&patient_account_id)
+						.into_iter()
+						.map(move |requester| (patient_account_id.clone(), requester))
+				})
+				.collect()
+		}
+
+		/// Returns the Doctors the Patient approved with the given scope, in the order they were
+		/// approved. Approvals without stored terms count as read-write.
+		pub fn approved_by_scope(
+			patient_account_id: T::AccountId,
+			scope: Scope,
+		) -> Vec<T::AccountId> {
+			AprovedRequestMap::<T>::get(&patient_account_id)
+				.into_iter()
+				.filter(|requester| {
+					ApprovalDetails::<T>::get(&patient_account_id, requester)
+						.unwrap_or_default()
+						.scope == scope
+				})
+				.collect()
+		}
+
+		/// Returns the Doctors the Patient approved with each Doctor's personal data hash, in the
+		/// order they were approved. Bounded by `MaxListLength`.
+		pub fn approved_doctor_profiles(
+			patient_account_id: T::AccountId,
+		) -> Vec<(T::AccountId, Option<BoundedVec<u8, T::MaxHashLength>>)> {
+			AprovedRequestMap::<T>::get(&patient_account_id)
+				.into_iter()
+				.map(|doctor_account_id| {
+					let profile = pallet_doctor::DataMap::<T>::get(&doctor_account_id)
+						.and_then(|doctor| doctor.personal_data_hash);
+
+					(doctor_account_id, profile)
+				})
+				.collect()
+		}
+
+		/// Returns a hash of the Patient's consent state, i.e. every approved Doctor with the terms
+		/// of their approval, sorted by Doctor. It only changes when an approval is granted,
+		/// revoked or its terms change.
+		pub fn consent_fingerprint(patient_account_id: T::AccountId) -> T::Hash {
+			let mut doctor_ids = AprovedRequestMap::<T>::get(&patient_account_id).into_inner();
+			doctor_ids.sort();
+
+			let entries: Vec<(T::AccountId, Approval<T>)> = doctor_ids
+				.into_iter()
+				.map(|requester| {
+					let approval = ApprovalDetails::<T>::get(&patient_account_id, &requester)
+						.unwrap_or_default();
+					(requester, approval)
+				})
+				.collect();
+
+			T::Hashing::hash_of(&entries)
+		}
+
+		/// Returns whether no Doctor is both in the Patient's request list and approved list.
+		pub fn lists_disjoint(patient_account_id: &T::AccountId) -> bool {
+			let approved_doctor_ids = AprovedRequestMap::<T>::get(patient_account_id);
+
+			!RequestMap::<T>::get(patient_account_id)
+				.iter()
+				.any(|requester| approved_doctor_ids.contains(requester))
+		}
+
+		/// Panics in debug builds if a Doctor is both requested and approved by the Patient.
+		fn assert_disjoint(patient_account_id: &T::AccountId) {
+			debug_assert!(
+				Self::lists_disjoint(patient_account_id),
+				"Doctor is both requested and approved by the same patient"
+			);
+		}
+
+		/// Checks that pending requests are held against registered Patients and that both the
+		/// pending and approved lists agree with their mirror in the Doctor pallet. A Doctor side
+		/// request may outlive the Patient side one while the Doctor's acceptance is pending. Also
+		/// checks that every stored scope decodes.
+		#[cfg(feature = "try-runtime")]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			for (patient_account_id, doctor_ids) in RequestMap::<T>::iter() {
+				ensure!(
+					DataMap::<T>::contains_key(&patient_account_id),
+					"RequestMap holds requests for an unregistered patient"
+				);
+
+				ensure!(
+					Self::lists_disjoint(&patient_account_id),
+					"RequestMap lists a doctor that is already approved"
+				);
+
+				for requester in doctor_ids.iter() {
+					ensure!(
+						pallet_doctor::Pallet::<T>::request_list(requester)
+							.contains(&patient_account_id),
+						"RequestMap entry has no matching request on the doctor side"
+					);
+				}
+			}
+
+			for (doctor_account_id, patient_ids) in pallet_doctor::RequestMap::<T>::iter() {
+				for patient_account_id in patient_ids.iter() {
+					ensure!(
+						RequestMap::<T>::get(patient_account_id).contains(&doctor_account_id) ||
+							PendingDoctorAcceptance::<T>::contains_key(
+								patient_account_id,
+								&doctor_account_id
+							),
+						"Doctor RequestMap entry has no matching request on the patient side"
+					);
+				}
+			}
+
+			for (patient_account_id, doctor_ids) in AprovedRequestMap::<T>::iter() {
+				for requester in doctor_ids.iter() {
+					ensure!(
+						pallet_doctor::DataMap::<T>::contains_key(requester),
+						"AprovedRequestMap approves a doctor without a record"
+					);
+					ensure!(
+						pallet_doctor::Pallet::<T>::approved_request_list(requester)
+							.contains(&patient_account_id),
+						"AprovedRequestMap entry has no matching approval on the doctor side"
+					);
+				}
+			}
+
+			for (doctor_account_id, patient_ids) in pallet_doctor::AprovedRequestMap::<T>::iter() {
+				for patient_account_id in patient_ids.iter() {
+					ensure!(
+						AprovedRequestMap::<T>::get(patient_account_id).contains(&doctor_account_id),
+						"Doctor AprovedRequestMap entry has no matching approval on the patient side"
+					);
+				}
+			}
+
+			// Entries failing to decode are skipped by `iter`, e.g. a scope of an unknown variant.
+			ensure!(
+				ApprovalDetails::<T>::iter_keys().count() == ApprovalDetails::<T>::iter().count(),
+				"ApprovalDetails holds a scope of an unknown variant"
+			);
+			ensure!(
+				PendingDoctorAcceptance::<T>::iter_keys().count() ==
+					PendingDoctorAcceptance::<T>::iter().count(),
+				"PendingDoctorAcceptance holds a scope of an unknown variant"
+			);
+			ensure!(
+				DefaultScope::<T>::iter_keys().count() == DefaultScope::<T>::iter().count(),
+				"DefaultScope holds a scope of an unknown variant"
+			);
+			ensure!(
+				SpecialtyConsent::<T>::iter_keys().count() == SpecialtyConsent::<T>::iter().count(),
+				"SpecialtyConsent holds a scope of an unknown variant"
+			);
+
+			Ok(())
+		}
+
+		fn set_blocked(
+			patient_account_id: T::AccountId,
+			doctor_account_id: T::AccountId,
+			blocked: bool,
+		) -> Result<(), DispatchError> {
+			if blocked {
+				BlockList::<T>::insert(&patient_account_id, &doctor_account_id, true);
+			} else {
+				BlockList::<T>::remove(&patient_account_id, &doctor_account_id);
+			}
+
+			Self::deposit_event(Event::BlockListChanged {
+				patient_account_id,
+				doctor_account_id,
+				blocked,
+			});
+
+			Ok(())
+		}
+
+		fn confirm(
+			patient_account_id: T::AccountId,
+			requester: T::AccountId,
+		) -> Result<(), DispatchError> {
+			ensure!(
+				Self::is_approved(patient_account_id.clone(), requester.clone()),
+				Error::<T>::NotApproved
+			);
+
+			Self::deposit_event(Event::AlreadyHasAccess {
+				doctor_account_id: requester,
+				patient_account_id,
+			});
+
+			Ok(())
+		}
+
+		/// Ends every pending request, pending acceptance and approval of the Patient, then moves
+		/// the record to `Tombstones`.
+		fn delete_record(patient_account_id: T::AccountId) -> Result<(), DispatchError> {
+			let record = DataMap::<T>::get(&patient_account_id).ok_or(Error::<T>::NoPatient)?;
+
+			// Frozen records are under a legal hold and can not be deleted.
+			ensure!(!Frozen::<T>::get(&patient_account_id), Error::<T>::RecordFrozen);
+
+			Self::clear_requests(patient_account_id.clone())?;
+
+			Self::revoke_all(patient_account_id.clone())?;
+
+			for (requester, _) in PendingDoctorAcceptance::<T>::drain_prefix(&patient_account_id) {
+				RequestedAt::<T>::remove(&patient_account_id, &requester);
+
+				let _ = pallet_doctor::Pallet::<T>::remove_request(
+					requester,
+					patient_account_id.clone(),
+				);
+			}
+
+			DataMap::<T>::remove(&patient_account_id);
+
+			Tombstones::<T>::insert(
+				&patient_account_id,
+				(frame_system::Pallet::<T>::block_number(), record),
+			);
+
+			Self::dec_patient_count();
+
+			Self::deposit_event(Event::PatientRecordDeleted { patient_account_id });
+
+			Ok(())
+		}
+
+		fn restore(patient_account_id: T::AccountId) -> Result<(), DispatchError> {
+			ensure!(
+				!DataMap::<T>::contains_key(&patient_account_id),
+				Error::<T>::AlreadyRegistered
+			);
+
+			let (_, record) =
+				Tombstones::<T>::take(&patient_account_id).ok_or(Error::<T>::NoTombstone)?;
+
+			DataMap::<T>::insert(&patient_account_id, record);
+
+			PatientCount::<T>::mutate(|count| *count = count.saturating_add(1));
+
+			Self::deposit_event(Event::RecordRestored { patient_account_id });
+
+			Ok(())
+		}
+
+		/// Purges tombstones older than `RetentionPeriod` for as long as `remaining_weight` allows,
+		/// along with the state kept next to the record. Each call continues after the last
+		/// tombstone the previous one checked and starts over once all were checked.
+		fn purge_tombstones(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			let read = T::DbWeight::get().reads(1);
+			// The tombstone and the maps cleared by `remove_auxiliary_state`.
+			let purge = T::DbWeight::get().writes(21);
+
+			// Reading and updating the cursor.
+			let mut used = T::DbWeight::get().reads_writes(1, 1);
+
+			if used.saturating_add(read).saturating_add(purge).any_gt(remaining_weight) {
+				return Weight::zero()
+			}
+
+			let mut expired = Vec::new();
+			let mut last_checked = None;
+			let mut exhausted = true;
+
+			// Every care team is read and written once to drop the purged Patients from it.
+			let mut teams_weighed = false;
+
+			let tombstones = match PurgeCursor::<T>::get() {
+				Some(cursor) => Tombstones::<T>::iter_from(cursor.into_inner()),
+				None => Tombstones::<T>::iter(),
+			};
+
+			for (patient_account_id, (deleted_at, _)) in tombstones {
+				let expires = now.saturating_sub(deleted_at) >= T::RetentionPeriod::get();
+
+				let mut needed = used.saturating_add(read);
+				if expires {
+					needed = needed.saturating_add(purge);
+				}
+				if expires && !teams_weighed {
+					let teams = CareTeams::<T>::iter_keys().count() as u64;
+					needed = needed.saturating_add(T::DbWeight::get().reads_writes(teams, teams));
+				}
+
+				if needed.any_gt(remaining_weight) {
+					exhausted = false;
+					break
+				}
+				used = needed;
+
+				if expires {
+					teams_weighed = true;
+					expired.push(patient_account_id.clone());
+				}
+
+				last_checked = Some(patient_account_id);
+			}
+
+			match last_checked {
+				_ if exhausted => PurgeCursor::<T>::kill(),
+				Some(last) => PurgeCursor::<T>::put(BoundedVec::truncate_from(
+					Tombstones::<T>::hashed_key_for(last),
+				)),
+				None => (),
+			}
+
+			if !expired.is_empty() {
+				Self::leave_care_teams(&expired);
+			}
+
+			for patient_account_id in expired {
+				Tombstones::<T>::remove(&patient_account_id);
+
+				Self::remove_auxiliary_state(&patient_account_id);
+
+				Self::deposit_event(Event::RecordPurged { patient_account_id });
+			}
+
+			used
+		}
+
+		/// Removes the Patients from every care team covering them.
+		fn leave_care_teams(patient_account_ids: &[T::AccountId]) {
+			for team_id in CareTeams::<T>::iter_keys().collect::<Vec<_>>() {
+				CareTeams::<T>::mutate(team_id, |patients| {
+					patients.retain(|patient| !patient_account_ids.contains(patient))
+				});
+			}
+		}
+
+		/// Removes the clinician from every care team they are a member of.
+		fn leave_team_memberships(member: &T::AccountId) {
+			for team_id in ClinicianTeams::<T>::take(member) {
+				TeamMembers::<T>::mutate_exists(team_id, |members| {
+					if let Some(list) = members {
+						list.retain(|account| account != member);
+
+						if list.is_empty() {
+							*members = None;
+						}
+					}
+				});
+			}
+		}
+
+		/// Removes the settings, counters and logs kept for the Patient next to their record.
+		/// Requests, acceptances, approvals and care teams are left to the caller.
+		fn remove_auxiliary_state(patient_account_id: &T::AccountId) {
+			RecentEvents::<T>::remove(patient_account_id);
+			LastUpdateBlock::<T>::remove(patient_account_id);
+			ApprovalActionCount::<T>::remove(patient_account_id);
+			UpdateCount::<T>::remove(patient_account_id);
+			PreviousDataHash::<T>::remove(patient_account_id);
+			LastAcknowledged::<T>::remove(patient_account_id);
+			LastAlertedCount::<T>::remove(patient_account_id);
+			SeenKeys::<T>::remove(patient_account_id);
+			Private::<T>::remove(patient_account_id);
+			RequireUpdateReason::<T>::remove(patient_account_id);
+			DefaultScope::<T>::remove(patient_account_id);
+			UnfreezeApprovals::<T>::remove(patient_account_id);
+			RecordLayout::<T>::remove(patient_account_id);
+			AccessLog::<T>::remove(patient_account_id);
+			SpecialtyConsent::<T>::remove(patient_account_id);
+			RequestSeq::<T>::remove(patient_account_id);
+			ApprovalCap::<T>::remove(patient_account_id);
+			MaxApprovalDuration::<T>::remove(patient_account_id);
+
+			let _ = BlockList::<T>::clear_prefix(patient_account_id, u32::MAX, None);
+
+			for (doctor_account_id, ()) in FeedbackGiven::<T>::drain_prefix(patient_account_id) {
+				DoctorFeedback::<T>::remove(&doctor_account_id, patient_account_id);
+				FeedbackCount::<T>::mutate(&doctor_account_id, |count| {
+					*count = count.saturating_sub(1)
+				});
+			}
+		}
+
+		/// Rewrites a record stored in an older `Patients` layout in the current one and marks it
+		/// as migrated. Records already in the current layout are only marked, records matching
+		/// no layout are left unmarked.
+		fn migrate_record(patient_account_id: &T::AccountId) {
+			if RecordLayout::<T>::get(patient_account_id) >= PATIENTS_LAYOUT {
+				return
+			}
+
+			let raw = match frame_support::storage::unhashed::get_raw(
+				&DataMap::<T>::hashed_key_for(patient_account_id),
+			) {
+				Some(raw) => raw,
+				None => return,
+			};
+
+			if Patients::<T>::decode_all(&mut &raw[..]).is_err() {
+				match Self::decode_old_record(&raw) {
+					Some(record) => DataMap::<T>::insert(patient_account_id, record),
+					None => return,
+				}
+			}
+
+			RecordLayout::<T>::insert(patient_account_id, PATIENTS_LAYOUT);
+		}
+
+		/// Decodes a record stored in one of the older `Patients` layouts. Each layout only appends
+		/// fields to the one before, so a record decodes without leftover input as its own layout
+		/// alone.
+		fn decode_old_record(raw: &[u8]) -> Option<Patients<T>> {
+			PatientsV3::<T>::decode_all(&mut &raw[..])
+				.map(|old| Patients {
+					personal_data_hash: old.personal_data_hash,
+					data_hash: old.data_hash,
+					loinc_code: old.loinc_code,
+					backup_data_hash: old.backup_data_hash,
+					deceased: old.deceased,
+					content_type: old.content_type,
+					emergency_contact_hash: None,
+				})
+				.or_else(|_| {
+					PatientsV2::<T>::decode_all(&mut &raw[..]).map(|old| Patients {
+						personal_data_hash: old.personal_data_hash,
+						data_hash: old.data_hash,
+						loinc_code: old.loinc_code,
+						backup_data_hash: old.backup_data_hash,
+						deceased: old.deceased,
+						content_type: [0u8; 16],
+						emergency_contact_hash: None,
+					})
+				})
+				.or_else(|_| {
+					PatientsV1::<T>::decode_all(&mut &raw[..]).map(|old| Patients {
+						personal_data_hash: old.personal_data_hash,
+						data_hash: old.data_hash,
+						loinc_code: old.loinc_code,
+						backup_data_hash: old.backup_data_hash,
+						deceased: false,
+						content_type: [0u8; 16],
+						emergency_contact_hash: None,
+					})
+				})
+				.or_else(|_| {
+					PatientsV0::<T>::decode_all(&mut &raw[..]).map(|old| Patients {
+						personal_data_hash: old.personal_data_hash,
+						data_hash: old.data_hash,
+						loinc_code: old.loinc_code,
+						backup_data_hash: None,
+						deceased: false,
+						content_type: [0u8; 16],
+						emergency_contact_hash: None,
+					})
+				})
+				.ok()
+		}
+
+		/// Migrates up to `limit` records following the cursor and moves the cursor past them.
+		fn migrate_batch(limit: u32) -> Result<(), DispatchError> {
+			let limit = limit.min(T::MaxBatch::get());
+
+			if limit == 0 {
+				return Ok(())
+			}
+
+			let (patient_ids, mut done): (Vec<T::AccountId>, u32) =
+				match MigrationCursor::<T>::get() {
+					Some((cursor, done)) => (
+						DataMap::<T>::iter_keys_from(cursor.into_inner())
+							.take(limit as usize)
+							.collect(),
+						done,
+					),
+					None => (DataMap::<T>::iter_keys().take(limit as usize).collect(), 0),
+				};
+
+			for patient_account_id in patient_ids.iter() {
+				Self::migrate_record(patient_account_id);
+			}
+
+			done = done.saturating_add(patient_ids.len() as u32);
+
+			match patient_ids.last() {
+				Some(last) if patient_ids.len() as u32 == limit => {
+					let cursor = BoundedVec::truncate_from(DataMap::<T>::hashed_key_for(last));
+					MigrationCursor::<T>::put((cursor, done));
+
+					let remaining = PatientCount::<T>::get().saturating_sub(done);
+					Self::deposit_event(Event::MigrationProgress { done, remaining });
+				},
+				_ => {
+					MigrationCursor::<T>::kill();
+
+					Self::deposit_event(Event::MigrationProgress { done, remaining: 0 });
+				},
+			}
+
+			Ok(())
+		}
+
+		fn add_prefix(prefix: BoundedVec<u8, T::MaxHashLength>) -> Result<(), DispatchError> {
+			AllowedHashPrefixes::<T>::try_mutate(|allowed| {
+				ensure!(!allowed.contains(&prefix), Error::<T>::PrefixAlreadyAllowed);
+
+				allowed.try_push(prefix.clone()).map_err(|_| Error::<T>::TooManyPrefixes)
+			})?;
+
+			Self::deposit_event(Event::AllowedPrefixAdded { prefix });
+
+			Ok(())
+		}
+
+		fn remove_prefix(prefix: BoundedVec<u8, T::MaxHashLength>) -> Result<(), DispatchError> {
+			AllowedHashPrefixes::<T>::try_mutate(|allowed| {
+				let ind = allowed
+					.iter()
+					.position(|allowed_prefix| allowed_prefix == &prefix)
+					.ok_or(Error::<T>::PrefixNotAllowed)?;
+
+				allowed.remove(ind);
+
+				Ok::<(), Error<T>>(())
+			})?;
+
+			Self::deposit_event(Event::AllowedPrefixRemoved { prefix });
+
+			Ok(())
+		}
+
+		fn attest(
+			doctor_account_id: T::AccountId,
+			patient_account_id: T::AccountId,
+			data_hash: BoundedVec<u8, T::MaxHashLength>,
+			at: T::BlockNumber,
+			signature: T::OffchainSignature,
+		) -> Result<(), DispatchError> {
+			ensure!(
+				AprovedRequestMap::<T>::get(&patient_account_id).contains(&doctor_account_id),
+				Error::<T>::NotApproved
+			);
+
+			let now = frame_system::Pallet::<T>::block_number();
+
+			if let Some(expires_at) =
+				ApprovalDetails::<T>::get(&patient_account_id, &doctor_account_id)
+					.and_then(|approval| approval.expires_at)
+			{
+				ensure!(now < expires_at, Error::<T>::ApprovalExpired);
+			}
+
+			ensure!(at <= now, Error::<T>::AttestationInFuture);
+
+			ensure!(
+				now.saturating_sub(at) <= T::MaxAttestationAge::get(),
+				Error::<T>::AttestationTooOld
+			);
+
+			let payload = (&patient_account_id, &data_hash, at).encode();
+
+			ensure!(
+				signature.verify(&payload[..], &doctor_account_id),
+				Error::<T>::InvalidSignature
+			);
+
+			let attestation = T::Hashing::hash_of(&(&payload, &signature));
+
+			Self::append_access_log(
+				&patient_account_id,
+				AccessLogEntry {
+					signer: doctor_account_id.clone(),
+					on_behalf_of: None,
+					at,
+					hash: attestation,
+				},
+			);
+
+			if T::EmitViewEvents::get() {
+				Self::deposit_event(Event::ViewAttested {
+					patient_account_id,
+					doctor_account_id,
+					attestation,
+				});
+			}
+
+			Ok(())
+		}
+
+		/// Appends an entry to the Patient's access log, evicting and reporting the oldest entry
+		/// once the log is full.
+		fn append_access_log(patient_account_id: &T::AccountId, entry: AccessLogEntry<T>) {
+			let evicted_entry =
+				AccessLog::<T>::mutate(patient_account_id, |log| match log.try_push(entry) {
+					Ok(()) => None,
+					Err(_) if log.is_empty() => None,
+					Err(entry) => {
+						let evicted_entry = log.remove(0);
+						let _ = log.try_push(entry);
+						Some(evicted_entry)
+					},
+				});
+
+			if let Some(evicted_entry) = evicted_entry {
+				Self::deposit_event(Event::AccessLogEntryEvicted {
+					patient_account_id: patient_account_id.clone(),
+					evicted_entry,
+				});
+			}
+		}
+
+		/// Moves the approvals, pending requests, pending acceptances, access log and feedback of
+		/// `source` to `target` and removes `source` with its settings. Doctors already known to `target`
+		/// keep their existing entry, entries of Doctors `target` blocked are dropped. Fails with
+		/// `ApprovalCapReached` if the moved approvals exceed the cap of `target`.
+		fn merge(
+			source: T::AccountId,
+			target: T::AccountId,
+			force: bool,
+		) -> Result<(), DispatchError> {
+			ensure!(source != target, Error::<T>::MergeConflict);
+
+			let source_record = DataMap::<T>::get(&source).ok_or(Error::<T>::NoPatient)?;
+			let mut target_record = DataMap::<T>::get(&target).ok_or(Error::<T>::NoPatient)?;
+
+			Self::ensure_not_frozen(&source)?;
+			Self::ensure_not_frozen(&target)?;
+
+			let has_data = |record: &Patients<T>| {
+				record.data_hash.is_some() || record.personal_data_hash.is_some()
+			};
+
+			ensure!(
+				force || !(has_data(&source_record) && has_data(&target_record)),
+				Error::<T>::MergeConflict
+			);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let cap = Self::effective_approval_cap(&target);
+			let mut approved_doctor_ids = AprovedRequestMap::<T>::get(&target);
+
+			for requester in AprovedRequestMap::<T>::take(&source) {
+				let approval = ApprovalDetails::<T>::take(&source, &requester).unwrap_or_default();
+				ApprovalReceipts::<T>::remove(&source, &requester);
+				PendingRenewals::<T>::remove(&source, &requester);
+				RequestedAt::<T>::remove(&source, &requester);
+
+				let known = approved_doctor_ids.contains(&requester);
+
+				if known || BlockList::<T>::get(&target, &requester) {
+					Self::dec_active_approvals(1);
+
+					let _ = pallet_doctor::Pallet::<T>::remove_approved_request(
+						requester.clone(),
+						source.clone(),
+					);
+
+					// Doctors `target` blocked lose the access they held through `source`.
+					if !known {
+						T::OnAccessRevoked::on_revoke(&source, &requester);
+					}
+					continue
+				}
+
+				ensure!((approved_doctor_ids.len() as u32) < cap, Error::<T>::ApprovalCapReached);
+
+				approved_doctor_ids
+					.try_push(requester.clone())
+					.map_err(|_| Error::<T>::MaxListLengthReached)?;
+
+				let receipt = T::Hashing::hash_of(&(&target, &requester, now, approval.scope));
+
+				ApprovalDetails::<T>::insert(&target, &requester, approval);
+				ApprovalReceipts::<T>::insert(&target, &requester, receipt);
+
+				pallet_doctor::Pallet::<T>::replace_patient(
+					requester,
+					source.clone(),
+					target.clone(),
+					pallet_doctor::ListKind::Approved,
+				);
+			}
+
+			let mut doctor_ids = RequestMap::<T>::get(&target);
+			let stored = doctor_ids.len();
+
+			// Requests to `target` of Doctors approved through `source` are settled by the approval.
+			doctor_ids.retain(|requester| {
+				if !approved_doctor_ids.contains(requester) {
+					return true
+				}
+
+				RequestedAt::<T>::remove(&target, requester);
+
+				let _ =
+					pallet_doctor::Pallet::<T>::remove_request(requester.clone(), target.clone());
+
+				false
+			});
+
+			let settled = (stored - doctor_ids.len()) as u32;
+			let retained = doctor_ids.len();
+
+			Self::dec_pending_requests(settled);
+
+			let source_doctor_ids = RequestMap::<T>::take(&source);
+
+			if !source_doctor_ids.is_empty() {
+				Self::bump_request_seq(&source);
+			}
+
+			for requester in source_doctor_ids {
+				let requested_at = RequestedAt::<T>::take(&source, &requester);
+
+				if doctor_ids.contains(&requester) ||
+					approved_doctor_ids.contains(&requester) ||
+					PendingDoctorAcceptance::<T>::contains_key(&target, &requester) ||
+					BlockList::<T>::get(&target, &requester)
+				{
+					Self::dec_pending_requests(1);
+
+					let _ = pallet_doctor::Pallet::<T>::remove_request(requester, source.clone());
+					continue
+				}
+
+				doctor_ids
+					.try_push(requester.clone())
+					.map_err(|_| Error::<T>::MaxListLengthReached)?;
+
+				if let Some(requested_at) = requested_at {
+					RequestedAt::<T>::insert(&target, &requester, requested_at);
+				}
+
+				pallet_doctor::Pallet::<T>::replace_patient(
+					requester,
+					source.clone(),
+					target.clone(),
+					pallet_doctor::ListKind::Request,
+				);
+			}
+
+			for (requester, pending) in PendingDoctorAcceptance::<T>::drain_prefix(&source) {
+				if approved_doctor_ids.contains(&requester) ||
+					doctor_ids.contains(&requester) ||
+					BlockList::<T>::get(&target, &requester) ||
+					PendingDoctorAcceptance::<T>::contains_key(&target, &requester)
+				{
+					let _ = pallet_doctor::Pallet::<T>::remove_request(requester, source.clone());
+					continue
+				}
+
+				PendingDoctorAcceptance::<T>::insert(&target, &requester, pending);
+
+				pallet_doctor::Pallet::<T>::replace_patient(
+					requester,
+					source.clone(),
+					target.clone(),
+					pallet_doctor::ListKind::Request,
+				);
+			}
+
+			if settled > 0 || doctor_ids.len() > retained {
+				Self::bump_request_seq(&target);
+			}
+
+			AprovedRequestMap::<T>::insert(&target, approved_doctor_ids);
+			RequestMap::<T>::insert(&target, doctor_ids);
+
+			for entry in AccessLog::<T>::take(&source) {
+				Self::append_access_log(&target, entry);
+			}
+
+			// Feedback `target` already left on a Doctor is kept over the one `source` left.
+			for (doctor_account_id, ()) in FeedbackGiven::<T>::drain_prefix(&source) {
+				match DoctorFeedback::<T>::take(&doctor_account_id, &source) {
+					Some(feedback_hash)
+						if !DoctorFeedback::<T>::contains_key(&doctor_account_id, &target) =>
+					{
+						DoctorFeedback::<T>::insert(&doctor_account_id, &target, feedback_hash);
+						FeedbackGiven::<T>::insert(&target, &doctor_account_id, ());
+					},
+					Some(_) => FeedbackCount::<T>::mutate(&doctor_account_id, |count| {
+						*count = count.saturating_sub(1)
+					}),
+					None => {},
+				}
+			}
+
+			target_record.personal_data_hash =
+				target_record.personal_data_hash.or(source_record.personal_data_hash);
+			target_record.backup_data_hash =
+				target_record.backup_data_hash.or(source_record.backup_data_hash);
+			target_record.loinc_code = target_record.loinc_code.or(source_record.loinc_code);
+			target_record.emergency_contact_hash =
+				target_record.emergency_contact_hash.or(source_record.emergency_contact_hash);
+
+			// The content type describes the data behind `data_hash`, so it moves along with it.
+			if target_record.data_hash.is_none() {
+				target_record.data_hash = source_record.data_hash;
+				target_record.content_type = source_record.content_type;
+			}
+
+			DataMap::<T>::insert(&target, target_record);
+			DataMap::<T>::remove(&source);
+
+			Self::leave_care_teams(&[source.clone()]);
+			Self::remove_auxiliary_state(&source);
+
+			Self::dec_patient_count();
+
+			Self::deposit_event(Event::RecordsMerged { source, target });
+
+			Ok(())
+		}
+
+		fn set_specialty_consent(
+			patient_account_id: T::AccountId,
+			specialty: [u8; 8],
+			scope: Option<Scope>,
+		) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+
+			SpecialtyConsent::<T>::try_mutate(&patient_account_id, |consents| {
+				consents.retain(|(consented, _)| consented != &specialty);
+
+				match scope {
+					Some(scope) => consents
+						.try_push((specialty, scope))
+						.map_err(|_| Error::<T>::TooManySpecialtyConsents),
+					None => Ok(()),
+				}
+			})?;
+
+			Self::deposit_event(Event::SpecialtyConsentChanged {
+				patient_account_id,
+				specialty,
+				scope,
+			});
+
+			Ok(())
+		}
+
+		fn request_renewal(
+			requester: T::AccountId,
+			patient_account_id: T::AccountId,
+		) -> Result<(), DispatchError> {
+			ensure!(
+				AprovedRequestMap::<T>::get(&patient_account_id).contains(&requester),
+				Error::<T>::NotApproved
+			);
+
+			let expires_at = ApprovalDetails::<T>::get(&patient_account_id, &requester)
+				.and_then(|approval| approval.expires_at)
+				.ok_or(Error::<T>::ApprovalNotExpiring)?;
+
+			let now = frame_system::Pallet::<T>::block_number();
+
+			// An expired approval no longer grants access and has to be requested anew.
+			ensure!(now < expires_at, Error::<T>::NotApproved);
+
+			ensure!(
+				expires_at.saturating_sub(now) <= T::RenewalPeriod::get(),
+				Error::<T>::RenewalNotDue
+			);
+
+			PendingRenewals::<T>::insert(&patient_account_id, &requester, now);
+
+			Self::deposit_event(Event::RenewalRequested { requester, patient_account_id });
+
+			Ok(())
+		}
+
+		fn renew(
+			patient_account_id: T::AccountId,
+			requester: T::AccountId,
+		) -> Result<(), DispatchError> {
+			PendingRenewals::<T>::take(&patient_account_id, &requester)
+				.ok_or(Error::<T>::NoRenewal)?;
+
+			let expires_at = ApprovalDetails::<T>::try_mutate(
+				&patient_account_id,
+				&requester,
+				|approval| -> Result<T::BlockNumber, DispatchError> {
+					let approval = approval.as_mut().ok_or(Error::<T>::NotApproved)?;
+					let current = approval.expires_at.ok_or(Error::<T>::ApprovalNotExpiring)?;
+					let renewed = current.saturating_add(T::RenewalPeriod::get());
+
+					// A renewal clamped below the current expiry leaves the approval as it is.
+					let expires_at = Self::effective_expiry(&patient_account_id, Some(renewed))?
+						.unwrap_or(renewed)
+						.max(current);
+
+					approval.expires_at = Some(expires_at);
+
+					Ok(expires_at)
+				},
+			)?;
+
+			Self::deposit_event(Event::ApprovalRenewed {
+				requester,
+				patient_account_id,
+				expires_at,
+			});
+
+			Ok(())
+		}
+
+		fn set_cap(
+			patient_account_id: T::AccountId,
+			cap: Option<u32>,
+		) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+
+			match cap {
+				Some(cap) => ApprovalCap::<T>::insert(&patient_account_id, cap),
+				None => ApprovalCap::<T>::remove(&patient_account_id),
+			}
+
+			Self::deposit_event(Event::ApprovalCapChanged { patient_account_id, cap });
+
+			Ok(())
+		}
+
+		/// Checks the approved lists of up to `limit` Patients, continuing where the previous call
+		/// stopped, and removes approvals of Doctors that no longer have a record.
+		fn prune_dangling(limit: u32) -> Result<(), DispatchError> {
+			let limit = limit.min(T::MaxBatch::get());
+
+			if limit == 0 {
+				return Ok(())
+			}
+
+			let entries: Vec<(T::AccountId, BoundedVec<T::AccountId, T::MaxListLength>)> =
+				match PruneCursor::<T>::get() {
+					Some(cursor) => AprovedRequestMap::<T>::iter_from(cursor.into_inner())
+						.take(limit as usize)
+						.collect(),
+					None => AprovedRequestMap::<T>::iter().take(limit as usize).collect(),
+				};
+
+			for (patient_account_id, doctor_ids) in entries.iter() {
+				for doctor_account_id in doctor_ids.iter() {
+					if !pallet_doctor::DataMap::<T>::contains_key(doctor_account_id) &&
+						Self::drop_approval(patient_account_id, doctor_account_id)
+					{
+						// Lists kept from before deregistration cleaned them up may still hold
+						// the Patient on the doctor side.
+						let _ = pallet_doctor::Pallet::<T>::remove_approved_request(
+							doctor_account_id.clone(),
+							patient_account_id.clone(),
+						);
+
+						Self::deposit_event(Event::DanglingApprovalPruned {
+							patient_account_id: patient_account_id.clone(),
+							doctor_account_id: doctor_account_id.clone(),
+						});
+					}
+				}
+			}
+
+			match entries.last() {
+				Some((last, _)) if entries.len() as u32 == limit => PruneCursor::<T>::put(
+					BoundedVec::truncate_from(AprovedRequestMap::<T>::hashed_key_for(last)),
+				),
+				_ => PruneCursor::<T>::kill(),
+			}
+
+			Ok(())
+		}
+
+		fn set_delegate(
+			doctor_account_id: T::AccountId,
+			delegate: T::AccountId,
+			authorized: bool,
+		) -> Result<(), DispatchError> {
+			ensure!(
+				pallet_doctor::DataMap::<T>::contains_key(&doctor_account_id),
+				pallet_doctor::Error::<T>::NotRegistered
+			);
+
+			DelegatedUpdaters::<T>::try_mutate(&doctor_account_id, |delegates| {
+				if authorized {
+					if !delegates.contains(&delegate) {
+						delegates
+							.try_push(delegate.clone())
+							.map_err(|_| Error::<T>::TooManyDelegates)?;
+					}
+				} else {
+					delegates.retain(|account_id| account_id != &delegate);
+				}
+
+				Ok::<(), DispatchError>(())
+			})?;
+
+			Self::deposit_event(Event::DelegatedUpdaterChanged {
+				doctor_account_id,
+				delegate,
+				authorized,
+			});
+
+			Ok(())
+		}
+
+		/// Logs an update a delegate submitted on a Doctor's authority.
+		fn note_delegated_update(
+			delegate: T::AccountId,
+			doctor_account_id: T::AccountId,
+			patient_account_id: T::AccountId,
+		) {
+			let hash = DataMap::<T>::get(&patient_account_id)
+				.map(|patient| T::Hashing::hash_of(&patient.data_hash))
+				.unwrap_or_default();
+
+			Self::append_access_log(
+				&patient_account_id,
+				AccessLogEntry {
+					signer: delegate.clone(),
+					on_behalf_of: Some(doctor_account_id.clone()),
+					at: frame_system::Pallet::<T>::block_number(),
+					hash,
+				},
+			);
+
+			Self::deposit_event(Event::DelegatedUpdate {
+				delegate,
+				doctor_account_id,
+				patient_account_id,
+			});
+		}
+
+		fn set_max_duration(
+			patient_account_id: T::AccountId,
+			max_duration: Option<T::BlockNumber>,
+		) -> Result<(), DispatchError> {
+			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+
+			match max_duration {
+				Some(max_duration) =>
+					MaxApprovalDuration::<T>::insert(&patient_account_id, max_duration),
+				None => MaxApprovalDuration::<T>::remove(&patient_account_id),
+			}
+
+			Self::deposit_event(Event::MaxApprovalDurationSet { patient_account_id, max_duration });
+
+			Ok(())
+		}
+
+		/// Removes the approvals of the Patient recorded on one side only. Approvals the Patient
+		/// holds are dropped when their Doctor does not list the Patient. Doctors the Patient no
+		/// longer lists are found through the `ApprovalDetails` left behind, at most
+		/// `MaxListLength` of them, and lose the Patient from their approved list.
+		fn repair(patient_account_id: T::AccountId) {
+			let mut fixed = 0u32;
+
+			let doctor_ids = AprovedRequestMap::<T>::get(&patient_account_id);
+
+			for doctor_account_id in doctor_ids.iter() {
+				if !pallet_doctor::Pallet::<T>::approved_request_list(doctor_account_id)
+					.contains(&patient_account_id) &&
+					Self::drop_approval(&patient_account_id, doctor_account_id)
+				{
+					fixed += 1;
+				}
+			}
+
+			let stale: Vec<T::AccountId> =
+				ApprovalDetails::<T>::iter_key_prefix(&patient_account_id)
+					.filter(|doctor_account_id| !doctor_ids.contains(doctor_account_id))
+					.take(T::MaxListLength::get() as usize)
+					.collect();
+
+			for doctor_account_id in stale {
+				ApprovalDetails::<T>::remove(&patient_account_id, &doctor_account_id);
+				ApprovalReceipts::<T>::remove(&patient_account_id, &doctor_account_id);
+				PendingRenewals::<T>::remove(&patient_account_id, &doctor_account_id);
+
+				// The Doctor side may already be gone along with the Patient side.
+				let _ = pallet_doctor::Pallet::<T>::remove_approved_request(
+					doctor_account_id,
+					patient_account_id.clone(),
+				);
+
+				fixed += 1;
+			}
+
+			Self::deposit_event(Event::ConsistencyRepaired { patient_account_id, fixed });
+		}
+
+		/// Stores the Patient's feedback on a Doctor they currently approve or whose access to
+		/// their data is recorded in their access log.
+		fn feedback(
+			patient_account_id: T::AccountId,
+			doctor_account_id: T::AccountId,
+			feedback_hash: BoundedVec<u8, T::MaxHashLength>,
+		) -> Result<(), DispatchError> {
+			let related = AprovedRequestMap::<T>::get(&patient_account_id)
+				.contains(&doctor_account_id) ||
+				AccessLog::<T>::get(&patient_account_id).iter().any(|entry| {
+					entry.signer == doctor_account_id ||
+						entry.on_behalf_of.as_ref() == Some(&doctor_account_id)
+				});
+
+			ensure!(related, Error::<T>::NotApproved);
+
+			if !DoctorFeedback::<T>::contains_key(&doctor_account_id, &patient_account_id) {
+				FeedbackCount::<T>::mutate(&doctor_account_id, |count| {
+					*count = count.saturating_add(1)
+				});
+			}
+
+			DoctorFeedback::<T>::insert(&doctor_account_id, &patient_account_id, &feedback_hash);
+			FeedbackGiven::<T>::insert(&patient_account_id, &doctor_account_id, ());
+
+			Self::deposit_event(Event::FeedbackLeft {
+				doctor_account_id,
+				patient_account_id,
+				feedback_hash,
+			});
+
+			Ok(())
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::purge_tombstones(now, remaining_weight)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain = StorageVersion::get::<Pallet<T>>();
+
+			if on_chain >= 10 {
+				return Weight::zero()
+			}
+
+			let mut translated = 0u64;
+
+			// Patient records keep their stored layout here, translating all of them at once could
+			// exceed the block weight. `migrate_patients_batch` rewrites them in batches instead.
+
+			// Existing approvals predate the approval delay and stay effective right away.
+			if on_chain < 3 {
+				ApprovalDetails::<T>::translate::<ApprovalV0<T>, _>(|_, _, old| {
+					translated += 1;
+					Some(Approval {
+						scope: old.scope,
+						expires_at: old.expires_at,
+						effective_at: Default::default(),
+					})
+				});
+			}
+
+			// Builds the reverse index of care team members. Memberships beyond
+			// MaxTeamsPerClinician are left out of the index.
+			if on_chain < 5 {
+				for (team_id, members) in TeamMembers::<T>::iter() {
+					for member in members {
+						let _ = ClinicianTeams::<T>::try_mutate(&member, |teams| {
+							teams.try_push(team_id)
+						});
+						translated += 1;
+					}
+				}
+			}
+
+			// Mirrors the scope of existing approvals onto the doctor side.
+			if on_chain < 6 {
+				for (patient_account_id, requester, approval) in ApprovalDetails::<T>::iter() {
+					pallet_doctor::ApprovedScopes::<T>::insert(
+						&requester,
+						&patient_account_id,
+						approval.scope,
+					);
+					translated += 1;
+				}
+			}
+
+			// Counts the approvals granted before TotalActiveApprovals was kept.
+			if on_chain < 8 {
+				let mut total = 0u32;
+				for doctor_ids in AprovedRequestMap::<T>::iter_values() {
+					total = total.saturating_add(doctor_ids.len() as u32);
+					translated += 1;
+				}
+				TotalActiveApprovals::<T>::put(total);
+			}
+
+			// Access log entries written before delegated updates were all signed by the Doctor.
+			if on_chain < 9 {
+				AccessLog::<T>::translate::<
+					BoundedVec<(T::AccountId, T::BlockNumber, T::Hash), T::MaxLogLength>,
+					_,
+				>(|_, old| {
+					translated += 1;
+					let entries = old
+						.into_iter()
+						.map(|(signer, at, hash)| AccessLogEntry {
+							signer,
+							on_behalf_of: None,
+							at,
+							hash,
+						})
+						.collect::<Vec<_>>();
+					Some(BoundedVec::truncate_from(entries))
+				});
+			}
+
+			// Acceptances pending from before the expiry was kept with them carry none, as before.
+			if on_chain < 10 {
+				PendingDoctorAcceptance::<T>::translate::<(T::BlockNumber, Scope), _>(
+					|_, _, (approved_at, scope)| {
+						translated += 1;
+						Some((approved_at, scope, None))
+					},
+				);
+			}
+
+			STORAGE_VERSION.put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: T::BlockNumber) -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::weight(0)]
+		#[pallet::call_index(1)]
+		pub fn register_patient_self(
+			origin: OriginFor<T>,
+			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_enabled(1)?;
+
+			let sender = ensure_signed(origin)?;
+
+			ensure!(
+				T::AllowPatientSelfRegistration::get(),
+				pallet_access::Error::<T>::AccessDenied
+			);
+
+			// The first registration of an account is free of charge.
+			let first_registration = !HasRegisteredOnce::<T>::get(&sender);
+
+			Self::register(sender.clone(), personal_data_hash, data_hash, loinc_code)?;
+
+			if first_registration {
+				return Ok(Pays::No.into())
+			}
+
+			Ok(().into())
+		}
+
+		#[pallet::weight(0)]
+		#[pallet::call_index(2)]
+		pub fn register_patient(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+		) -> DispatchResult {
+			Self::ensure_enabled(2)?;
+
+			let sender = ensure_signed(origin.clone())?;
+
+			pallet_access::Pallet::<T>::has_role(origin, sender.clone(), roles::PATIENT_ADMIN)?;
+
+			Self::register(patient_account_id, personal_data_hash, data_hash, loinc_code)?;
+
+			Ok(())
+		}
+
+		/// Queues a request for the Patient's data. Fails with `AlreadyApproved` if the Patient
+		/// already approved the caller, see `confirm_access` for a non-failing check.
+		#[pallet::weight(<T as Config>::WeightInfo::request_patient_data())]
+		#[pallet::call_index(3)]
+		pub fn request_patient_data(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_enabled(3)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_data_access(&sender)?;
+
+			Self::request(sender, patient_account_id)?;
+
+			Ok(())
+		}
+
+		#[pallet::weight(<T as Config>::WeightInfo::approve_request())]
+		#[pallet::call_index(4)]
+		pub fn approve_request(
+			origin: OriginFor<T>,
+			requester: T::AccountId,
+			scope: Scope,
+			expires_at: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			Self::ensure_enabled(4)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::note_approval_action(&sender)?;
+
+			Self::approve(sender, requester, scope, expires_at)?;
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		#[pallet::call_index(5)]
+		pub fn update_patient_data(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+			content_type: [u8; 16],
+			reason_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+			idempotency_key: Option<[u8; 16]>,
+			on_behalf_of: Option<T::AccountId>,
+		) -> DispatchResult {
+			Self::ensure_enabled(5)?;
+
+			let sender = ensure_signed(origin)?;
+
+			// A delegate writes on the authority of the Doctor, who remains the approved party.
+			let requester = match &on_behalf_of {
+				Some(doctor_account_id) => {
+					ensure!(
+						DelegatedUpdaters::<T>::get(doctor_account_id).contains(&sender),
+						Error::<T>::NotDelegate
+					);
+					doctor_account_id.clone()
+				},
+				None => sender.clone(),
+			};
+
+			Self::ensure_data_access(&requester)?;
+
+			// A retried submission whose key was already processed is a no-op.
+			if let Some(key) = idempotency_key {
+				if Self::key_seen(&sender, key) {
+					return Ok(())
+				}
+			}
+
+			let written = Self::update(
+				patient_account_id.clone(),
+				requester.clone(),
+				data_hash,
+				personal_data_hash,
+				loinc_code,
+				content_type,
+				reason_hash,
+			)?;
+
+			if written && on_behalf_of.is_some() {
+				Self::note_delegated_update(sender, requester, patient_account_id);
+			}
+
+			Ok(())
+		}
+
+		/// Removes RequestMap entries left behind under the wrong keys by the old
+		/// `remove_request` bug. Only requests backed by a pending entry on the doctor side are
+		/// kept.
+		#[pallet::weight(0)]
+		#[pallet::call_index(6)]
+		pub fn cleanup_orphaned_requests(
+			origin: OriginFor<T>,
+			keys: BoundedVec<T::AccountId, T::MaxBatch>,
+		) -> DispatchResult {
+			Self::ensure_enabled(6)?;
+
+			ensure_root(origin)?;
+
+			for key in keys {
+				Self::prune_orphaned_requests(key);
+			}
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		#[pallet::call_index(7)]
+		pub fn accept_patient(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_enabled(7)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::accept(sender, patient_account_id)?;
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		#[pallet::call_index(8)]
+		pub fn decline_patient(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_enabled(8)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::decline(sender, patient_account_id)?;
+
+			Ok(())
+		}
+
+		/// Freezing takes a single privacy admin. Unfreezing records an approval from a
+		/// compliance officer and only clears the flag once `UnfreezeQuorum` officers approved.
+		#[pallet::weight(0)]
+		#[pallet::call_index(9)]
+		pub fn set_frozen(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+			frozen: bool,
+		) -> DispatchResult {
+			Self::ensure_enabled(9)?;
+
+			let sender = ensure_signed(origin)?;
+
+			if frozen {
+				pallet_access::Pallet::<T>::validate_role(sender, T::PrivacyAdminRole::get())?;
+
+				Self::set_frozen_state(patient_account_id, frozen)?;
+			} else {
+				pallet_access::Pallet::<T>::validate_role(
+					sender.clone(),
+					T::ComplianceRole::get(),
+				)?;
+
+				Self::approve_unfreeze(sender, patient_account_id)?;
+			}
+
+			Ok(())
+		}
+
+		#[pallet::weight(0)]
+		#[pallet::call_index(10)]
+		pub fn set_privacy(origin: OriginFor<T>, private: bool) -> DispatchResult {
+			Self::ensure_enabled(10)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::set_private_state(sender, private)?;
+
+			Ok(())
+		}
+
+		/// Exchanges the Patient's `data_hash` and `personal_data_hash`, e.g. after a mislabeled
+		/// upload. Like an update it needs a `reason_hash` if the Patient requires one.
+		#[pallet::weight(0)]
+		#[pallet::call_index(11)]
+		pub fn swap_hashes(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+			reason_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		) -> DispatchResult {
+			Self::ensure_enabled(11)?;
+
+			let requester = ensure_signed(origin)?;
+
+			Self::ensure_data_access(&requester)?;
+
+			Self::swap(patient_account_id, requester, reason_hash)?;
+
+			Ok(())
+		}
+
+		/// Approves a request with the caller's default scope, read-write unless changed with
+		/// `set_default_scope`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(12)]
+		pub fn approve_request_default(
+			origin: OriginFor<T>,
+			requester: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_enabled(12)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::note_approval_action(&sender)?;
+
+			let scope = DefaultScope::<T>::get(&sender);
+
+			Self::approve(sender, requester, scope, None)?;
+
+			Ok(())
+		}
+
+		/// Sets the hash of a redundant copy of the Patient's record kept on a second storage
+		/// network.
+		#[pallet::weight(0)]
+		#[pallet::call_index(13)]
+		pub fn set_backup_hash(
+			origin: OriginFor<T>,
+			backup_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		) -> DispatchResult {
+			Self::ensure_enabled(13)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::set_backup(sender, backup_data_hash)?;
+
+			Ok(())
+		}
+
+		/// Changes the scope of an approval already given to a Doctor, keeping the relationship
+		/// in place.
+		#[pallet::weight(0)]
+		#[pallet::call_index(14)]
+		pub fn set_approval_scope(
+			origin: OriginFor<T>,
+			requester: T::AccountId,
+			scope: Scope,
+		) -> DispatchResult {
+			Self::ensure_enabled(14)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::set_scope(sender, requester, scope)?;
+
+			Ok(())
+		}
+
+		/// Rejects every request waiting for the Patient's approval. The work is bounded by
+		/// `MaxListLength`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(15)]
+		pub fn clear_all_requests(origin: OriginFor<T>) -> DispatchResult {
+			Self::ensure_enabled(15)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::clear_requests(sender)?;
+
+			Ok(())
+		}
+
+		/// Marks the Patient as deceased. Their record stays readable but rejects any further
+		/// request, approval or update.
+		#[pallet::weight(0)]
+		#[pallet::call_index(16)]
+		pub fn mark_deceased(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_enabled(16)?;
+
+			let sender = ensure_signed(origin)?;
+
+			pallet_access::Pallet::<T>::validate_role(sender, T::ComplianceRole::get())?;
+
+			Self::set_deceased(patient_account_id)?;
+
+			Ok(())
+		}
+
+		/// Records that the Patient has seen all updates made to their data so far.
+		#[pallet::weight(0)]
+		#[pallet::call_index(17)]
+		pub fn acknowledge_update(origin: OriginFor<T>) -> DispatchResult {
+			Self::ensure_enabled(17)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::acknowledge(sender)?;
+
+			Ok(())
+		}
+
+		/// Registers several Patients at once, skipping accounts that are already registered.
+		/// The work grows linearly with the number of entries, which `MaxBatch` bounds.
+		#[pallet::weight(0)]
+		#[pallet::call_index(18)]
+		pub fn register_patients_batch(
+			origin: OriginFor<T>,
+			entries: BoundedVec<
+				(
+					T::AccountId,
+					Option<BoundedVec<u8, T::MaxHashLength>>,
+					Option<BoundedVec<u8, T::MaxHashLength>>,
+				),
+				T::MaxBatch,
+			>,
+		) -> DispatchResult {
+			Self::ensure_enabled(18)?;
+
+			let sender = ensure_signed(origin.clone())?;
+
+			pallet_access::Pallet::<T>::has_role(origin, sender.clone(), roles::PATIENT_ADMIN)?;
+
+			Self::register_batch(entries)?;
+
+			Ok(())
+		}
+
+		/// Enables or disables a single dispatchable of this pallet by its call index. This call
+		/// itself can not be disabled.
+		#[pallet::weight(0)]
+		#[pallet::call_index(19)]
+		pub fn set_call_enabled(
+			origin: OriginFor<T>,
+			call_index: u8,
+			enabled: bool,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			Self::set_call_state(call_index, enabled)?;
+
+			Ok(())
+		}
+
+		/// Sets the Patients and members of a care team. Members can read every Patient of the
+		/// team but never update them. Empty lists remove the team. Fails if a new member would
+		/// exceed `MaxTeamsPerClinician`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(20)]
+		pub fn set_care_team(
+			origin: OriginFor<T>,
+			team_id: u32,
+			patients: BoundedVec<T::AccountId, T::MaxListLength>,
+			members: BoundedVec<T::AccountId, T::MaxListLength>,
+		) -> DispatchResult {
+			Self::ensure_enabled(20)?;
+
+			ensure_root(origin)?;
+
+			Self::set_team(team_id, patients, members)?;
+
+			Ok(())
+		}
+
+		/// Revokes the access of every Doctor the Patient approved. The work is bounded by
+		/// `MaxListLength`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(21)]
+		pub fn revoke_all_access(origin: OriginFor<T>) -> DispatchResult {
+			Self::ensure_enabled(21)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::note_approval_action(&sender)?;
+
+			Self::revoke_all(sender)?;
+
+			Ok(())
+		}
+
+		/// Blocks or unblocks a Doctor. A blocked Doctor can not be approved by the Patient,
+		/// existing approvals are left untouched.
+		#[pallet::weight(0)]
+		#[pallet::call_index(22)]
+		pub fn set_doctor_blocked(
+			origin: OriginFor<T>,
+			doctor_account_id: T::AccountId,
+			blocked: bool,
+		) -> DispatchResult {
+			Self::ensure_enabled(22)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::set_blocked(sender, doctor_account_id, blocked)?;
+
+			Ok(())
 		}
-	}
 
-	#[pallet::pallet]
-	#[pallet::generate_store(pub(super) trait Store)]
-	pub struct Pallet<T>(_);
+		/// Emits `AlreadyHasAccess` if the caller may read the Patient's data, so a client can
+		/// skip `request_patient_data` and its `AlreadyApproved` error. Fails with `NotApproved`
+		/// otherwise.
+		#[pallet::weight(0)]
+		#[pallet::call_index(23)]
+		pub fn confirm_access(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_enabled(23)?;
 
-	#[pallet::config]
-	pub trait Config: frame_system::Config + pallet_access::Config + pallet_doctor::Config {
-		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+			let sender = ensure_signed(origin)?;
 
-		#[pallet::constant]
-		type MaxLoincCodeLength: Get<u32>;
-	}
+			Self::confirm(patient_account_id, sender)?;
 
-	#[pallet::storage]
-	#[pallet::getter(fn patient_data)]
-	pub type DataMap<T: Config> =
-		StorageMap<_, Twox64Concat, T::AccountId, Patients<T>, OptionQuery>;
+			Ok(())
+		}
 
-	///Storage Map for Storing all doctors who made request to view or update data against patient
-	/// AccountId
-	#[pallet::storage]
-	#[pallet::getter(fn request_list)]
-	pub type RequestMap<T: Config> = StorageMap<
-		_,
-		Twox64Concat,
-		T::AccountId,
-		BoundedVec<T::AccountId, T::MaxListLength>,
-		ValueQuery,
-	>;
+		/// Sets whether every `update_patient_data` and `swap_hashes` on the caller's record must
+		/// carry a `reason_hash`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(24)]
+		pub fn set_require_update_reason(origin: OriginFor<T>, required: bool) -> DispatchResult {
+			Self::ensure_enabled(24)?;
 
-	///Storage Map for Storing all approved requests for Patients Against their Account Id
-	#[pallet::storage]
-	#[pallet::getter(fn approved_request_list)]
-	pub type AprovedRequestMap<T: Config> = StorageMap<
-		_,
-		Twox64Concat,
-		T::AccountId,
-		BoundedVec<T::AccountId, T::MaxListLength>,
-		ValueQuery,
-	>;
+			let sender = ensure_signed(origin)?;
 
-	#[pallet::event]
-	#[pallet::generate_deposit(pub(super) fn deposit_event)]
-	pub enum Event<T: Config> {
-		/// When Patient Data is Successfully registered.
-		PatientDataUpdated { patient_account_id: T::AccountId },
-		/// When a Request is Sucessfully added to the RequestQueue
-		RequestQueued { requester: T::AccountId, patient_account_id: T::AccountId },
-		/// When a Request is Successfully Approved
-		RequestApproved { requester: T::AccountId, patient_account_id: T::AccountId },
-		/// When a request is successfully executed
-		DataUpdated { requester: T::AccountId, patient_account_id: T::AccountId },
-	}
+			Self::set_update_reason_requirement(sender, required)?;
 
-	#[pallet::error]
-	pub enum Error<T> {
-		BoundsOverflow,
-		AlreadyRegistered,
-		AlreadyRequested,
-		AlreadyApproved,
-		NoRequest,
-		UnableToUpdate,
-		MaxListLengthReached,
-		NotApproved,
-		NoPatient,
-	}
+			Ok(())
+		}
 
-	impl<T: Config> Pallet<T> {
-		fn register(
+		/// Revokes a single Doctor's access to the Patient without the Patient's action, e.g. on
+		/// a court order. Requires `ComplianceRole`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(25)]
+		pub fn force_revoke_access(
+			origin: OriginFor<T>,
 			patient_account_id: T::AccountId,
-			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
-		) -> Result<(), DispatchError> {
-			ensure!(
-				!DataMap::<T>::contains_key(&patient_account_id),
-				Error::<T>::AlreadyRegistered
-			);
+			doctor_account_id: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_enabled(25)?;
 
-			let patient = Patients::<T> { personal_data_hash, data_hash, loinc_code };
+			let sender = ensure_signed(origin)?;
 
-			DataMap::<T>::insert(&patient_account_id, patient);
+			pallet_access::Pallet::<T>::validate_role(sender.clone(), T::ComplianceRole::get())?;
 
-			Self::deposit_event(Event::PatientDataUpdated { patient_account_id });
+			Self::force_revoke(sender, patient_account_id, doctor_account_id)?;
 
 			Ok(())
 		}
 
-		fn request(
-			requester: T::AccountId,
-			patient_account_id: T::AccountId,
-		) -> Result<(), DispatchError> {
-			ensure!(DataMap::<T>::contains_key(&patient_account_id), Error::<T>::NoPatient);
+		/// Deletes the caller's record. Requests and approvals end right away, the record itself
+		/// is kept as a tombstone for `RetentionPeriod` blocks before it is purged.
+		#[pallet::weight(0)]
+		#[pallet::call_index(26)]
+		pub fn delete_patient_record(origin: OriginFor<T>) -> DispatchResult {
+			Self::ensure_enabled(26)?;
 
-			let doctors_ids = RequestMap::<T>::get(&patient_account_id);
+			let sender = ensure_signed(origin)?;
 
-			ensure!(
-				!doctors_ids.iter().any(|account_id| account_id == &requester),
-				Error::<T>::AlreadyRequested
-			);
+			Self::delete_record(sender)?;
 
-			let approved_doctor_ids = AprovedRequestMap::<T>::get(&patient_account_id);
+			Ok(())
+		}
 
-			ensure!(
-				!approved_doctor_ids.iter().any(|account_id| account_id == &requester),
-				Error::<T>::AlreadyApproved
-			);
+		/// Restores a deleted record that has not been purged yet. Requests and approvals ended
+		/// by the deletion are not restored.
+		#[pallet::weight(0)]
+		#[pallet::call_index(27)]
+		pub fn restore_record(
+			origin: OriginFor<T>,
+			patient_account_id: T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_enabled(27)?;
 
-			RequestMap::<T>::try_append(&patient_account_id, requester.clone())
-				.map_err(|_| Error::<T>::MaxListLengthReached)?;
+			ensure_root(origin)?;
 
-			pallet_doctor::Pallet::<T>::add_request(requester.clone(), patient_account_id.clone())?;
+			Self::restore(patient_account_id)?;
+
+			Ok(())
+		}
+
+		/// Sets the scope `approve_request_default` grants on behalf of the caller.
+		#[pallet::weight(0)]
+		#[pallet::call_index(28)]
+		pub fn set_default_scope(origin: OriginFor<T>, scope: Scope) -> DispatchResult {
+			Self::ensure_enabled(28)?;
+
+			let sender = ensure_signed(origin)?;
 
-			Self::deposit_event(Event::RequestQueued { requester, patient_account_id });
+			Self::set_default(sender, scope)?;
 
 			Ok(())
 		}
 
-		fn remove_request(
-			patient_account_id: T::AccountId,
-			requester: T::AccountId,
-		) -> Result<(), DispatchError> {
-			let mut doctor_ids = RequestMap::<T>::get(&patient_account_id);
+		/// Migrates up to `limit` Patient records to the current layout, continuing where the
+		/// previous call stopped. Meant for layout changes too large for a single runtime upgrade.
+		/// `limit` is capped at `MaxBatch`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(29)]
+		pub fn migrate_patients_batch(origin: OriginFor<T>, limit: u32) -> DispatchResult {
+			Self::ensure_enabled(29)?;
 
-			ensure!(
-				doctor_ids.iter().any(|account_id| account_id == &requester),
-				Error::<T>::NoRequest
-			);
+			ensure_root(origin)?;
 
-			if let Some(ind) = doctor_ids.iter().position(|id| id == &requester) {
-				doctor_ids.swap_remove(ind);
-			} else {
-				return Err(Error::<T>::NoRequest.into())
-			}
+			Self::migrate_batch(limit)?;
+
+			Ok(())
+		}
+
+		/// Sets or clears the hash of the Patient's emergency contact details.
+		#[pallet::weight(0)]
+		#[pallet::call_index(30)]
+		pub fn set_emergency_contact(
+			origin: OriginFor<T>,
+			emergency_contact_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
+		) -> DispatchResult {
+			Self::ensure_enabled(30)?;
 
-			RequestMap::<T>::insert(&requester, doctor_ids);
+			let sender = ensure_signed(origin)?;
+
+			Self::set_emergency_contact_hash(sender, emergency_contact_hash)?;
 
 			Ok(())
 		}
 
-		fn approve(
-			patient_account_id: T::AccountId,
-			requester: T::AccountId,
-		) -> Result<(), DispatchError> {
-			Self::remove_request(patient_account_id.clone(), requester.clone())?;
+		/// Allows hashes starting with `prefix`. Once any prefix is allowed, every submitted hash
+		/// must start with one of them.
+		#[pallet::weight(0)]
+		#[pallet::call_index(31)]
+		pub fn add_allowed_prefix(
+			origin: OriginFor<T>,
+			prefix: BoundedVec<u8, T::MaxHashLength>,
+		) -> DispatchResult {
+			Self::ensure_enabled(31)?;
 
-			let approved_doctor_ids = AprovedRequestMap::<T>::get(&patient_account_id);
+			ensure_root(origin)?;
 
-			ensure!(
-				!approved_doctor_ids.iter().any(|account_id| account_id == &requester),
-				Error::<T>::AlreadyApproved
-			);
+			Self::add_prefix(prefix)?;
+
+			Ok(())
+		}
 
-			AprovedRequestMap::<T>::try_append(&patient_account_id, requester.clone())
-				.map_err(|_| Error::<T>::MaxListLengthReached)?;
+		/// Removes an allowed hash prefix. Removing the last one accepts every hash again.
+		#[pallet::weight(0)]
+		#[pallet::call_index(32)]
+		pub fn remove_allowed_prefix(
+			origin: OriginFor<T>,
+			prefix: BoundedVec<u8, T::MaxHashLength>,
+		) -> DispatchResult {
+			Self::ensure_enabled(32)?;
 
-			pallet_doctor::Pallet::<T>::add_approved_request(
-				patient_account_id.clone(),
-				requester.clone(),
-			)?;
+			ensure_root(origin)?;
 
-			Self::deposit_event(Event::RequestApproved { requester, patient_account_id });
+			Self::remove_prefix(prefix)?;
 
 			Ok(())
 		}
 
-		fn update(
+		/// Records a Doctor's signed attestation of viewing `data_hash` at block `at`. The Doctor
+		/// signs the SCALE encoded `(patient, data_hash, at)` with their account key. `at` must not
+		/// lie in the future nor more than `MaxAttestationAge` blocks in the past, and the Doctor's
+		/// approval must not have expired.
+		#[pallet::weight(0)]
+		#[pallet::call_index(33)]
+		pub fn attest_view(
+			origin: OriginFor<T>,
 			patient_account_id: T::AccountId,
-			requester: T::AccountId,
-			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
-		) -> Result<(), DispatchError> {
-			let approved_doctor_ids = AprovedRequestMap::<T>::get(&patient_account_id);
+			data_hash: BoundedVec<u8, T::MaxHashLength>,
+			at: T::BlockNumber,
+			signature: T::OffchainSignature,
+		) -> DispatchResult {
+			Self::ensure_enabled(33)?;
 
-			ensure!(
-				approved_doctor_ids.iter().any(|account_id| account_id == &requester),
-				Error::<T>::NotApproved
-			);
+			let sender = ensure_signed(origin)?;
 
-			let mut patient_data = DataMap::<T>::get(&patient_account_id).unwrap_or_default();
+			Self::attest(sender, patient_account_id, data_hash, at, signature)?;
 
-			patient_data.data_hash = data_hash;
-			patient_data.personal_data_hash = personal_data_hash;
-			patient_data.loinc_code = loinc_code;
+			Ok(())
+		}
 
-			DataMap::<T>::insert(&patient_account_id, patient_data);
+		/// Merges the duplicate Patient record `source` into `target` and removes `source`.
+		/// Missing data hashes of `target` are taken from `source`. Fails with `MergeConflict`
+		/// when both records hold data, unless `force` is set, in which case `target`'s data is
+		/// kept. Requires `PATIENT_ADMIN`.
+		#[pallet::weight(0)]
+		#[pallet::call_index(34)]
+		pub fn merge_records(
+			origin: OriginFor<T>,
+			source: T::AccountId,
+			target: T::AccountId,
+			force: bool,
+		) -> DispatchResult {
+			Self::ensure_enabled(34)?;
+
+			let sender = ensure_signed(origin.clone())?;
+
+			pallet_access::Pallet::<T>::has_role(origin, sender.clone(), roles::PATIENT_ADMIN)?;
 
-			Self::deposit_event(Event::DataUpdated { requester, patient_account_id });
+			Self::merge(source, target, force)?;
 
 			Ok(())
 		}
-	}
 
-	#[pallet::call]
-	impl<T: Config> Pallet<T> {
+		/// Pre-consents to every Doctor of `specialty`, whose requests are then approved with
+		/// `scope` instead of being queued. `None` withdraws the consent.
 		#[pallet::weight(0)]
-		#[pallet::call_index(1)]
-		pub fn register_patient_self(
+		#[pallet::call_index(35)]
+		pub fn consent_to_specialty(
 			origin: OriginFor<T>,
-			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
+			specialty: [u8; 8],
+			scope: Option<Scope>,
 		) -> DispatchResult {
+			Self::ensure_enabled(35)?;
+
 			let sender = ensure_signed(origin)?;
 
-			Self::register(sender.clone(), personal_data_hash, data_hash, loinc_code)?;
+			Self::set_specialty_consent(sender, specialty, scope)?;
 
 			Ok(())
 		}
 
+		/// Asks the Patient to renew the caller's approval once it expires within `RenewalPeriod`.
+		/// Fails with `NotApproved` once the approval has expired.
 		#[pallet::weight(0)]
-		#[pallet::call_index(2)]
-		pub fn register_patient(
+		#[pallet::call_index(36)]
+		pub fn renew_approval(
 			origin: OriginFor<T>,
 			patient_account_id: T::AccountId,
-			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
 		) -> DispatchResult {
-			let sender = ensure_signed(origin.clone())?;
+			Self::ensure_enabled(36)?;
 
-			pallet_access::Pallet::<T>::has_role(origin, sender.clone(), [0u8; 32])?;
+			let sender = ensure_signed(origin)?;
 
-			Self::register(patient_account_id, personal_data_hash, data_hash, loinc_code)?;
+			Self::request_renewal(sender, patient_account_id)?;
 
 			Ok(())
 		}
 
+		/// Confirms a Doctor's pending renewal, extending the approval's expiry by
+		/// `RenewalPeriod`.
 		#[pallet::weight(0)]
-		#[pallet::call_index(3)]
-		pub fn request_patient_data(
+		#[pallet::call_index(37)]
+		pub fn confirm_renewal(
 			origin: OriginFor<T>,
-			patient_account_id: T::AccountId,
+			doctor_account_id: T::AccountId,
 		) -> DispatchResult {
-			let sender = ensure_signed(origin.clone())?;
+			Self::ensure_enabled(37)?;
 
-			pallet_access::Pallet::<T>::has_role(origin, sender.clone(), [0u8; 32])?;
+			let sender = ensure_signed(origin)?;
 
-			Self::request(sender, patient_account_id)?;
+			Self::renew(sender, doctor_account_id)?;
 
 			Ok(())
 		}
 
+		/// Sets a cap on the number of Doctors the caller can have approved at once, or clears it
+		/// with `None`. Caps above `MaxApprovalsPerPatient` have no effect beyond it. Existing
+		/// approvals above the cap are kept.
 		#[pallet::weight(0)]
-		#[pallet::call_index(4)]
-		pub fn approve_request(origin: OriginFor<T>, requester: T::AccountId) -> DispatchResult {
+		#[pallet::call_index(38)]
+		pub fn set_approval_cap(origin: OriginFor<T>, cap: Option<u32>) -> DispatchResult {
+			Self::ensure_enabled(38)?;
+
 			let sender = ensure_signed(origin)?;
 
-			Self::approve(sender, requester)?;
+			Self::set_cap(sender, cap)?;
 
 			Ok(())
 		}
 
+		/// Removes approvals of Doctors whose record no longer exists, checking up to `limit`
+		/// Patients per call. `limit` is capped at `MaxBatch`. Meant for approvals left behind
+		/// before deregistration cleaned them up.
 		#[pallet::weight(0)]
-		#[pallet::call_index(5)]
-		pub fn update_patient_data(
+		#[pallet::call_index(39)]
+		pub fn prune_dangling_approvals(origin: OriginFor<T>, limit: u32) -> DispatchResult {
+			Self::ensure_enabled(39)?;
+
+			ensure_root(origin)?;
+
+			Self::prune_dangling(limit)?;
+
+			Ok(())
+		}
+
+		/// Authorizes `delegate`, e.g. a nurse, to submit `update_patient_data` on the caller's
+		/// behalf, or withdraws the authorization. The caller must be a registered Doctor.
+		#[pallet::weight(0)]
+		#[pallet::call_index(40)]
+		pub fn set_delegated_updater(
+			origin: OriginFor<T>,
+			delegate: T::AccountId,
+			authorized: bool,
+		) -> DispatchResult {
+			Self::ensure_enabled(40)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::set_delegate(sender, delegate, authorized)?;
+
+			Ok(())
+		}
+
+		/// Sets the longest duration in blocks the caller allows any approval to last, or clears it
+		/// with `None`. Applies to approvals and renewals from now on, existing approvals keep
+		/// their expiry.
+		#[pallet::weight(0)]
+		#[pallet::call_index(41)]
+		pub fn set_max_approval_duration(
+			origin: OriginFor<T>,
+			max_duration: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			Self::ensure_enabled(41)?;
+
+			let sender = ensure_signed(origin)?;
+
+			Self::set_max_duration(sender, max_duration)?;
+
+			Ok(())
+		}
+
+		/// Reconciles the Patient's approved list with the approved lists of the Doctors on it,
+		/// removing approvals recorded on only one side.
+		#[pallet::weight(0)]
+		#[pallet::call_index(42)]
+		pub fn repair_consistency(
 			origin: OriginFor<T>,
 			patient_account_id: T::AccountId,
-			data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			personal_data_hash: Option<BoundedVec<u8, T::MaxHashLength>>,
-			loinc_code: Option<BoundedVec<u8, T::MaxLoincCodeLength>>,
 		) -> DispatchResult {
-			let requester = ensure_signed(origin.clone())?;
+			Self::ensure_enabled(42)?;
+
+			ensure_root(origin)?;
+
+			Self::repair(patient_account_id);
+
+			Ok(())
+		}
+
+		/// Leaves feedback on a Doctor the caller approves, or whose access is recorded in the
+		/// caller's access log. Feedback left earlier is replaced.
+		#[pallet::weight(0)]
+		#[pallet::call_index(43)]
+		pub fn leave_feedback(
+			origin: OriginFor<T>,
+			doctor_account_id: T::AccountId,
+			feedback_hash: BoundedVec<u8, T::MaxHashLength>,
+		) -> DispatchResult {
+			Self::ensure_enabled(43)?;
 
-			pallet_access::Pallet::<T>::has_role(origin, requester.clone(), [0u8; 32])?;
+			let sender = ensure_signed(origin)?;
 
-			Self::update(patient_account_id, requester, data_hash, personal_data_hash, loinc_code)?;
+			Self::feedback(sender, doctor_account_id, feedback_hash)?;
 
 			Ok(())
 		}
 	}
+
+	/// Drops the approvals Patients granted to a Doctor whose record was removed, along with the
+	/// Doctor's pending requests, acceptances and care team memberships.
+	impl<T: Config> pallet_doctor::OnDoctorDeregistered<T::AccountId> for Pallet<T> {
+		fn on_deregister(
+			doctor_account_id: &T::AccountId,
+			approved_patient_ids: &[T::AccountId],
+			requested_patient_ids: &[T::AccountId],
+		) {
+			for patient_account_id in approved_patient_ids {
+				Self::drop_approval(patient_account_id, doctor_account_id);
+			}
+
+			for patient_account_id in requested_patient_ids {
+				Self::drop_request(patient_account_id, doctor_account_id);
+			}
+
+			Self::leave_team_memberships(doctor_account_id);
+
+			DelegatedUpdaters::<T>::remove(doctor_account_id);
+		}
+	}
 }
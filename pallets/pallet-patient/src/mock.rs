@@ -0,0 +1,229 @@
+use crate as pallet_patient;
+use crate::Scope;
+use frame_support::{
+	assert_ok, parameter_types,
+	traits::{ConstBool, ConstU16, ConstU32, ConstU64},
+	weights::constants::RocksDbWeight,
+};
+use pallet_access::roles;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestSignature, UintAuthorityId},
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub const PATIENT: u64 = 1;
+pub const OTHER_PATIENT: u64 = 2;
+pub const DOCTOR: u64 = 11;
+pub const OTHER_DOCTOR: u64 = 12;
+/// Account holding `roles::PRIVACY_ADMIN`.
+pub const PRIVACY_ADMIN: u64 = 20;
+/// Account holding `roles::COMPLIANCE`.
+pub const COMPLIANCE: u64 = 21;
+/// Account holding `roles::PATIENT_ADMIN`.
+pub const PATIENT_ADMIN: u64 = 22;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		AccessModule: pallet_access,
+		PatientModule: pallet_patient,
+		DoctorModule: pallet_doctor,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = RocksDbWeight;
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_access::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxMetadataLength = ConstU32<32>;
+	type MaxRoles = ConstU32<8>;
+	type MaxRotationBatch = ConstU32<8>;
+	type MaxRolesPerAccount = ConstU32<4>;
+	type AdminOrigin = frame_system::EnsureRoot<u64>;
+	type RoleEventTopics = ConstBool<false>;
+}
+
+impl pallet_doctor::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type MaxHashLength = ConstU32<64>;
+	type MaxListLength = ConstU32<4>;
+	type RequireRoleToDeregister = ConstBool<false>;
+	type DeregisterApproverRole = PrivacyAdminRole;
+	type VerificationAdminRole = PrivacyAdminRole;
+	type VerifierRole = ComplianceRole;
+	type OnDoctorDeregistered = PatientModule;
+}
+
+parameter_types! {
+	pub const PrivacyAdminRole: [u8; 32] = roles::PRIVACY_ADMIN;
+	pub const ComplianceRole: [u8; 32] = roles::COMPLIANCE;
+	pub const PatientRole: [u8; 32] = roles::PATIENT;
+	pub static TwoSidedConsent: bool = false;
+	pub static AllowPatientSelfRegistration: bool = true;
+	pub static UpdateCooldown: u64 = 0;
+	pub static MaxApprovalActionsPerWindow: u32 = 16;
+	pub static ApprovalDelay: u64 = 0;
+	pub static ApprovalAlertThreshold: u32 = 0;
+	pub static UnfreezeQuorum: u32 = 1;
+	pub static DataAccessRoles: Vec<[u8; 32]> = vec![roles::DOCTOR];
+	pub static RequireAllDataAccessRoles: bool = true;
+	pub static RequireCidPrefix: Vec<u8> = Vec::new();
+	pub static RejectDuplicateHashes: bool = false;
+	pub static RejectUnchangedUpdates: bool = false;
+	pub static EmitViewEvents: bool = true;
+	pub static ClampApprovalDuration: bool = true;
+	pub static MaxApprovalsPerPatient: u32 = 4;
+	/// (Patient, Doctor) pairs reported to `OnAccessRevoked`, oldest first.
+	pub static Revocations: Vec<(u64, u64)> = Vec::new();
+}
+
+/// Records every revocation in `Revocations`.
+pub struct RecordRevocations;
+
+impl pallet_patient::OnAccessRevoked<u64> for RecordRevocations {
+	fn on_revoke(patient_account_id: &u64, doctor_account_id: &u64) {
+		let mut revocations = Revocations::get();
+		revocations.push((*patient_account_id, *doctor_account_id));
+		Revocations::set(revocations);
+	}
+}
+
+impl pallet_patient::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type MaxLoincCodeLength = ConstU32<16>;
+	type MaxBatch = ConstU32<4>;
+	type TwoSidedConsent = TwoSidedConsent;
+	type MaxRecentEvents = ConstU32<4>;
+	type PrivacyAdminRole = PrivacyAdminRole;
+	type ComplianceRole = ComplianceRole;
+	type AllowPatientSelfRegistration = AllowPatientSelfRegistration;
+	type AutoAssignPatientRole = ConstBool<true>;
+	type PatientRole = PatientRole;
+	type UpdateCooldown = UpdateCooldown;
+	type ApprovalActionWindow = ConstU64<10>;
+	type MaxApprovalActionsPerWindow = MaxApprovalActionsPerWindow;
+	type ApprovalDelay = ApprovalDelay;
+	type MaxApprovalsPerPatient = MaxApprovalsPerPatient;
+	type ApprovalAlertThreshold = ApprovalAlertThreshold;
+	type MaxSeenKeys = ConstU32<4>;
+	type UnfreezeQuorum = UnfreezeQuorum;
+	type DataAccessRoles = DataAccessRoles;
+	type RequireAllDataAccessRoles = RequireAllDataAccessRoles;
+	type RequireCidPrefix = RequireCidPrefix;
+	type RejectDuplicateHashes = RejectDuplicateHashes;
+	type RejectUnchangedUpdates = RejectUnchangedUpdates;
+	type EmitViewEvents = EmitViewEvents;
+	type ClampApprovalDuration = ClampApprovalDuration;
+	type MaxLogLength = ConstU32<2>;
+	type MaxAllowedPrefixes = ConstU32<2>;
+	type MaxDelegates = ConstU32<2>;
+	type MaxSpecialtyConsents = ConstU32<2>;
+	type MaxTeamsPerClinician = ConstU32<2>;
+	type RetentionPeriod = ConstU64<10>;
+	type MaxAttestationAge = ConstU64<5>;
+	type RenewalPeriod = ConstU64<5>;
+	type OnAccessRevoked = RecordRevocations;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+}
+
+/// Builds genesis storage with the well known roles and `PRIVACY_ADMIN`, `COMPLIANCE` and
+/// `PATIENT_ADMIN` holding their namesake roles.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = GenesisConfig {
+		system: Default::default(),
+		access_module: pallet_access::GenesisConfig {
+			roles: vec![
+				roles::DOCTOR,
+				roles::PRIVACY_ADMIN,
+				roles::COMPLIANCE,
+				roles::PATIENT_ADMIN,
+				roles::PATIENT,
+			],
+			members: vec![],
+		},
+	}
+	.build_storage()
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(AccessModule::assign(
+			RuntimeOrigin::root(),
+			PRIVACY_ADMIN,
+			roles::PRIVACY_ADMIN
+		));
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), COMPLIANCE, roles::COMPLIANCE));
+		assert_ok!(AccessModule::assign(
+			RuntimeOrigin::root(),
+			PATIENT_ADMIN,
+			roles::PATIENT_ADMIN
+		));
+	});
+	ext
+}
+
+/// Registers `who` as a Patient without any data.
+pub fn register_patient(who: u64) {
+	assert_ok!(PatientModule::register_patient_self(RuntimeOrigin::signed(who), None, None, None));
+}
+
+/// Registers `who` as a Doctor holding `roles::DOCTOR`.
+pub fn register_doctor(who: u64) {
+	assert_ok!(DoctorModule::register(RuntimeOrigin::signed(who), None));
+	assert_ok!(AccessModule::assign(RuntimeOrigin::root(), who, roles::DOCTOR));
+}
+
+/// Queues a request of `doctor` for the data of `patient`.
+pub fn request(doctor: u64, patient: u64) {
+	assert_ok!(PatientModule::request_patient_data(RuntimeOrigin::signed(doctor), patient));
+}
+
+/// Queues a request of `doctor` and approves it read-write.
+pub fn approve(doctor: u64, patient: u64) {
+	request(doctor, patient);
+	assert_ok!(PatientModule::approve_request(
+		RuntimeOrigin::signed(patient),
+		doctor,
+		Scope::ReadWrite,
+		None
+	));
+}
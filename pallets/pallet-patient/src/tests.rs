@@ -0,0 +1,4043 @@
+use crate::{
+	mock::*, AccessLog, AccessLogEntry, AllowedHashPrefixes, Approval, ApprovalActionCount,
+	ApprovalCap, ApprovalDetails, ApprovalReceipts, AprovedRequestMap, BlockList, CareTeams,
+	ClinicianTeams, DataMap, DefaultScope, DelegatedUpdaters, DoctorFeedback, DoctorLoad, Error,
+	Event, EventKind, FeedbackGiven, Frozen, HasRegisteredOnce, LastAcknowledged, LastAlertedCount,
+	LastUpdateBlock, MaxApprovalDuration, MigrationCursor, PatientCount, PendingDoctorAcceptance,
+	PendingRequestCount, PreviousDataHash, Private, ProtocolStats, PurgeCursor, RecentEvents,
+	RecordLayout, RequestMap, RequestSeq, RequestedAt, RequireUpdateReason, Scope, SeenKeys,
+	SpecialtyConsent, TeamMembers, Tombstones, TotalActiveApprovals, UnfreezeApprovals,
+	UpdateCount, PATIENTS_LAYOUT,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchResult, Pays},
+	storage::unhashed,
+	traits::{Get, GetStorageVersion, Hooks, StorageVersion},
+	weights::Weight,
+	BoundedVec,
+};
+use pallet_access::roles;
+use pallet_doctor::ListKind;
+use sp_runtime::{
+	testing::TestSignature,
+	traits::{BadOrigin, Hash},
+};
+
+fn hash(byte: u8) -> BoundedVec<u8, <Test as pallet_doctor::Config>::MaxHashLength> {
+	BoundedVec::truncate_from(vec![byte; 4])
+}
+
+/// Returns the last event deposited by the Patient pallet.
+fn last_event() -> Event<Test> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::PatientModule(event) => Some(event),
+			_ => None,
+		})
+		.last()
+		.expect("an event was deposited")
+}
+
+/// Writes `byte` as the Patient's data hash on behalf of `doctor`.
+fn try_update(doctor: u64, patient: u64, byte: u8) -> DispatchResult {
+	PatientModule::update_patient_data(
+		RuntimeOrigin::signed(doctor),
+		patient,
+		Some(hash(byte)),
+		None,
+		None,
+		[0u8; 16],
+		None,
+		None,
+		None,
+	)
+}
+
+/// Writes `byte` as the Patient's data hash on behalf of `doctor` under an idempotency key.
+fn update_with_key(doctor: u64, patient: u64, byte: u8, key: u8) -> DispatchResult {
+	PatientModule::update_patient_data(
+		RuntimeOrigin::signed(doctor),
+		patient,
+		Some(hash(byte)),
+		None,
+		None,
+		[0u8; 16],
+		None,
+		Some([key; 16]),
+		None,
+	)
+}
+
+fn update(doctor: u64, patient: u64, byte: u8) {
+	assert_ok!(try_update(doctor, patient, byte));
+}
+
+#[test]
+fn request_block_is_carried_to_approval_and_first_update() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+
+		System::set_block_number(3);
+		request(DOCTOR, PATIENT);
+		assert!(matches!(last_event(), Event::RequestQueued { requested_at: 3, .. }));
+
+		System::set_block_number(5);
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		assert!(matches!(last_event(), Event::RequestApproved { requested_at: 3, .. }));
+
+		System::set_block_number(8);
+		update(DOCTOR, PATIENT, 1);
+		assert!(matches!(last_event(), Event::DataUpdated { requested_at: Some(3), .. }));
+
+		// Only the first update after the approval carries the request block.
+		update(DOCTOR, PATIENT, 2);
+		assert!(matches!(last_event(), Event::DataUpdated { requested_at: None, .. }));
+	});
+}
+
+#[test]
+fn approval_removes_the_request_under_the_patient_key() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+
+		approve(DOCTOR, PATIENT);
+
+		assert!(RequestMap::<Test>::get(PATIENT).is_empty());
+		assert!(!RequestMap::<Test>::contains_key(DOCTOR));
+	});
+}
+
+#[test]
+fn cleanup_prunes_orphaned_requests_only() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		request(DOCTOR, PATIENT);
+
+		// Left behind under the Doctor's key by the old `remove_request`.
+		RequestMap::<Test>::insert(DOCTOR, BoundedVec::truncate_from(vec![OTHER_DOCTOR]));
+
+		let keys = BoundedVec::truncate_from(vec![DOCTOR, PATIENT]);
+		assert_noop!(
+			PatientModule::cleanup_orphaned_requests(RuntimeOrigin::signed(PATIENT), keys.clone()),
+			BadOrigin
+		);
+		assert_ok!(PatientModule::cleanup_orphaned_requests(RuntimeOrigin::root(), keys));
+
+		assert!(!RequestMap::<Test>::contains_key(DOCTOR));
+		assert_eq!(RequestMap::<Test>::get(PATIENT).into_inner(), vec![DOCTOR]);
+		System::assert_has_event(Event::OrphanedRequestsPruned { key: DOCTOR, removed: 1 }.into());
+		assert!(matches!(last_event(), Event::OrphanedRequestsPruned { key: PATIENT, removed: 0 }));
+	});
+}
+
+#[test]
+fn two_sided_approval_waits_for_the_doctor() {
+	new_test_ext().execute_with(|| {
+		TwoSidedConsent::set(true);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+
+		approve(DOCTOR, PATIENT);
+		assert!(PendingDoctorAcceptance::<Test>::contains_key(PATIENT, DOCTOR));
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).is_empty());
+		assert!(pallet_doctor::AprovedRequestMap::<Test>::get(DOCTOR).is_empty());
+		assert_noop!(
+			PatientModule::request_patient_data(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			Error::<Test>::AcceptancePending
+		);
+
+		assert_ok!(PatientModule::accept_patient(RuntimeOrigin::signed(DOCTOR), PATIENT));
+		assert!(!PendingDoctorAcceptance::<Test>::contains_key(PATIENT, DOCTOR));
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).into_inner(), vec![DOCTOR]);
+		assert_eq!(
+			pallet_doctor::AprovedRequestMap::<Test>::get(DOCTOR).into_inner(),
+			vec![PATIENT]
+		);
+		assert_eq!(
+			last_event(),
+			Event::DoctorAccepted { requester: DOCTOR, patient_account_id: PATIENT }
+		);
+	});
+}
+
+#[test]
+fn declined_approval_ends_the_request() {
+	new_test_ext().execute_with(|| {
+		TwoSidedConsent::set(true);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		assert_ok!(PatientModule::decline_patient(RuntimeOrigin::signed(DOCTOR), PATIENT));
+		assert!(!PendingDoctorAcceptance::<Test>::contains_key(PATIENT, DOCTOR));
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).is_empty());
+		assert!(pallet_doctor::RequestMap::<Test>::get(DOCTOR).is_empty());
+		assert_eq!(
+			last_event(),
+			Event::DoctorDeclined { requester: DOCTOR, patient_account_id: PATIENT }
+		);
+
+		assert_noop!(
+			PatientModule::accept_patient(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			Error::<Test>::NoPendingAcceptance
+		);
+
+		// The Doctor may ask again later.
+		request(DOCTOR, PATIENT);
+	});
+}
+
+#[test]
+fn recent_events_evict_the_oldest_entry() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+
+		System::set_block_number(2);
+		request(DOCTOR, PATIENT);
+		System::set_block_number(3);
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		System::set_block_number(4);
+		update(DOCTOR, PATIENT, 1);
+
+		assert_eq!(
+			RecentEvents::<Test>::get(PATIENT).into_inner(),
+			vec![
+				(1, EventKind::Registered),
+				(2, EventKind::RequestQueued),
+				(3, EventKind::RequestApproved),
+				(4, EventKind::DataUpdated),
+			]
+		);
+
+		System::set_block_number(5);
+		update(DOCTOR, PATIENT, 2);
+
+		assert_eq!(
+			RecentEvents::<Test>::get(PATIENT).into_inner(),
+			vec![
+				(2, EventKind::RequestQueued),
+				(3, EventKind::RequestApproved),
+				(4, EventKind::DataUpdated),
+				(5, EventKind::DataUpdated),
+			]
+		);
+		assert!(RecentEvents::<Test>::get(OTHER_PATIENT).is_empty());
+	});
+}
+
+#[test]
+fn privacy_admin_freezes_a_record() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		assert_noop!(
+			PatientModule::set_frozen(RuntimeOrigin::signed(DOCTOR), PATIENT, true),
+			pallet_access::Error::<Test>::NotAssigned
+		);
+
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(PRIVACY_ADMIN), PATIENT, true));
+		assert!(Frozen::<Test>::get(PATIENT));
+		assert_noop!(try_update(DOCTOR, PATIENT, 1), Error::<Test>::RecordFrozen);
+
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(COMPLIANCE), PATIENT, false));
+		update(DOCTOR, PATIENT, 1);
+	});
+}
+
+#[test]
+fn private_patient_rejects_new_requests() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+
+		assert_ok!(PatientModule::set_privacy(RuntimeOrigin::signed(PATIENT), true));
+		assert!(Private::<Test>::get(PATIENT));
+		assert_noop!(
+			PatientModule::request_patient_data(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			Error::<Test>::PatientPrivate
+		);
+
+		assert_ok!(PatientModule::set_privacy(RuntimeOrigin::signed(PATIENT), false));
+		request(DOCTOR, PATIENT);
+	});
+}
+
+#[test]
+fn writable_patients_skips_expired_and_read_only_approvals() {
+	new_test_ext().execute_with(|| {
+		let expired_patient = 3;
+		register_doctor(DOCTOR);
+		for patient in [PATIENT, OTHER_PATIENT, expired_patient] {
+			register_patient(patient);
+			approve(DOCTOR, patient);
+		}
+
+		ApprovalDetails::<Test>::insert(
+			OTHER_PATIENT,
+			DOCTOR,
+			Approval::<Test> { scope: Scope::ReadOnly, ..Default::default() },
+		);
+		ApprovalDetails::<Test>::insert(
+			expired_patient,
+			DOCTOR,
+			Approval::<Test> { expires_at: Some(5), ..Default::default() },
+		);
+		assert_eq!(PatientModule::writable_patients(DOCTOR), vec![PATIENT, expired_patient]);
+
+		System::set_block_number(5);
+		assert_eq!(PatientModule::writable_patients(DOCTOR), vec![PATIENT]);
+		assert_noop!(try_update(DOCTOR, OTHER_PATIENT, 1), Error::<Test>::ReadOnlyAccess);
+		assert_noop!(try_update(DOCTOR, expired_patient, 1), Error::<Test>::ApprovalExpired);
+		update(DOCTOR, PATIENT, 1);
+	});
+}
+
+#[test]
+fn counters_follow_requests_and_approvals() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		assert_eq!(PatientCount::<Test>::get(), 1);
+		assert_eq!(pallet_doctor::DoctorCount::<Test>::get(), 1);
+
+		request(DOCTOR, PATIENT);
+		assert_eq!(PendingRequestCount::<Test>::get(), 1);
+
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		assert_eq!(PendingRequestCount::<Test>::get(), 0);
+
+		register_doctor(OTHER_DOCTOR);
+		request(OTHER_DOCTOR, PATIENT);
+		assert_ok!(PatientModule::delete_patient_record(RuntimeOrigin::signed(PATIENT)));
+		assert_eq!(PendingRequestCount::<Test>::get(), 0);
+		assert_eq!(PatientCount::<Test>::get(), 0);
+
+		assert_ok!(PatientModule::restore_record(RuntimeOrigin::root(), PATIENT));
+		assert_eq!(PatientCount::<Test>::get(), 1);
+	});
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "PendingRequestCount decremented below zero"))]
+fn pending_request_count_stays_at_zero() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		request(DOCTOR, PATIENT);
+
+		// Lets the counter fall behind the queued request.
+		PendingRequestCount::<Test>::kill();
+
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+
+		assert_eq!(PendingRequestCount::<Test>::get(), 0);
+	});
+}
+
+#[test]
+fn self_registration_can_be_disabled() {
+	new_test_ext().execute_with(|| {
+		AllowPatientSelfRegistration::set(false);
+
+		assert_noop!(
+			PatientModule::register_patient_self(RuntimeOrigin::signed(PATIENT), None, None, None),
+			pallet_access::Error::<Test>::AccessDenied
+		);
+
+		assert_ok!(PatientModule::register_patient(
+			RuntimeOrigin::signed(PATIENT_ADMIN),
+			PATIENT,
+			None,
+			None,
+			None
+		));
+		assert!(DataMap::<Test>::contains_key(PATIENT));
+	});
+}
+
+#[test]
+fn self_registration_is_allowed_by_default() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PatientModule::register_patient_self(
+			RuntimeOrigin::signed(PATIENT),
+			None,
+			None,
+			None
+		));
+		assert!(DataMap::<Test>::contains_key(PATIENT));
+	});
+}
+
+#[test]
+fn removing_a_request_keeps_the_remaining_order() {
+	new_test_ext().execute_with(|| {
+		let third_doctor = 13;
+		register_patient(PATIENT);
+		for doctor in [DOCTOR, OTHER_DOCTOR, third_doctor] {
+			register_doctor(doctor);
+			request(doctor, PATIENT);
+		}
+
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			OTHER_DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+
+		assert_eq!(RequestMap::<Test>::get(PATIENT).into_inner(), vec![DOCTOR, third_doctor]);
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).into_inner(), vec![OTHER_DOCTOR]);
+	});
+}
+
+#[test]
+fn only_the_first_registration_is_free() {
+	new_test_ext().execute_with(|| {
+		let info =
+			PatientModule::register_patient_self(RuntimeOrigin::signed(PATIENT), None, None, None)
+				.unwrap();
+		assert_eq!(info.pays_fee, Pays::No);
+		assert!(HasRegisteredOnce::<Test>::get(PATIENT));
+
+		let err =
+			PatientModule::register_patient_self(RuntimeOrigin::signed(PATIENT), None, None, None)
+				.unwrap_err();
+		assert_eq!(err.error, Error::<Test>::AlreadyRegistered.into());
+		assert_eq!(err.post_info.pays_fee, Pays::Yes);
+	});
+}
+
+#[test]
+fn swap_hashes_exchanges_the_two_hashes() {
+	new_test_ext().execute_with(|| {
+		register_doctor(DOCTOR);
+		assert_noop!(
+			PatientModule::swap_hashes(RuntimeOrigin::signed(DOCTOR), PATIENT, None),
+			Error::<Test>::NoPatient
+		);
+
+		assert_ok!(PatientModule::register_patient_self(
+			RuntimeOrigin::signed(PATIENT),
+			Some(hash(1)),
+			Some(hash(2)),
+			None
+		));
+		approve(DOCTOR, PATIENT);
+
+		assert_ok!(PatientModule::swap_hashes(RuntimeOrigin::signed(DOCTOR), PATIENT, None));
+
+		let patient = DataMap::<Test>::get(PATIENT).unwrap();
+		assert_eq!(patient.personal_data_hash, Some(hash(2)));
+		assert_eq!(patient.data_hash, Some(hash(1)));
+		assert!(matches!(
+			last_event(),
+			Event::DataUpdated { requester: DOCTOR, patient_account_id: PATIENT, .. }
+		));
+	});
+}
+
+#[test]
+fn export_bundles_the_whole_patient_state() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(PatientModule::export_patient(PATIENT), None);
+
+		assert_ok!(PatientModule::register_patient_self(
+			RuntimeOrigin::signed(PATIENT),
+			Some(hash(1)),
+			Some(hash(2)),
+			None
+		));
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		request(OTHER_DOCTOR, PATIENT);
+		assert_ok!(PatientModule::set_privacy(RuntimeOrigin::signed(PATIENT), true));
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(PRIVACY_ADMIN), PATIENT, true));
+
+		let export = PatientModule::export_patient(PATIENT).unwrap();
+		assert_eq!(export.record, DataMap::<Test>::get(PATIENT).unwrap());
+		assert_eq!(export.record.data_hash, Some(hash(2)));
+		assert_eq!(export.pending_requests, vec![OTHER_DOCTOR]);
+		assert_eq!(
+			export.approvals,
+			vec![(DOCTOR, ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap())]
+		);
+		assert!(export.frozen);
+		assert!(export.private);
+	});
+}
+
+#[test]
+fn which_registered_answers_every_account_or_none() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+
+		assert_eq!(
+			PatientModule::which_registered(vec![PATIENT, 3, OTHER_PATIENT, 4]),
+			Some(vec![true, false, true, false])
+		);
+		assert_eq!(PatientModule::which_registered(vec![PATIENT; 5]), None);
+	});
+}
+
+#[test]
+fn approval_carries_the_granted_scope() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		request(DOCTOR, PATIENT);
+		request(OTHER_DOCTOR, PATIENT);
+
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadOnly,
+			None
+		));
+		assert!(matches!(
+			last_event(),
+			Event::RequestApproved { requester: DOCTOR, scope: Scope::ReadOnly, .. }
+		));
+		assert_eq!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().scope, Scope::ReadOnly);
+		assert_noop!(try_update(DOCTOR, PATIENT, 1), Error::<Test>::ReadOnlyAccess);
+
+		assert_ok!(PatientModule::approve_request_default(
+			RuntimeOrigin::signed(PATIENT),
+			OTHER_DOCTOR
+		));
+		assert_eq!(
+			ApprovalDetails::<Test>::get(PATIENT, OTHER_DOCTOR).unwrap().scope,
+			Scope::ReadWrite
+		);
+		update(OTHER_DOCTOR, PATIENT, 1);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+mod try_state {
+	use super::*;
+
+	/// Builds a Patient with an approved Doctor and a pending request of another one.
+	fn consistent_state() {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		request(OTHER_DOCTOR, PATIENT);
+		assert_ok!(PatientModule::do_try_state());
+	}
+
+	#[test]
+	fn request_of_an_unregistered_patient_fails() {
+		new_test_ext().execute_with(|| {
+			consistent_state();
+			DataMap::<Test>::remove(PATIENT);
+
+			assert_eq!(
+				PatientModule::do_try_state(),
+				Err("RequestMap holds requests for an unregistered patient")
+			);
+		});
+	}
+
+	#[test]
+	fn request_missing_on_the_doctor_side_fails() {
+		new_test_ext().execute_with(|| {
+			consistent_state();
+			pallet_doctor::RequestMap::<Test>::remove(OTHER_DOCTOR);
+
+			assert_eq!(
+				PatientModule::do_try_state(),
+				Err("RequestMap entry has no matching request on the doctor side")
+			);
+		});
+	}
+
+	#[test]
+	fn request_missing_on_the_patient_side_fails() {
+		new_test_ext().execute_with(|| {
+			consistent_state();
+			RequestMap::<Test>::remove(PATIENT);
+
+			assert_eq!(
+				PatientModule::do_try_state(),
+				Err("Doctor RequestMap entry has no matching request on the patient side")
+			);
+		});
+	}
+
+	#[test]
+	fn approval_missing_on_the_doctor_side_fails() {
+		new_test_ext().execute_with(|| {
+			consistent_state();
+			pallet_doctor::AprovedRequestMap::<Test>::remove(DOCTOR);
+
+			assert_eq!(
+				PatientModule::do_try_state(),
+				Err("AprovedRequestMap entry has no matching approval on the doctor side")
+			);
+		});
+	}
+
+	#[test]
+	fn approval_missing_on_the_patient_side_fails() {
+		new_test_ext().execute_with(|| {
+			consistent_state();
+			AprovedRequestMap::<Test>::remove(PATIENT);
+
+			assert_eq!(
+				PatientModule::do_try_state(),
+				Err("Doctor AprovedRequestMap entry has no matching approval on the patient side")
+			);
+		});
+	}
+
+	#[test]
+	fn doctor_both_requested_and_approved_fails() {
+		new_test_ext().execute_with(|| {
+			consistent_state();
+			RequestMap::<Test>::insert(PATIENT, accounts(vec![OTHER_DOCTOR, DOCTOR]));
+
+			assert_eq!(
+				PatientModule::do_try_state(),
+				Err("RequestMap lists a doctor that is already approved")
+			);
+		});
+	}
+
+	#[test]
+	fn scope_of_an_unknown_variant_fails() {
+		new_test_ext().execute_with(|| {
+			consistent_state();
+			// The approval of `DOCTOR`, with its scope set to the unassigned index 2.
+			let approval = (2u8, None::<u64>, 1u64).encode();
+			unhashed::put_raw(&ApprovalDetails::<Test>::hashed_key_for(PATIENT, DOCTOR), &approval);
+
+			assert_eq!(
+				PatientModule::do_try_state(),
+				Err("ApprovalDetails holds a scope of an unknown variant")
+			);
+		});
+	}
+
+	#[test]
+	fn approval_of_a_doctor_without_a_record_fails() {
+		new_test_ext().execute_with(|| {
+			consistent_state();
+			pallet_doctor::DataMap::<Test>::remove(DOCTOR);
+
+			assert_eq!(
+				PatientModule::do_try_state(),
+				Err("AprovedRequestMap approves a doctor without a record")
+			);
+		});
+	}
+}
+
+#[test]
+fn backup_hash_is_set_and_cleared_by_the_patient() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PatientModule::set_backup_hash(RuntimeOrigin::signed(PATIENT), Some(hash(9))),
+			Error::<Test>::NoPatient
+		);
+		register_patient(PATIENT);
+
+		assert_ok!(PatientModule::set_backup_hash(RuntimeOrigin::signed(PATIENT), Some(hash(9))));
+		assert_eq!(PatientModule::patient_data(PATIENT).unwrap().backup_data_hash, Some(hash(9)));
+		assert_eq!(
+			PatientModule::export_patient(PATIENT).unwrap().record.backup_data_hash,
+			Some(hash(9))
+		);
+		assert!(matches!(last_event(), Event::BackupHashSet { patient_account_id: PATIENT }));
+
+		assert_ok!(PatientModule::set_backup_hash(RuntimeOrigin::signed(PATIENT), None));
+		assert_eq!(DataMap::<Test>::get(PATIENT).unwrap().backup_data_hash, None);
+	});
+}
+
+#[test]
+fn upgrade_adds_an_empty_backup_hash_to_old_records() {
+	new_test_ext().execute_with(|| {
+		let old_record = (Some(hash(1)), Some(hash(2)), None::<Vec<u8>>).encode();
+		unhashed::put_raw(&DataMap::<Test>::hashed_key_for(PATIENT), &old_record);
+		StorageVersion::new(0).put::<PatientModule>();
+
+		PatientModule::on_runtime_upgrade();
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), 1));
+
+		let patient = DataMap::<Test>::get(PATIENT).unwrap();
+		assert_eq!(patient.personal_data_hash, Some(hash(1)));
+		assert_eq!(patient.data_hash, Some(hash(2)));
+		assert_eq!(patient.backup_data_hash, None);
+		assert_eq!(
+			PatientModule::on_chain_storage_version(),
+			PatientModule::current_storage_version()
+		);
+	});
+}
+
+#[test]
+fn updates_respect_the_cooldown() {
+	new_test_ext().execute_with(|| {
+		UpdateCooldown::set(3);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		update(DOCTOR, PATIENT, 1);
+		assert_eq!(PatientModule::last_update_block(PATIENT), Some(1));
+
+		System::set_block_number(3);
+		assert_noop!(try_update(DOCTOR, PATIENT, 2), Error::<Test>::UpdateTooSoon);
+
+		System::set_block_number(4);
+		update(DOCTOR, PATIENT, 2);
+		assert_eq!(PatientModule::last_update_block(PATIENT), Some(4));
+	});
+}
+
+#[test]
+fn downgraded_approval_rejects_updates() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		assert_noop!(
+			PatientModule::set_approval_scope(
+				RuntimeOrigin::signed(PATIENT),
+				DOCTOR,
+				Scope::ReadOnly
+			),
+			Error::<Test>::NotApproved
+		);
+
+		approve(DOCTOR, PATIENT);
+		update(DOCTOR, PATIENT, 1);
+
+		assert_ok!(PatientModule::set_approval_scope(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadOnly
+		));
+		assert!(matches!(
+			last_event(),
+			Event::ApprovalScopeChanged { requester: DOCTOR, scope: Scope::ReadOnly, .. }
+		));
+		assert_noop!(try_update(DOCTOR, PATIENT, 2), Error::<Test>::ReadOnlyAccess);
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).contains(&DOCTOR));
+	});
+}
+
+#[test]
+fn clear_all_requests_empties_both_sides() {
+	new_test_ext().execute_with(|| {
+		let third_doctor = 13;
+		register_patient(PATIENT);
+		for doctor in [DOCTOR, OTHER_DOCTOR, third_doctor] {
+			register_doctor(doctor);
+			request(doctor, PATIENT);
+		}
+
+		assert_ok!(PatientModule::clear_all_requests(RuntimeOrigin::signed(PATIENT)));
+
+		assert!(RequestMap::<Test>::get(PATIENT).is_empty());
+		for doctor in [DOCTOR, OTHER_DOCTOR, third_doctor] {
+			assert!(pallet_doctor::RequestMap::<Test>::get(doctor).is_empty());
+		}
+		assert_eq!(PendingRequestCount::<Test>::get(), 0);
+		assert!(matches!(
+			last_event(),
+			Event::AllRequestsCleared { patient_account_id: PATIENT, count: 3 }
+		));
+		assert_eq!(
+			RecentEvents::<Test>::get(PATIENT).last().map(|(_, kind)| *kind),
+			Some(EventKind::RequestDeclined)
+		);
+	});
+}
+
+#[test]
+fn clearing_an_empty_queue_records_nothing() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		let recent = RecentEvents::<Test>::get(PATIENT);
+
+		assert_ok!(PatientModule::clear_all_requests(RuntimeOrigin::signed(PATIENT)));
+
+		assert_eq!(RecentEvents::<Test>::get(PATIENT), recent);
+		assert!(matches!(last_event(), Event::AllRequestsCleared { count: 0, .. }));
+	});
+}
+
+#[test]
+fn filling_a_list_reports_which_list_is_full() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		let doctors = [11, 12, 13, 14, 15];
+		for doctor in doctors {
+			register_doctor(doctor);
+		}
+		for doctor in &doctors[..4] {
+			request(*doctor, PATIENT);
+		}
+		System::assert_has_event(
+			Event::ListFull { owner: PATIENT, list_kind: ListKind::Request }.into(),
+		);
+		assert_noop!(
+			PatientModule::request_patient_data(RuntimeOrigin::signed(15), PATIENT),
+			Error::<Test>::MaxListLengthReached
+		);
+
+		for doctor in &doctors[..4] {
+			assert_ok!(PatientModule::approve_request(
+				RuntimeOrigin::signed(PATIENT),
+				*doctor,
+				Scope::ReadWrite,
+				None
+			));
+		}
+		System::assert_has_event(
+			Event::ListFull { owner: PATIENT, list_kind: ListKind::Approved }.into(),
+		);
+	});
+}
+
+#[test]
+fn data_access_roles_combine_with_all_or_any() {
+	new_test_ext().execute_with(|| {
+		DataAccessRoles::set(vec![roles::DOCTOR, roles::PRIVACY_ADMIN]);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+
+		assert_noop!(
+			PatientModule::request_patient_data(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			pallet_access::Error::<Test>::AccessDenied
+		);
+
+		RequireAllDataAccessRoles::set(false);
+		request(DOCTOR, PATIENT);
+
+		RequireAllDataAccessRoles::set(true);
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), DOCTOR, roles::PRIVACY_ADMIN));
+		register_patient(OTHER_PATIENT);
+		request(DOCTOR, OTHER_PATIENT);
+	});
+}
+
+#[test]
+fn deceased_record_is_readable_but_rejects_writes() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		update(DOCTOR, PATIENT, 1);
+		request(OTHER_DOCTOR, PATIENT);
+
+		assert_noop!(
+			PatientModule::mark_deceased(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			pallet_access::Error::<Test>::NotAssigned
+		);
+		assert_ok!(PatientModule::mark_deceased(RuntimeOrigin::signed(COMPLIANCE), PATIENT));
+		assert!(matches!(
+			last_event(),
+			Event::PatientMarkedDeceased { patient_account_id: PATIENT }
+		));
+
+		assert_noop!(try_update(DOCTOR, PATIENT, 2), Error::<Test>::RecordFrozenDeceased);
+		assert_noop!(
+			PatientModule::approve_request(
+				RuntimeOrigin::signed(PATIENT),
+				OTHER_DOCTOR,
+				Scope::ReadWrite,
+				None
+			),
+			Error::<Test>::RecordFrozenDeceased
+		);
+		register_doctor(13);
+		assert_noop!(
+			PatientModule::request_patient_data(RuntimeOrigin::signed(13), PATIENT),
+			Error::<Test>::RecordFrozenDeceased
+		);
+
+		let patient = PatientModule::patient_data(PATIENT).unwrap();
+		assert!(patient.deceased);
+		assert_eq!(patient.data_hash, Some(hash(1)));
+		assert!(PatientModule::export_patient(PATIENT).is_some());
+	});
+}
+
+#[test]
+fn upgrade_adds_the_deceased_flag_to_backed_up_records() {
+	new_test_ext().execute_with(|| {
+		let old_record = (Some(hash(1)), Some(hash(2)), None::<Vec<u8>>, Some(hash(3))).encode();
+		unhashed::put_raw(&DataMap::<Test>::hashed_key_for(PATIENT), &old_record);
+		StorageVersion::new(1).put::<PatientModule>();
+
+		PatientModule::on_runtime_upgrade();
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), 1));
+
+		let patient = DataMap::<Test>::get(PATIENT).unwrap();
+		assert_eq!(patient.backup_data_hash, Some(hash(3)));
+		assert!(!patient.deceased);
+		assert_eq!(
+			PatientModule::on_chain_storage_version(),
+			PatientModule::current_storage_version()
+		);
+	});
+}
+
+#[test]
+fn approval_takes_effect_after_the_delay() {
+	new_test_ext().execute_with(|| {
+		ApprovalDelay::set(3);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		assert_eq!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().effective_at, 4);
+
+		assert_noop!(try_update(DOCTOR, PATIENT, 1), Error::<Test>::ApprovalNotYetEffective);
+		assert!(PatientModule::writable_patients(DOCTOR).is_empty());
+
+		System::set_block_number(4);
+		assert_eq!(PatientModule::writable_patients(DOCTOR), vec![PATIENT]);
+		update(DOCTOR, PATIENT, 1);
+	});
+}
+
+#[test]
+fn upgrade_makes_existing_approvals_effective_at_once() {
+	new_test_ext().execute_with(|| {
+		let old_approval = (Scope::ReadOnly, Some(9u64)).encode();
+		unhashed::put_raw(&ApprovalDetails::<Test>::hashed_key_for(PATIENT, DOCTOR), &old_approval);
+		StorageVersion::new(2).put::<PatientModule>();
+
+		PatientModule::on_runtime_upgrade();
+
+		assert_eq!(
+			ApprovalDetails::<Test>::get(PATIENT, DOCTOR),
+			Some(Approval { scope: Scope::ReadOnly, expires_at: Some(9), effective_at: 0 })
+		);
+	});
+}
+
+#[test]
+fn acknowledging_clears_unacknowledged_updates() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		assert!(!PatientModule::has_unacknowledged(PATIENT));
+
+		update(DOCTOR, PATIENT, 1);
+		assert!(PatientModule::has_unacknowledged(PATIENT));
+
+		assert_ok!(PatientModule::acknowledge_update(RuntimeOrigin::signed(PATIENT)));
+		assert!(matches!(
+			last_event(),
+			Event::UpdateAcknowledged { patient_account_id: PATIENT, update_count: 1 }
+		));
+		assert!(!PatientModule::has_unacknowledged(PATIENT));
+
+		// A write in the same block as the acknowledgement is still reported.
+		update(DOCTOR, PATIENT, 2);
+		assert!(PatientModule::has_unacknowledged(PATIENT));
+	});
+}
+
+#[test]
+fn batch_registration_skips_registered_patients() {
+	new_test_ext().execute_with(|| {
+		let third_patient = 3;
+		register_patient(PATIENT);
+		let entries = BoundedVec::truncate_from(vec![
+			(PATIENT, None, None),
+			(OTHER_PATIENT, Some(hash(1)), None),
+			(third_patient, None, Some(hash(2))),
+		]);
+
+		assert_noop!(
+			PatientModule::register_patients_batch(RuntimeOrigin::signed(PATIENT), entries.clone()),
+			pallet_access::Error::<Test>::NotAssigned
+		);
+		assert_ok!(PatientModule::register_patients_batch(
+			RuntimeOrigin::signed(PATIENT_ADMIN),
+			entries
+		));
+
+		assert!(matches!(last_event(), Event::BatchRegisterResult { registered: 2, skipped: 1 }));
+		assert_eq!(PatientCount::<Test>::get(), 3);
+		assert_eq!(DataMap::<Test>::get(OTHER_PATIENT).unwrap().personal_data_hash, Some(hash(1)));
+		assert_eq!(DataMap::<Test>::get(third_patient).unwrap().data_hash, Some(hash(2)));
+	});
+}
+
+#[test]
+fn approval_details_reports_scope_and_expiry() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		ApprovalDetails::<Test>::insert(
+			PATIENT,
+			DOCTOR,
+			Approval::<Test> { scope: Scope::ReadOnly, expires_at: Some(9), ..Default::default() },
+		);
+
+		assert_eq!(
+			PatientModule::approval_details(PATIENT, DOCTOR),
+			Some((Scope::ReadOnly, Some(9)))
+		);
+		assert_eq!(
+			PatientModule::approval_details(PATIENT, OTHER_DOCTOR),
+			Some((Scope::ReadWrite, None))
+		);
+		assert_eq!(PatientModule::approval_details(OTHER_PATIENT, DOCTOR), None);
+	});
+}
+
+#[test]
+fn disabled_call_is_rejected_while_others_still_work() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		request(OTHER_DOCTOR, PATIENT);
+
+		assert_noop!(
+			PatientModule::set_call_enabled(RuntimeOrigin::signed(PATIENT), 3, false),
+			BadOrigin
+		);
+		assert_ok!(PatientModule::set_call_enabled(RuntimeOrigin::root(), 3, false));
+		assert!(matches!(
+			last_event(),
+			Event::CallEnabledChanged { call_index: 3, enabled: false }
+		));
+
+		assert_noop!(
+			PatientModule::request_patient_data(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			Error::<Test>::CallDisabled
+		);
+		register_patient(OTHER_PATIENT);
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			OTHER_DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+
+		assert_ok!(PatientModule::set_call_enabled(RuntimeOrigin::root(), 3, true));
+		assert!(!PatientModule::is_call_disabled(3));
+		request(DOCTOR, PATIENT);
+	});
+}
+
+#[test]
+fn doctor_load_counts_each_relationship() {
+	new_test_ext().execute_with(|| {
+		let read_only_patient = 3;
+		let pending_patient = 4;
+		register_doctor(DOCTOR);
+		for patient in [PATIENT, OTHER_PATIENT, read_only_patient] {
+			register_patient(patient);
+			approve(DOCTOR, patient);
+		}
+		register_patient(pending_patient);
+		request(DOCTOR, pending_patient);
+
+		ApprovalDetails::<Test>::insert(
+			read_only_patient,
+			DOCTOR,
+			Approval::<Test> { scope: Scope::ReadOnly, ..Default::default() },
+		);
+		ApprovalDetails::<Test>::insert(
+			OTHER_PATIENT,
+			DOCTOR,
+			Approval::<Test> { expires_at: Some(5), ..Default::default() },
+		);
+		System::set_block_number(5);
+
+		assert_eq!(
+			PatientModule::doctor_load(DOCTOR),
+			DoctorLoad { pending_requests: 1, approved_patients: 3, writable_patients: 1 }
+		);
+		assert_eq!(PatientModule::doctor_load(OTHER_DOCTOR), DoctorLoad::default());
+	});
+}
+
+#[test]
+fn request_of_an_unregistered_doctor_writes_nothing() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), DOCTOR, roles::DOCTOR));
+
+		assert_noop!(
+			PatientModule::request_patient_data(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			pallet_doctor::Error::<Test>::NotRegistered
+		);
+		assert!(RequestMap::<Test>::get(PATIENT).is_empty());
+		assert!(RequestedAt::<Test>::get(PATIENT, DOCTOR).is_none());
+		assert_eq!(PendingRequestCount::<Test>::get(), 0);
+	});
+}
+
+#[test]
+fn accessible_patients_lists_explicit_approvals() {
+	new_test_ext().execute_with(|| {
+		register_doctor(DOCTOR);
+		for patient in [PATIENT, OTHER_PATIENT, 3] {
+			register_patient(patient);
+		}
+		approve(DOCTOR, PATIENT);
+		approve(DOCTOR, OTHER_PATIENT);
+		request(DOCTOR, 3);
+
+		assert_eq!(PatientModule::accessible_patients(DOCTOR), vec![PATIENT, OTHER_PATIENT]);
+		assert!(PatientModule::accessible_patients(OTHER_DOCTOR).is_empty());
+	});
+}
+
+fn accounts(ids: Vec<u64>) -> BoundedVec<u64, <Test as pallet_doctor::Config>::MaxListLength> {
+	BoundedVec::truncate_from(ids)
+}
+
+#[test]
+fn care_team_members_read_team_patients() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+
+		assert_noop!(
+			PatientModule::set_care_team(
+				RuntimeOrigin::signed(PRIVACY_ADMIN),
+				1,
+				accounts(vec![PATIENT]),
+				accounts(vec![DOCTOR])
+			),
+			BadOrigin
+		);
+		assert_ok!(PatientModule::set_care_team(
+			RuntimeOrigin::root(),
+			1,
+			accounts(vec![PATIENT]),
+			accounts(vec![DOCTOR])
+		));
+		assert!(matches!(last_event(), Event::CareTeamUpdated { team_id: 1 }));
+
+		assert!(PatientModule::is_approved(PATIENT, DOCTOR));
+		assert!(!PatientModule::is_approved(OTHER_PATIENT, DOCTOR));
+		assert!(!PatientModule::is_approved(PATIENT, OTHER_DOCTOR));
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).is_empty());
+
+		assert_ok!(PatientModule::set_care_team(
+			RuntimeOrigin::root(),
+			1,
+			accounts(vec![PATIENT]),
+			accounts(vec![])
+		));
+
+		assert!(!PatientModule::is_approved(PATIENT, DOCTOR));
+	});
+}
+
+#[test]
+fn accessible_patients_joins_approvals_and_care_teams() {
+	new_test_ext().execute_with(|| {
+		for patient in [PATIENT, OTHER_PATIENT, 3] {
+			register_patient(patient);
+		}
+		register_doctor(DOCTOR);
+
+		approve(DOCTOR, PATIENT);
+		approve(DOCTOR, OTHER_PATIENT);
+		assert_ok!(PatientModule::set_care_team(
+			RuntimeOrigin::root(),
+			1,
+			accounts(vec![OTHER_PATIENT, 3]),
+			accounts(vec![DOCTOR, OTHER_DOCTOR])
+		));
+
+		assert_eq!(PatientModule::accessible_patients(DOCTOR), vec![PATIENT, OTHER_PATIENT, 3]);
+		assert_eq!(PatientModule::accessible_patients(OTHER_DOCTOR), vec![OTHER_PATIENT, 3]);
+	});
+}
+
+fn high_approval_alerts() -> usize {
+	System::events()
+		.iter()
+		.filter(|record| {
+			matches!(record.event, RuntimeEvent::PatientModule(Event::HighApprovalCount { .. }))
+		})
+		.count()
+}
+
+#[test]
+fn approval_alert_fires_once_on_crossing_the_threshold() {
+	new_test_ext().execute_with(|| {
+		ApprovalAlertThreshold::set(2);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		register_doctor(3);
+
+		approve(DOCTOR, PATIENT);
+		assert_eq!(high_approval_alerts(), 0);
+
+		approve(OTHER_DOCTOR, PATIENT);
+		assert_eq!(high_approval_alerts(), 1);
+		assert_eq!(LastAlertedCount::<Test>::get(PATIENT), 2);
+
+		approve(3, PATIENT);
+		assert_eq!(high_approval_alerts(), 1);
+	});
+}
+
+#[test]
+fn approval_alert_is_off_at_zero() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+
+		approve(DOCTOR, PATIENT);
+
+		assert_eq!(high_approval_alerts(), 0);
+	});
+}
+
+#[test]
+fn revoke_all_access_clears_both_sides() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		for doctor in [DOCTOR, OTHER_DOCTOR, 3] {
+			register_doctor(doctor);
+			approve(doctor, PATIENT);
+		}
+
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+
+		assert!(matches!(
+			last_event(),
+			Event::AllAccessRevoked { patient_account_id: PATIENT, count: 3 }
+		));
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).is_empty());
+		for doctor in [DOCTOR, OTHER_DOCTOR, 3] {
+			assert!(pallet_doctor::AprovedRequestMap::<Test>::get(doctor).is_empty());
+			assert!(ApprovalDetails::<Test>::get(PATIENT, doctor).is_none());
+		}
+		assert_noop!(try_update(DOCTOR, PATIENT, 1), Error::<Test>::NotApproved);
+	});
+}
+
+#[test]
+fn approval_alert_fires_again_after_dropping_below_the_threshold() {
+	new_test_ext().execute_with(|| {
+		ApprovalAlertThreshold::set(2);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		assert_eq!(high_approval_alerts(), 1);
+
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+		assert_eq!(LastAlertedCount::<Test>::get(PATIENT), 0);
+
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		assert_eq!(high_approval_alerts(), 2);
+	});
+}
+
+#[test]
+fn repeated_idempotency_key_is_a_no_op() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		assert_ok!(update_with_key(DOCTOR, PATIENT, 1, 1));
+		assert_ok!(update_with_key(DOCTOR, PATIENT, 2, 1));
+		assert_eq!(DataMap::<Test>::get(PATIENT).unwrap().data_hash, Some(hash(1)));
+
+		assert_ok!(update_with_key(DOCTOR, PATIENT, 2, 2));
+		assert_eq!(DataMap::<Test>::get(PATIENT).unwrap().data_hash, Some(hash(2)));
+		assert_eq!(SeenKeys::<Test>::get(DOCTOR).into_inner(), vec![[1; 16], [2; 16]]);
+	});
+}
+
+#[test]
+fn oldest_idempotency_key_is_evicted_when_full() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		for key in 1..=5 {
+			assert_ok!(update_with_key(DOCTOR, PATIENT, key, key));
+		}
+		assert_eq!(
+			SeenKeys::<Test>::get(DOCTOR).into_inner(),
+			vec![[2; 16], [3; 16], [4; 16], [5; 16]]
+		);
+
+		assert_ok!(update_with_key(DOCTOR, PATIENT, 6, 1));
+		assert_eq!(DataMap::<Test>::get(PATIENT).unwrap().data_hash, Some(hash(6)));
+	});
+}
+
+#[test]
+fn registered_patients_receive_the_patient_role() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		System::assert_has_event(
+			pallet_access::Event::RoleAssigned { user: PATIENT, role: roles::PATIENT }.into(),
+		);
+		assert_ok!(AccessModule::validate_role(PATIENT, roles::PATIENT));
+
+		assert_ok!(PatientModule::register_patient(
+			RuntimeOrigin::signed(PATIENT_ADMIN),
+			OTHER_PATIENT,
+			None,
+			None,
+			None
+		));
+		assert_ok!(AccessModule::validate_role(OTHER_PATIENT, roles::PATIENT));
+	});
+}
+
+#[test]
+fn registering_a_patient_role_holder_keeps_the_role() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), PATIENT, roles::PATIENT));
+
+		register_patient(PATIENT);
+
+		assert_ok!(AccessModule::validate_role(PATIENT, roles::PATIENT));
+	});
+}
+
+#[test]
+fn care_team_patients_are_paged() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PatientModule::set_care_team(
+			RuntimeOrigin::root(),
+			1,
+			accounts(vec![1, 2, 3, 4]),
+			accounts(vec![DOCTOR])
+		));
+
+		assert_eq!(PatientModule::care_team_size(1), 4);
+		assert_eq!(PatientModule::care_team_patients_page(1, 0, 3), vec![1, 2, 3]);
+		assert_eq!(PatientModule::care_team_patients_page(1, 3, 3), vec![4]);
+		assert!(PatientModule::care_team_patients_page(1, 4, 3).is_empty());
+		assert!(PatientModule::care_team_patients_page(1, u32::MAX, 3).is_empty());
+
+		assert_eq!(PatientModule::care_team_size(2), 0);
+		assert!(PatientModule::care_team_patients_page(2, 0, 3).is_empty());
+	});
+}
+
+#[test]
+fn blocked_doctor_can_not_be_approved_until_unblocked() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		request(DOCTOR, PATIENT);
+
+		assert_ok!(PatientModule::set_doctor_blocked(RuntimeOrigin::signed(PATIENT), DOCTOR, true));
+		assert!(matches!(last_event(), Event::BlockListChanged { blocked: true, .. }));
+		assert_noop!(
+			PatientModule::approve_request(
+				RuntimeOrigin::signed(PATIENT),
+				DOCTOR,
+				Scope::ReadWrite,
+				None
+			),
+			Error::<Test>::RequesterBlocked
+		);
+
+		assert_ok!(PatientModule::set_doctor_blocked(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			false
+		));
+		assert!(!BlockList::<Test>::contains_key(PATIENT, DOCTOR));
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).into_inner(), vec![DOCTOR]);
+	});
+}
+
+#[test]
+fn doctor_blocked_after_approval_can_not_accept() {
+	new_test_ext().execute_with(|| {
+		TwoSidedConsent::set(true);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		assert_ok!(PatientModule::set_doctor_blocked(RuntimeOrigin::signed(PATIENT), DOCTOR, true));
+
+		assert_noop!(
+			PatientModule::accept_patient(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			Error::<Test>::RequesterBlocked
+		);
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).is_empty());
+	});
+}
+
+#[test]
+fn unfreezing_needs_a_quorum_of_distinct_officers() {
+	new_test_ext().execute_with(|| {
+		let other_officer = 23;
+		UnfreezeQuorum::set(2);
+		assert_ok!(AccessModule::assign(RuntimeOrigin::root(), other_officer, roles::COMPLIANCE));
+		register_patient(PATIENT);
+
+		assert_noop!(
+			PatientModule::set_frozen(RuntimeOrigin::signed(COMPLIANCE), PATIENT, false),
+			Error::<Test>::NotFrozen
+		);
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(PRIVACY_ADMIN), PATIENT, true));
+		assert_noop!(
+			PatientModule::set_frozen(RuntimeOrigin::signed(PRIVACY_ADMIN), PATIENT, false),
+			pallet_access::Error::<Test>::NotAssigned
+		);
+
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(COMPLIANCE), PATIENT, false));
+		assert!(matches!(last_event(), Event::UnfreezeProgress { approvals: 1, quorum: 2, .. }));
+		assert!(Frozen::<Test>::get(PATIENT));
+		assert_noop!(
+			PatientModule::set_frozen(RuntimeOrigin::signed(COMPLIANCE), PATIENT, false),
+			Error::<Test>::AlreadyApprovedUnfreeze
+		);
+
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(other_officer), PATIENT, false));
+		assert!(matches!(last_event(), Event::FrozenStateChanged { frozen: false, .. }));
+		assert!(!Frozen::<Test>::get(PATIENT));
+		assert!(UnfreezeApprovals::<Test>::get(PATIENT).is_empty());
+	});
+}
+
+#[test]
+fn refreezing_discards_collected_unfreeze_approvals() {
+	new_test_ext().execute_with(|| {
+		UnfreezeQuorum::set(2);
+		register_patient(PATIENT);
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(PRIVACY_ADMIN), PATIENT, true));
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(COMPLIANCE), PATIENT, false));
+
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(PRIVACY_ADMIN), PATIENT, true));
+
+		assert!(UnfreezeApprovals::<Test>::get(PATIENT).is_empty());
+	});
+}
+
+#[test]
+fn request_and_approval_events_carry_the_specialty() {
+	new_test_ext().execute_with(|| {
+		let cardiology = *b"cardiolo";
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		assert_ok!(DoctorModule::set_specialty(RuntimeOrigin::signed(DOCTOR), Some(cardiology)));
+
+		request(DOCTOR, PATIENT);
+		assert!(matches!(
+			last_event(),
+			Event::RequestQueued { specialty: Some(s), .. } if s == cardiology
+		));
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		assert!(matches!(
+			last_event(),
+			Event::RequestApproved { specialty: Some(s), .. } if s == cardiology
+		));
+
+		request(OTHER_DOCTOR, PATIENT);
+		assert!(matches!(last_event(), Event::RequestQueued { specialty: None, .. }));
+	});
+}
+
+#[test]
+fn content_type_is_stored_with_the_data_hash() {
+	new_test_ext().execute_with(|| {
+		let pdf = *b"application/pdf\0";
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		assert_eq!(PatientModule::patient_data(PATIENT).unwrap().content_type, [0u8; 16]);
+
+		assert_ok!(PatientModule::update_patient_data(
+			RuntimeOrigin::signed(DOCTOR),
+			PATIENT,
+			Some(hash(1)),
+			None,
+			None,
+			pdf,
+			None,
+			None,
+			None
+		));
+
+		assert_eq!(PatientModule::patient_data(PATIENT).unwrap().content_type, pdf);
+		assert!(
+			matches!(last_event(), Event::DataUpdated { content_type, .. } if content_type == pdf)
+		);
+
+		assert_ok!(PatientModule::swap_hashes(RuntimeOrigin::signed(DOCTOR), PATIENT, None));
+		assert_eq!(PatientModule::patient_data(PATIENT).unwrap().content_type, pdf);
+	});
+}
+
+#[test]
+fn upgrade_adds_an_unknown_content_type_to_old_records() {
+	new_test_ext().execute_with(|| {
+		let old_record =
+			(Some(hash(1)), Some(hash(2)), None::<Vec<u8>>, Some(hash(3)), true).encode();
+		unhashed::put_raw(&DataMap::<Test>::hashed_key_for(PATIENT), &old_record);
+		StorageVersion::new(3).put::<PatientModule>();
+
+		PatientModule::on_runtime_upgrade();
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), 1));
+
+		let patient = DataMap::<Test>::get(PATIENT).unwrap();
+		assert_eq!(patient.backup_data_hash, Some(hash(3)));
+		assert!(patient.deceased);
+		assert_eq!(patient.content_type, [0u8; 16]);
+		assert_eq!(
+			PatientModule::on_chain_storage_version(),
+			PatientModule::current_storage_version()
+		);
+	});
+}
+
+#[test]
+fn submitted_hashes_must_carry_the_required_prefix() {
+	new_test_ext().execute_with(|| {
+		RequireCidPrefix::set(vec![1, 1]);
+		register_doctor(DOCTOR);
+
+		assert_noop!(
+			PatientModule::register_patient_self(
+				RuntimeOrigin::signed(PATIENT),
+				Some(hash(2)),
+				None,
+				None
+			),
+			Error::<Test>::InvalidHashFormat
+		);
+		assert_ok!(PatientModule::register_patient_self(
+			RuntimeOrigin::signed(PATIENT),
+			Some(hash(1)),
+			None,
+			None
+		));
+		approve(DOCTOR, PATIENT);
+
+		assert_noop!(try_update(DOCTOR, PATIENT, 2), Error::<Test>::InvalidHashFormat);
+		update(DOCTOR, PATIENT, 1);
+		assert_noop!(
+			PatientModule::set_backup_hash(RuntimeOrigin::signed(PATIENT), Some(hash(2))),
+			Error::<Test>::InvalidHashFormat
+		);
+	});
+}
+
+#[test]
+fn empty_prefix_accepts_any_hash() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		update(DOCTOR, PATIENT, 2);
+		assert_ok!(PatientModule::set_backup_hash(RuntimeOrigin::signed(PATIENT), Some(hash(3))));
+	});
+}
+
+#[test]
+fn clinician_joins_care_teams_up_to_the_cap() {
+	new_test_ext().execute_with(|| {
+		for team_id in 1..=2 {
+			assert_ok!(PatientModule::set_care_team(
+				RuntimeOrigin::root(),
+				team_id,
+				accounts(vec![PATIENT]),
+				accounts(vec![DOCTOR])
+			));
+		}
+		assert_eq!(ClinicianTeams::<Test>::get(DOCTOR).into_inner(), vec![1, 2]);
+
+		assert_noop!(
+			PatientModule::set_care_team(
+				RuntimeOrigin::root(),
+				3,
+				accounts(vec![OTHER_PATIENT]),
+				accounts(vec![DOCTOR, OTHER_DOCTOR])
+			),
+			Error::<Test>::TooManyTeams
+		);
+		assert!(!PatientModule::is_approved(OTHER_PATIENT, DOCTOR));
+
+		// Leaving a team frees a place for another one.
+		assert_ok!(PatientModule::set_care_team(
+			RuntimeOrigin::root(),
+			1,
+			accounts(vec![PATIENT]),
+			accounts(vec![])
+		));
+		assert_ok!(PatientModule::set_care_team(
+			RuntimeOrigin::root(),
+			3,
+			accounts(vec![OTHER_PATIENT]),
+			accounts(vec![DOCTOR])
+		));
+		assert_eq!(ClinicianTeams::<Test>::get(DOCTOR).into_inner(), vec![2, 3]);
+		assert!(PatientModule::is_approved(OTHER_PATIENT, DOCTOR));
+	});
+}
+
+#[test]
+fn upgrade_indexes_existing_care_team_members() {
+	new_test_ext().execute_with(|| {
+		CareTeams::<Test>::insert(1, accounts(vec![PATIENT]));
+		TeamMembers::<Test>::insert(1, accounts(vec![DOCTOR, OTHER_DOCTOR]));
+		TeamMembers::<Test>::insert(2, accounts(vec![DOCTOR]));
+		StorageVersion::new(4).put::<PatientModule>();
+
+		PatientModule::on_runtime_upgrade();
+
+		assert_eq!(ClinicianTeams::<Test>::get(DOCTOR).len(), 2);
+		assert_eq!(ClinicianTeams::<Test>::get(OTHER_DOCTOR).into_inner(), vec![1]);
+		assert!(PatientModule::is_approved(PATIENT, OTHER_DOCTOR));
+	});
+}
+
+#[test]
+fn change_detection_follows_updates() {
+	new_test_ext().execute_with(|| {
+		register_doctor(DOCTOR);
+		register_patient(PATIENT);
+		approve(DOCTOR, PATIENT);
+		assert!(!PatientModule::has_changed_since(PATIENT, 0));
+		assert_eq!(PatientModule::previous_hash(PATIENT), None);
+
+		System::set_block_number(2);
+		update(DOCTOR, PATIENT, 1);
+		assert_eq!(PatientModule::previous_hash(PATIENT), None);
+
+		System::set_block_number(4);
+		update(DOCTOR, PATIENT, 2);
+		// Writing the same hash again leaves the previous one in place.
+		update(DOCTOR, PATIENT, 2);
+		assert_eq!(PatientModule::previous_hash(PATIENT), Some(hash(1)));
+
+		System::set_block_number(6);
+		update(DOCTOR, PATIENT, 3);
+		assert_eq!(PatientModule::previous_hash(PATIENT), Some(hash(2)));
+
+		assert!(PatientModule::has_changed_since(PATIENT, 5));
+		assert!(!PatientModule::has_changed_since(PATIENT, 6));
+	});
+}
+
+#[test]
+fn approved_doctor_confirms_access_instead_of_requesting() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		assert_noop!(
+			PatientModule::confirm_access(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			Error::<Test>::NotApproved
+		);
+
+		approve(DOCTOR, PATIENT);
+		assert_noop!(
+			PatientModule::request_patient_data(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			Error::<Test>::AlreadyApproved
+		);
+
+		assert_ok!(PatientModule::confirm_access(RuntimeOrigin::signed(DOCTOR), PATIENT));
+		assert!(matches!(
+			last_event(),
+			Event::AlreadyHasAccess { doctor_account_id: DOCTOR, patient_account_id: PATIENT }
+		));
+		assert!(RequestMap::<Test>::get(PATIENT).is_empty());
+	});
+}
+
+#[test]
+fn required_update_reason_applies_to_updates_and_swaps() {
+	new_test_ext().execute_with(|| {
+		let write = |reason_hash| {
+			PatientModule::update_patient_data(
+				RuntimeOrigin::signed(DOCTOR),
+				PATIENT,
+				Some(hash(1)),
+				None,
+				None,
+				[0u8; 16],
+				reason_hash,
+				None,
+				None,
+			)
+		};
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		// Off by default, an update without a reason goes through.
+		assert_ok!(write(None));
+
+		assert_ok!(PatientModule::set_require_update_reason(RuntimeOrigin::signed(PATIENT), true));
+		assert!(PatientModule::requires_update_reason(PATIENT));
+		assert_noop!(write(None), Error::<Test>::ReasonRequired);
+		assert_noop!(
+			PatientModule::swap_hashes(RuntimeOrigin::signed(DOCTOR), PATIENT, None),
+			Error::<Test>::ReasonRequired
+		);
+
+		assert_ok!(write(Some(hash(9))));
+		assert!(matches!(
+			last_event(),
+			Event::DataUpdated { reason_hash: Some(reason), .. } if reason == hash(9)
+		));
+		assert_ok!(PatientModule::swap_hashes(
+			RuntimeOrigin::signed(DOCTOR),
+			PATIENT,
+			Some(hash(8))
+		));
+
+		assert_ok!(PatientModule::set_require_update_reason(RuntimeOrigin::signed(PATIENT), false));
+		assert_ok!(write(None));
+	});
+}
+
+#[test]
+fn approval_stores_a_receipt_until_revoked() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		let expected = <Test as frame_system::Config>::Hashing::hash_of(&(
+			PATIENT,
+			DOCTOR,
+			1u64,
+			Scope::ReadWrite,
+		));
+		assert_eq!(PatientModule::approval_receipt(PATIENT, DOCTOR), Some(expected));
+		assert!(matches!(
+			last_event(),
+			Event::RequestApproved { receipt, .. } if receipt == expected
+		));
+
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+		assert!(ApprovalReceipts::<Test>::get(PATIENT, DOCTOR).is_none());
+	});
+}
+
+#[test]
+fn compliance_officer_force_revokes_a_single_approval() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+
+		assert_noop!(
+			PatientModule::force_revoke_access(RuntimeOrigin::signed(PATIENT), PATIENT, DOCTOR),
+			pallet_access::Error::<Test>::NotAssigned
+		);
+		assert_ok!(PatientModule::force_revoke_access(
+			RuntimeOrigin::signed(COMPLIANCE),
+			PATIENT,
+			DOCTOR
+		));
+
+		System::assert_last_event(
+			Event::AccessForceRevoked {
+				patient_account_id: PATIENT,
+				doctor_account_id: DOCTOR,
+				by: COMPLIANCE,
+			}
+			.into(),
+		);
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).into_inner(), vec![OTHER_DOCTOR]);
+		assert!(!DoctorModule::approved_request_list(DOCTOR).contains(&PATIENT));
+		assert!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).is_none());
+		assert!(ApprovalReceipts::<Test>::get(PATIENT, DOCTOR).is_none());
+
+		assert_noop!(
+			PatientModule::force_revoke_access(RuntimeOrigin::signed(COMPLIANCE), PATIENT, DOCTOR),
+			Error::<Test>::NotApproved
+		);
+	});
+}
+
+#[test]
+fn deleted_record_is_tombstoned_until_restored() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		request(OTHER_DOCTOR, PATIENT);
+		let record = DataMap::<Test>::get(PATIENT).unwrap();
+
+		assert_ok!(PatientModule::delete_patient_record(RuntimeOrigin::signed(PATIENT)));
+		System::assert_last_event(
+			Event::PatientRecordDeleted { patient_account_id: PATIENT }.into(),
+		);
+		assert!(DataMap::<Test>::get(PATIENT).is_none());
+		assert_eq!(Tombstones::<Test>::get(PATIENT), Some((1, record.clone())));
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).is_empty());
+		assert!(RequestMap::<Test>::get(PATIENT).is_empty());
+		assert!(!DoctorModule::approved_request_list(DOCTOR).contains(&PATIENT));
+		assert!(!DoctorModule::request_list(OTHER_DOCTOR).contains(&PATIENT));
+
+		assert_noop!(
+			PatientModule::restore_record(RuntimeOrigin::signed(PATIENT), PATIENT),
+			BadOrigin
+		);
+		assert_ok!(PatientModule::restore_record(RuntimeOrigin::root(), PATIENT));
+		System::assert_last_event(Event::RecordRestored { patient_account_id: PATIENT }.into());
+		assert_eq!(DataMap::<Test>::get(PATIENT), Some(record));
+		assert!(Tombstones::<Test>::get(PATIENT).is_none());
+		// Access ended by the deletion stays ended.
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).is_empty());
+		assert_noop!(
+			PatientModule::restore_record(RuntimeOrigin::root(), OTHER_PATIENT),
+			Error::<Test>::NoTombstone
+		);
+	});
+}
+
+#[test]
+fn frozen_record_can_not_be_deleted() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		assert_ok!(PatientModule::set_frozen(RuntimeOrigin::signed(PRIVACY_ADMIN), PATIENT, true));
+
+		assert_noop!(
+			PatientModule::delete_patient_record(RuntimeOrigin::signed(PATIENT)),
+			Error::<Test>::RecordFrozen
+		);
+	});
+}
+
+#[test]
+fn purge_after_retention_clears_the_state_kept_next_to_the_record() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		update(DOCTOR, PATIENT, 1);
+		update(DOCTOR, PATIENT, 2);
+		assert_ok!(PatientModule::acknowledge_update(RuntimeOrigin::signed(PATIENT)));
+		assert_ok!(PatientModule::set_require_update_reason(RuntimeOrigin::signed(PATIENT), true));
+		assert_ok!(PatientModule::set_doctor_blocked(RuntimeOrigin::signed(PATIENT), DOCTOR, true));
+		assert_ok!(PatientModule::set_care_team(
+			RuntimeOrigin::root(),
+			1,
+			accounts(vec![PATIENT, OTHER_PATIENT]),
+			accounts(vec![OTHER_DOCTOR])
+		));
+		assert_ok!(PatientModule::set_privacy(RuntimeOrigin::signed(PATIENT), true));
+		assert_ok!(PatientModule::set_default_scope(
+			RuntimeOrigin::signed(PATIENT),
+			Scope::ReadOnly
+		));
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), 2));
+		consent_to_cardiology(PATIENT);
+		assert_ok!(PatientModule::set_approval_cap(RuntimeOrigin::signed(PATIENT), Some(3)));
+		set_max_duration(PATIENT, Some(30));
+		assert_ok!(PatientModule::attest_view(
+			RuntimeOrigin::signed(DOCTOR),
+			PATIENT,
+			hash(9),
+			1,
+			TestSignature(DOCTOR, (PATIENT, hash(9), 1u64).encode())
+		));
+		leave_feedback(PATIENT, DOCTOR, 3);
+		assert_ok!(PatientModule::delete_patient_record(RuntimeOrigin::signed(PATIENT)));
+
+		// Deleted at block 1, kept for 10 blocks.
+		PatientModule::on_idle(10, Weight::MAX);
+		assert!(Tombstones::<Test>::contains_key(PATIENT));
+		assert!(Private::<Test>::get(PATIENT));
+
+		PatientModule::on_idle(11, Weight::MAX);
+		System::assert_last_event(Event::RecordPurged { patient_account_id: PATIENT }.into());
+		assert!(!Tombstones::<Test>::contains_key(PATIENT));
+		assert_eq!(CareTeams::<Test>::get(1).into_inner(), vec![OTHER_PATIENT]);
+		assert!(!RecentEvents::<Test>::contains_key(PATIENT));
+		assert!(!LastUpdateBlock::<Test>::contains_key(PATIENT));
+		assert!(!ApprovalActionCount::<Test>::contains_key(PATIENT));
+		assert!(!UpdateCount::<Test>::contains_key(PATIENT));
+		assert!(!PreviousDataHash::<Test>::contains_key(PATIENT));
+		assert!(!LastAcknowledged::<Test>::contains_key(PATIENT));
+		assert!(!Private::<Test>::contains_key(PATIENT));
+		assert!(!RequireUpdateReason::<Test>::contains_key(PATIENT));
+		assert!(!DefaultScope::<Test>::contains_key(PATIENT));
+		assert!(!RecordLayout::<Test>::contains_key(PATIENT));
+		assert!(!AccessLog::<Test>::contains_key(PATIENT));
+		assert!(!SpecialtyConsent::<Test>::contains_key(PATIENT));
+		assert!(!RequestSeq::<Test>::contains_key(PATIENT));
+		assert!(!ApprovalCap::<Test>::contains_key(PATIENT));
+		assert!(!MaxApprovalDuration::<Test>::contains_key(PATIENT));
+		assert!(!DoctorFeedback::<Test>::contains_key(DOCTOR, PATIENT));
+		assert!(!FeedbackGiven::<Test>::contains_key(PATIENT, DOCTOR));
+		assert_eq!(PatientModule::feedback_count(DOCTOR), 0);
+		assert!(BlockList::<Test>::iter_prefix(PATIENT).next().is_none());
+		assert!(DataMap::<Test>::contains_key(OTHER_PATIENT));
+
+		assert_noop!(
+			PatientModule::restore_record(RuntimeOrigin::root(), PATIENT),
+			Error::<Test>::NoTombstone
+		);
+	});
+}
+
+/// Weight `on_idle` needs to check, and if expired purge, a single tombstone while no care team
+/// exists.
+fn one_tombstone() -> Weight {
+	let db = <Test as frame_system::Config>::DbWeight::get();
+	db.reads_writes(1, 1).saturating_add(db.reads(1)).saturating_add(db.writes(21))
+}
+
+#[test]
+fn purge_continues_where_the_previous_block_stopped() {
+	new_test_ext().execute_with(|| {
+		for patient in [PATIENT, OTHER_PATIENT, 3] {
+			register_patient(patient);
+			assert_ok!(PatientModule::delete_patient_record(RuntimeOrigin::signed(patient)));
+		}
+
+		// Nothing expired yet, each block checks the next tombstone.
+		let mut checked = Vec::new();
+		for _ in 0..2 {
+			assert!(PatientModule::on_idle(2, one_tombstone()).all_lte(one_tombstone()));
+			let cursor = PurgeCursor::<Test>::get().unwrap().into_inner();
+			assert!(!checked.contains(&cursor));
+			checked.push(cursor);
+		}
+		PatientModule::on_idle(2, one_tombstone());
+		assert!(PurgeCursor::<Test>::get().is_none());
+		assert_eq!(Tombstones::<Test>::iter().count(), 3);
+
+		// Too little weight to check any tombstone leaves the cursor alone.
+		assert_eq!(PatientModule::on_idle(11, Weight::zero()), Weight::zero());
+
+		for purged in 1..=3 {
+			PatientModule::on_idle(11, one_tombstone());
+			assert_eq!(Tombstones::<Test>::iter().count(), 3 - purged);
+		}
+	});
+}
+
+#[test]
+fn doctor_side_view_mirrors_the_approval_scope() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		request(DOCTOR, PATIENT);
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadOnly,
+			None
+		));
+		approve(DOCTOR, OTHER_PATIENT);
+
+		assert_eq!(
+			DoctorModule::approved_patients_with_scope(DOCTOR),
+			vec![(PATIENT, Scope::ReadOnly), (OTHER_PATIENT, Scope::ReadWrite)]
+		);
+
+		assert_ok!(PatientModule::set_approval_scope(
+			RuntimeOrigin::signed(OTHER_PATIENT),
+			DOCTOR,
+			Scope::ReadOnly
+		));
+		assert_eq!(DoctorModule::approved_scope(DOCTOR, OTHER_PATIENT), Some(Scope::ReadOnly));
+
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+		assert_eq!(DoctorModule::approved_scope(DOCTOR, PATIENT), None);
+		assert_eq!(
+			DoctorModule::approved_patients_with_scope(DOCTOR),
+			vec![(OTHER_PATIENT, Scope::ReadOnly)]
+		);
+	});
+}
+
+#[test]
+fn upgrade_mirrors_existing_approval_scopes() {
+	new_test_ext().execute_with(|| {
+		ApprovalDetails::<Test>::insert(
+			PATIENT,
+			DOCTOR,
+			Approval::<Test> { scope: Scope::ReadOnly, ..Default::default() },
+		);
+		StorageVersion::new(5).put::<PatientModule>();
+
+		PatientModule::on_runtime_upgrade();
+
+		assert_eq!(DoctorModule::approved_scope(DOCTOR, PATIENT), Some(Scope::ReadOnly));
+		assert_eq!(
+			PatientModule::on_chain_storage_version(),
+			PatientModule::current_storage_version()
+		);
+	});
+}
+
+#[test]
+fn default_approval_uses_the_patient_default_scope() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		request(DOCTOR, PATIENT);
+		assert_ok!(PatientModule::approve_request_default(RuntimeOrigin::signed(PATIENT), DOCTOR));
+		assert_eq!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().scope, Scope::ReadWrite);
+
+		assert_noop!(
+			PatientModule::set_default_scope(RuntimeOrigin::signed(OTHER_PATIENT), Scope::ReadOnly),
+			Error::<Test>::NoPatient
+		);
+		assert_ok!(PatientModule::set_default_scope(
+			RuntimeOrigin::signed(PATIENT),
+			Scope::ReadOnly
+		));
+		System::assert_last_event(
+			Event::DefaultScopeChanged { patient_account_id: PATIENT, scope: Scope::ReadOnly }
+				.into(),
+		);
+
+		request(OTHER_DOCTOR, PATIENT);
+		assert_ok!(PatientModule::approve_request_default(
+			RuntimeOrigin::signed(PATIENT),
+			OTHER_DOCTOR
+		));
+		assert_eq!(
+			ApprovalDetails::<Test>::get(PATIENT, OTHER_DOCTOR).unwrap().scope,
+			Scope::ReadOnly
+		);
+		assert_noop!(try_update(OTHER_DOCTOR, PATIENT, 1), Error::<Test>::ReadOnlyAccess);
+	});
+}
+
+#[test]
+fn duplicate_hashes_are_rejected_only_when_configured() {
+	new_test_ext().execute_with(|| {
+		let write = |data_hash: u8, personal_data_hash: u8| {
+			PatientModule::update_patient_data(
+				RuntimeOrigin::signed(DOCTOR),
+				PATIENT,
+				Some(hash(data_hash)),
+				Some(hash(personal_data_hash)),
+				None,
+				[0u8; 16],
+				None,
+				None,
+				None,
+			)
+		};
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		assert_ok!(write(1, 1));
+
+		RejectDuplicateHashes::set(true);
+		assert_noop!(write(2, 2), Error::<Test>::DuplicateHash);
+		assert_ok!(write(2, 3));
+	});
+}
+
+#[test]
+fn batch_migration_spans_calls_and_clears_the_cursor() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=5 {
+			register_patient(who);
+		}
+
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), 2));
+		System::assert_last_event(Event::MigrationProgress { done: 2, remaining: 3 }.into());
+		assert!(MigrationCursor::<Test>::get().is_some());
+
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), 2));
+		System::assert_last_event(Event::MigrationProgress { done: 4, remaining: 1 }.into());
+
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), 2));
+		System::assert_last_event(Event::MigrationProgress { done: 5, remaining: 0 }.into());
+		assert!(MigrationCursor::<Test>::get().is_none());
+
+		for who in 1..=5 {
+			assert_eq!(RecordLayout::<Test>::get(who), PATIENTS_LAYOUT);
+		}
+
+		assert_noop!(
+			PatientModule::migrate_patients_batch(RuntimeOrigin::signed(PATIENT), 2),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn batch_migration_converts_old_layouts() {
+	new_test_ext().execute_with(|| {
+		let no_hash: Option<Vec<u8>> = None;
+
+		// V0 layout: personal data hash, data hash and LOINC code.
+		let v0 = (Some(vec![1u8; 4]), Some(vec![2u8; 4]), no_hash.clone()).encode();
+		// V1 layout: V0 plus the backup data hash.
+		let v1 =
+			(Some(vec![1u8; 4]), no_hash.clone(), no_hash.clone(), Some(vec![3u8; 4])).encode();
+		// V2 layout: V1 plus the deceased flag.
+		let v2 = (no_hash.clone(), Some(vec![2u8; 4]), no_hash.clone(), no_hash, true).encode();
+
+		for (who, raw) in [(1u64, v0), (2, v1), (3, v2)] {
+			unhashed::put_raw(&DataMap::<Test>::hashed_key_for(who), &raw);
+			assert!(DataMap::<Test>::get(who).is_none());
+		}
+		PatientCount::<Test>::put(3);
+
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), 4));
+
+		let record = DataMap::<Test>::get(1).unwrap();
+		assert_eq!(record.personal_data_hash, Some(hash(1)));
+		assert_eq!(record.data_hash, Some(hash(2)));
+		assert_eq!(record.backup_data_hash, None);
+		assert!(!record.deceased);
+
+		let record = DataMap::<Test>::get(2).unwrap();
+		assert_eq!(record.personal_data_hash, Some(hash(1)));
+		assert_eq!(record.data_hash, None);
+		assert_eq!(record.backup_data_hash, Some(hash(3)));
+
+		let record = DataMap::<Test>::get(3).unwrap();
+		assert_eq!(record.data_hash, Some(hash(2)));
+		assert!(record.deceased);
+		assert_eq!(record.content_type, [0u8; 16]);
+
+		for who in 1..=3 {
+			assert_eq!(RecordLayout::<Test>::get(who), PATIENTS_LAYOUT);
+		}
+	});
+}
+
+#[test]
+fn patient_sets_and_clears_the_emergency_contact() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PatientModule::set_emergency_contact(RuntimeOrigin::signed(PATIENT), Some(hash(5))),
+			Error::<Test>::NoPatient
+		);
+
+		register_patient(PATIENT);
+		assert_eq!(DataMap::<Test>::get(PATIENT).unwrap().emergency_contact_hash, None);
+
+		assert_ok!(PatientModule::set_emergency_contact(
+			RuntimeOrigin::signed(PATIENT),
+			Some(hash(5))
+		));
+		System::assert_last_event(
+			Event::EmergencyContactSet { patient_account_id: PATIENT }.into(),
+		);
+		assert_eq!(DataMap::<Test>::get(PATIENT).unwrap().emergency_contact_hash, Some(hash(5)));
+
+		assert_ok!(PatientModule::set_emergency_contact(RuntimeOrigin::signed(PATIENT), None));
+		assert_eq!(DataMap::<Test>::get(PATIENT).unwrap().emergency_contact_hash, None);
+	});
+}
+
+#[test]
+fn runtime_upgrade_leaves_records_to_the_batch_migration() {
+	new_test_ext().execute_with(|| {
+		let no_hash: Option<Vec<u8>> = None;
+		let content_type = [7u8; 16];
+		// V3 layout: V2 plus the content type.
+		let v3 =
+			(no_hash.clone(), Some(vec![2u8; 4]), no_hash.clone(), no_hash, false, content_type)
+				.encode();
+		let key = DataMap::<Test>::hashed_key_for(PATIENT);
+		unhashed::put_raw(&key, &v3);
+		PatientCount::<Test>::put(1);
+
+		StorageVersion::new(6).put::<PatientModule>();
+		PatientModule::on_runtime_upgrade();
+
+		assert_eq!(unhashed::get_raw(&key), Some(v3));
+		assert_eq!(
+			PatientModule::on_chain_storage_version(),
+			PatientModule::current_storage_version()
+		);
+
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), 1));
+
+		let record = DataMap::<Test>::get(PATIENT).unwrap();
+		assert_eq!(record.data_hash, Some(hash(2)));
+		assert_eq!(record.content_type, content_type);
+		assert_eq!(record.emergency_contact_hash, None);
+		assert_eq!(RecordLayout::<Test>::get(PATIENT), PATIENTS_LAYOUT);
+	});
+}
+
+#[test]
+fn allowed_prefixes_restrict_registered_and_updated_hashes() {
+	new_test_ext().execute_with(|| {
+		let prefix = || BoundedVec::truncate_from(vec![1u8]);
+		let register = |who: u64, byte: u8| {
+			PatientModule::register_patient_self(
+				RuntimeOrigin::signed(who),
+				None,
+				Some(hash(byte)),
+				None,
+			)
+		};
+
+		assert_noop!(
+			PatientModule::add_allowed_prefix(RuntimeOrigin::signed(PATIENT), prefix()),
+			BadOrigin
+		);
+		assert_ok!(PatientModule::add_allowed_prefix(RuntimeOrigin::root(), prefix()));
+		System::assert_last_event(Event::AllowedPrefixAdded { prefix: prefix() }.into());
+		assert_noop!(
+			PatientModule::add_allowed_prefix(RuntimeOrigin::root(), prefix()),
+			Error::<Test>::PrefixAlreadyAllowed
+		);
+
+		assert_noop!(register(PATIENT, 2), Error::<Test>::DisallowedBackend);
+		assert_ok!(register(PATIENT, 1));
+
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		assert_noop!(try_update(DOCTOR, PATIENT, 2), Error::<Test>::DisallowedBackend);
+		assert_ok!(try_update(DOCTOR, PATIENT, 1));
+	});
+}
+
+#[test]
+fn empty_prefix_set_allows_every_hash() {
+	new_test_ext().execute_with(|| {
+		let prefix = || BoundedVec::truncate_from(vec![1u8]);
+
+		assert_ok!(PatientModule::add_allowed_prefix(RuntimeOrigin::root(), prefix()));
+		assert_ok!(PatientModule::remove_allowed_prefix(RuntimeOrigin::root(), prefix()));
+		System::assert_last_event(Event::AllowedPrefixRemoved { prefix: prefix() }.into());
+		assert!(AllowedHashPrefixes::<Test>::get().is_empty());
+		assert_noop!(
+			PatientModule::remove_allowed_prefix(RuntimeOrigin::root(), prefix()),
+			Error::<Test>::PrefixNotAllowed
+		);
+
+		assert_ok!(PatientModule::register_patient_self(
+			RuntimeOrigin::signed(PATIENT),
+			None,
+			Some(hash(2)),
+			None
+		));
+	});
+}
+
+#[test]
+fn allowed_prefixes_are_bounded() {
+	new_test_ext().execute_with(|| {
+		for byte in 1..=2u8 {
+			assert_ok!(PatientModule::add_allowed_prefix(
+				RuntimeOrigin::root(),
+				BoundedVec::truncate_from(vec![byte])
+			));
+		}
+
+		assert_noop!(
+			PatientModule::add_allowed_prefix(
+				RuntimeOrigin::root(),
+				BoundedVec::truncate_from(vec![3u8])
+			),
+			Error::<Test>::TooManyPrefixes
+		);
+	});
+}
+
+#[test]
+fn approval_edges_pair_each_patient_with_their_doctors() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		for patient in [PATIENT, OTHER_PATIENT] {
+			approve(DOCTOR, patient);
+			approve(OTHER_DOCTOR, patient);
+		}
+
+		let mut edges = PatientModule::approval_edges(vec![PATIENT, OTHER_PATIENT]);
+		edges.sort();
+		assert_eq!(
+			edges,
+			vec![
+				(PATIENT, DOCTOR),
+				(PATIENT, OTHER_DOCTOR),
+				(OTHER_PATIENT, DOCTOR),
+				(OTHER_PATIENT, OTHER_DOCTOR)
+			]
+		);
+
+		assert_eq!(PatientModule::approval_edges(vec![OTHER_PATIENT]).len(), 2);
+		assert!(PatientModule::approval_edges(vec![3]).is_empty());
+	});
+}
+
+#[test]
+fn attestation_is_bound_to_its_signed_block() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		ApprovalDetails::<Test>::insert(
+			PATIENT,
+			DOCTOR,
+			Approval::<Test> { expires_at: Some(20), ..Default::default() },
+		);
+
+		let sign = |doctor: u64, at: u64| TestSignature(doctor, (PATIENT, hash(9), at).encode());
+		let attest = |doctor: u64, at: u64, signature: TestSignature| {
+			PatientModule::attest_view(
+				RuntimeOrigin::signed(doctor),
+				PATIENT,
+				hash(9),
+				at,
+				signature,
+			)
+		};
+
+		System::set_block_number(4);
+		assert_noop!(attest(OTHER_DOCTOR, 2, sign(OTHER_DOCTOR, 2)), Error::<Test>::NotApproved);
+		assert_ok!(attest(DOCTOR, 2, sign(DOCTOR, 2)));
+		let attestation = <Test as frame_system::Config>::Hashing::hash_of(&(
+			(PATIENT, hash(9), 2u64).encode(),
+			sign(DOCTOR, 2),
+		));
+		System::assert_last_event(
+			Event::ViewAttested {
+				patient_account_id: PATIENT,
+				doctor_account_id: DOCTOR,
+				attestation,
+			}
+			.into(),
+		);
+		assert_eq!(
+			AccessLog::<Test>::get(PATIENT).into_inner(),
+			vec![AccessLogEntry { signer: DOCTOR, on_behalf_of: None, at: 2, hash: attestation }]
+		);
+
+		// Signed for another block, or forged by another account.
+		assert_noop!(attest(DOCTOR, 3, sign(DOCTOR, 2)), Error::<Test>::InvalidSignature);
+		assert_noop!(attest(DOCTOR, 2, sign(OTHER_DOCTOR, 2)), Error::<Test>::InvalidSignature);
+		assert_noop!(attest(DOCTOR, 5, sign(DOCTOR, 5)), Error::<Test>::AttestationInFuture);
+
+		System::set_block_number(10);
+		assert_noop!(attest(DOCTOR, 4, sign(DOCTOR, 4)), Error::<Test>::AttestationTooOld);
+		assert_ok!(attest(DOCTOR, 5, sign(DOCTOR, 5)));
+
+		System::set_block_number(20);
+		assert_noop!(attest(DOCTOR, 20, sign(DOCTOR, 20)), Error::<Test>::ApprovalExpired);
+	});
+}
+
+#[test]
+fn full_access_log_reports_the_evicted_entry() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		let attest = |at: u64| {
+			System::set_block_number(at);
+			assert_ok!(PatientModule::attest_view(
+				RuntimeOrigin::signed(DOCTOR),
+				PATIENT,
+				hash(9),
+				at,
+				TestSignature(DOCTOR, (PATIENT, hash(9), at).encode())
+			));
+			AccessLog::<Test>::get(PATIENT).last().cloned().unwrap()
+		};
+
+		let first = attest(1);
+		let second = attest(2);
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::PatientModule(Event::AccessLogEntryEvicted { .. })
+		)));
+
+		let third = attest(3);
+		System::assert_has_event(
+			Event::AccessLogEntryEvicted { patient_account_id: PATIENT, evicted_entry: first }
+				.into(),
+		);
+		assert_eq!(AccessLog::<Test>::get(PATIENT).into_inner(), vec![second.clone(), third]);
+
+		attest(4);
+		System::assert_has_event(
+			Event::AccessLogEntryEvicted { patient_account_id: PATIENT, evicted_entry: second }
+				.into(),
+		);
+		assert_eq!(AccessLog::<Test>::get(PATIENT).len(), 2);
+	});
+}
+
+#[test]
+fn view_events_can_be_suppressed_without_losing_the_log() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		let attest = || {
+			assert_ok!(PatientModule::attest_view(
+				RuntimeOrigin::signed(DOCTOR),
+				PATIENT,
+				hash(9),
+				1,
+				TestSignature(DOCTOR, (PATIENT, hash(9), 1u64).encode())
+			));
+		};
+		let view_events = || {
+			System::events()
+				.into_iter()
+				.filter(|record| {
+					matches!(record.event, RuntimeEvent::PatientModule(Event::ViewAttested { .. }))
+				})
+				.count()
+		};
+
+		EmitViewEvents::set(false);
+		attest();
+		assert_eq!(AccessLog::<Test>::get(PATIENT).len(), 1);
+		assert_eq!(view_events(), 0);
+
+		EmitViewEvents::set(true);
+		attest();
+		assert_eq!(AccessLog::<Test>::get(PATIENT).len(), 2);
+		assert_eq!(view_events(), 1);
+	});
+}
+
+fn merge(source: u64, target: u64, force: bool) -> DispatchResult {
+	PatientModule::merge_records(RuntimeOrigin::signed(PATIENT_ADMIN), source, target, force)
+}
+
+/// Sets the data hash of a registered Patient's record directly.
+fn set_data_hash(patient: u64, byte: u8) {
+	DataMap::<Test>::mutate(patient, |record| {
+		let record = record.as_mut().unwrap();
+		record.data_hash = Some(hash(byte));
+		record.content_type = [byte; 16];
+	});
+}
+
+#[test]
+fn clean_merge_moves_doctors_and_data_to_the_target() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		set_data_hash(PATIENT, 2);
+		approve(DOCTOR, PATIENT);
+		request(DOCTOR, OTHER_PATIENT);
+		approve(OTHER_DOCTOR, OTHER_PATIENT);
+		request(OTHER_DOCTOR, PATIENT);
+
+		assert_noop!(
+			PatientModule::merge_records(
+				RuntimeOrigin::signed(PATIENT),
+				PATIENT,
+				OTHER_PATIENT,
+				false
+			),
+			pallet_access::Error::<Test>::AccessDenied
+		);
+		assert_noop!(merge(PATIENT, PATIENT, false), Error::<Test>::MergeConflict);
+
+		assert_ok!(merge(PATIENT, OTHER_PATIENT, false));
+		System::assert_last_event(
+			Event::RecordsMerged { source: PATIENT, target: OTHER_PATIENT }.into(),
+		);
+
+		let record = DataMap::<Test>::get(OTHER_PATIENT).unwrap();
+		assert_eq!(record.data_hash, Some(hash(2)));
+		assert_eq!(record.content_type, [2u8; 16]);
+		assert!(!DataMap::<Test>::contains_key(PATIENT));
+		assert_eq!(PatientCount::<Test>::get(), 1);
+
+		// Requests of Doctors approved on either side are settled by the approval.
+		assert_eq!(
+			AprovedRequestMap::<Test>::get(OTHER_PATIENT).into_inner(),
+			vec![OTHER_DOCTOR, DOCTOR]
+		);
+		assert!(RequestMap::<Test>::get(OTHER_PATIENT).is_empty());
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).is_empty());
+		assert!(ApprovalDetails::<Test>::get(OTHER_PATIENT, DOCTOR).is_some());
+		assert!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).is_none());
+		assert_eq!(PendingRequestCount::<Test>::get(), 0);
+
+		assert_eq!(
+			pallet_doctor::AprovedRequestMap::<Test>::get(DOCTOR).into_inner(),
+			vec![OTHER_PATIENT]
+		);
+		assert!(pallet_doctor::RequestMap::<Test>::get(DOCTOR).is_empty());
+		assert!(pallet_doctor::RequestMap::<Test>::get(OTHER_DOCTOR).is_empty());
+	});
+}
+
+#[test]
+fn merge_moves_pending_requests_and_drops_blocked_doctors() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		request(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		assert_ok!(PatientModule::set_doctor_blocked(
+			RuntimeOrigin::signed(OTHER_PATIENT),
+			OTHER_DOCTOR,
+			true
+		));
+
+		assert_ok!(merge(PATIENT, OTHER_PATIENT, false));
+
+		assert_eq!(RequestMap::<Test>::get(OTHER_PATIENT).into_inner(), vec![DOCTOR]);
+		assert_eq!(
+			pallet_doctor::RequestMap::<Test>::get(DOCTOR).into_inner(),
+			vec![OTHER_PATIENT]
+		);
+		assert_eq!(PendingRequestCount::<Test>::get(), 1);
+
+		assert!(AprovedRequestMap::<Test>::get(OTHER_PATIENT).is_empty());
+		assert!(pallet_doctor::AprovedRequestMap::<Test>::get(OTHER_DOCTOR).is_empty());
+	});
+}
+
+#[test]
+fn conflicting_merge_needs_force_and_keeps_the_target_data() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		set_data_hash(PATIENT, 2);
+		set_data_hash(OTHER_PATIENT, 3);
+
+		assert_noop!(merge(PATIENT, OTHER_PATIENT, false), Error::<Test>::MergeConflict);
+
+		assert_ok!(merge(PATIENT, OTHER_PATIENT, true));
+
+		let record = DataMap::<Test>::get(OTHER_PATIENT).unwrap();
+		assert_eq!(record.data_hash, Some(hash(3)));
+		assert_eq!(record.content_type, [3u8; 16]);
+		assert!(!DataMap::<Test>::contains_key(PATIENT));
+	});
+}
+
+#[test]
+fn merge_moves_the_access_log_and_clears_the_source_state() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		assert_ok!(PatientModule::attest_view(
+			RuntimeOrigin::signed(DOCTOR),
+			PATIENT,
+			hash(9),
+			1,
+			TestSignature(DOCTOR, (PATIENT, hash(9), 1u64).encode())
+		));
+		let entry = AccessLog::<Test>::get(PATIENT)[0].clone();
+		assert_ok!(PatientModule::set_default_scope(
+			RuntimeOrigin::signed(PATIENT),
+			Scope::ReadOnly
+		));
+		assert_ok!(PatientModule::set_care_team(
+			RuntimeOrigin::root(),
+			1,
+			accounts(vec![PATIENT]),
+			accounts(vec![OTHER_DOCTOR])
+		));
+
+		assert_ok!(merge(PATIENT, OTHER_PATIENT, false));
+
+		assert_eq!(AccessLog::<Test>::get(OTHER_PATIENT).into_inner(), vec![entry]);
+		assert!(!AccessLog::<Test>::contains_key(PATIENT));
+		assert!(!DefaultScope::<Test>::contains_key(PATIENT));
+		assert!(CareTeams::<Test>::get(1).is_empty());
+	});
+}
+
+#[test]
+fn expired_license_rejects_requests_and_updates() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		assert_ok!(DoctorModule::set_license_expiry(
+			RuntimeOrigin::signed(PRIVACY_ADMIN),
+			DOCTOR,
+			Some(5)
+		));
+
+		// Valid until block 5.
+		System::set_block_number(4);
+		update(DOCTOR, PATIENT, 1);
+
+		System::set_block_number(5);
+		assert_noop!(try_update(DOCTOR, PATIENT, 2), Error::<Test>::LicenseExpired);
+		assert_noop!(
+			PatientModule::request_patient_data(RuntimeOrigin::signed(DOCTOR), OTHER_PATIENT),
+			Error::<Test>::LicenseExpired
+		);
+
+		assert_ok!(DoctorModule::set_license_expiry(
+			RuntimeOrigin::signed(PRIVACY_ADMIN),
+			DOCTOR,
+			Some(10)
+		));
+		update(DOCTOR, PATIENT, 2);
+		request(DOCTOR, OTHER_PATIENT);
+	});
+}
+
+#[test]
+fn batches_take_exactly_max_batch_entries() {
+	new_test_ext().execute_with(|| {
+		type Bytes = BoundedVec<u8, <Test as pallet_doctor::Config>::MaxHashLength>;
+		type Entries =
+			BoundedVec<(u64, Option<Bytes>, Option<Bytes>), <Test as crate::Config>::MaxBatch>;
+		let max = <<Test as crate::Config>::MaxBatch as Get<u32>>::get() as u64;
+		let entries = |count: u64| (1..=count).map(|who| (who, None, None)).collect::<Vec<_>>();
+
+		let full = Entries::try_from(entries(max)).unwrap();
+		assert_ok!(PatientModule::register_patients_batch(
+			RuntimeOrigin::signed(PATIENT_ADMIN),
+			full
+		));
+		assert_eq!(PatientCount::<Test>::get() as u64, max);
+
+		// One over the bound can neither be built nor decoded from a submitted call.
+		assert!(Entries::try_from(entries(max + 1)).is_err());
+		assert!(Entries::decode(&mut &entries(max + 1).encode()[..]).is_err());
+
+		// Migration batches are capped at the same bound.
+		register_patient(max + 1);
+		assert_ok!(PatientModule::migrate_patients_batch(RuntimeOrigin::root(), u32::MAX));
+		System::assert_last_event(
+			Event::MigrationProgress { done: max as u32, remaining: 1 }.into(),
+		);
+	});
+}
+
+#[test]
+fn consent_fingerprint_follows_approval_changes_only() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		let empty = PatientModule::consent_fingerprint(PATIENT);
+		assert_eq!(PatientModule::consent_fingerprint(OTHER_PATIENT), empty);
+
+		approve(DOCTOR, PATIENT);
+		let one = PatientModule::consent_fingerprint(PATIENT);
+		assert_ne!(one, empty);
+
+		// Unrelated activity leaves the fingerprint alone.
+		update(DOCTOR, PATIENT, 1);
+		request(OTHER_DOCTOR, PATIENT);
+		approve(DOCTOR, OTHER_PATIENT);
+		assert_eq!(PatientModule::consent_fingerprint(PATIENT), one);
+
+		assert_ok!(PatientModule::set_approval_scope(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadOnly
+		));
+		let read_only = PatientModule::consent_fingerprint(PATIENT);
+		assert_ne!(read_only, one);
+
+		assert_ok!(PatientModule::force_revoke_access(
+			RuntimeOrigin::signed(COMPLIANCE),
+			PATIENT,
+			DOCTOR
+		));
+		assert_eq!(PatientModule::consent_fingerprint(PATIENT), empty);
+	});
+}
+
+#[test]
+fn consent_fingerprint_ignores_the_approval_order() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, OTHER_PATIENT);
+		approve(DOCTOR, OTHER_PATIENT);
+
+		assert_eq!(
+			PatientModule::consent_fingerprint(PATIENT),
+			PatientModule::consent_fingerprint(OTHER_PATIENT)
+		);
+	});
+}
+
+#[test]
+fn revoke_all_reports_each_revoked_doctor() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		for doctor in [DOCTOR, OTHER_DOCTOR] {
+			register_doctor(doctor);
+			approve(doctor, PATIENT);
+		}
+
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+
+		assert_eq!(Revocations::get(), vec![(PATIENT, DOCTOR), (PATIENT, OTHER_DOCTOR)]);
+	});
+}
+
+#[test]
+fn force_revoke_reports_the_revoked_doctor() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+
+		assert_ok!(PatientModule::force_revoke_access(
+			RuntimeOrigin::signed(COMPLIANCE),
+			PATIENT,
+			DOCTOR
+		));
+
+		assert_eq!(Revocations::get(), vec![(PATIENT, DOCTOR)]);
+	});
+}
+
+#[test]
+fn merge_reports_approvals_dropped_for_blocked_doctors() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		assert_ok!(PatientModule::set_doctor_blocked(
+			RuntimeOrigin::signed(OTHER_PATIENT),
+			OTHER_DOCTOR,
+			true
+		));
+
+		assert_ok!(merge(PATIENT, OTHER_PATIENT, false));
+
+		assert_eq!(Revocations::get(), vec![(PATIENT, OTHER_DOCTOR)]);
+	});
+}
+
+#[test]
+fn total_active_approvals_follows_approvals_across_patients() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		approve(DOCTOR, OTHER_PATIENT);
+		assert_eq!(PatientModule::total_active_approvals(), 3);
+
+		assert_ok!(PatientModule::force_revoke_access(
+			RuntimeOrigin::signed(COMPLIANCE),
+			OTHER_PATIENT,
+			DOCTOR
+		));
+		assert_eq!(PatientModule::total_active_approvals(), 2);
+
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+		assert_eq!(PatientModule::total_active_approvals(), 0);
+	});
+}
+
+#[test]
+fn merge_counts_only_the_approvals_it_keeps() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		register_doctor(3);
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		approve(3, PATIENT);
+		approve(DOCTOR, OTHER_PATIENT);
+		assert_ok!(PatientModule::set_doctor_blocked(
+			RuntimeOrigin::signed(OTHER_PATIENT),
+			OTHER_DOCTOR,
+			true
+		));
+
+		assert_ok!(merge(PATIENT, OTHER_PATIENT, false));
+
+		assert_eq!(AprovedRequestMap::<Test>::get(OTHER_PATIENT).len(), 2);
+		assert_eq!(PatientModule::total_active_approvals(), 2);
+	});
+}
+
+#[test]
+fn upgrade_counts_the_existing_approvals() {
+	new_test_ext().execute_with(|| {
+		AprovedRequestMap::<Test>::insert(PATIENT, accounts(vec![DOCTOR, OTHER_DOCTOR]));
+		AprovedRequestMap::<Test>::insert(OTHER_PATIENT, accounts(vec![DOCTOR]));
+		StorageVersion::new(7).put::<PatientModule>();
+
+		PatientModule::on_runtime_upgrade();
+
+		assert_eq!(PatientModule::total_active_approvals(), 3);
+		assert_eq!(
+			PatientModule::on_chain_storage_version(),
+			PatientModule::current_storage_version()
+		);
+	});
+}
+
+const CARDIOLOGY: [u8; 8] = *b"cardiolo";
+
+/// Registers `who` as a Doctor of `specialty`.
+fn register_specialist(who: u64, specialty: [u8; 8]) {
+	register_doctor(who);
+	assert_ok!(DoctorModule::set_specialty(RuntimeOrigin::signed(who), Some(specialty)));
+}
+
+fn consent_to_cardiology(who: u64) {
+	assert_ok!(PatientModule::consent_to_specialty(
+		RuntimeOrigin::signed(who),
+		CARDIOLOGY,
+		Some(Scope::ReadOnly)
+	));
+}
+
+fn auto_approved() -> bool {
+	System::events().iter().any(|record| {
+		matches!(record.event, RuntimeEvent::PatientModule(Event::SpecialtyAutoApproved { .. }))
+	})
+}
+
+#[test]
+fn consented_specialty_is_auto_approved() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_specialist(DOCTOR, CARDIOLOGY);
+		register_specialist(OTHER_DOCTOR, *b"dermatol");
+		consent_to_cardiology(PATIENT);
+
+		request(DOCTOR, PATIENT);
+		request(OTHER_DOCTOR, PATIENT);
+
+		assert!(auto_approved());
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).into_inner(), vec![DOCTOR]);
+		assert_eq!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().scope, Scope::ReadOnly);
+		assert_eq!(RequestMap::<Test>::get(PATIENT).into_inner(), vec![OTHER_DOCTOR]);
+		assert_eq!(PendingRequestCount::<Test>::get(), 1);
+	});
+}
+
+#[test]
+fn withdrawn_or_blocked_consent_leaves_the_request_queued() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_specialist(DOCTOR, CARDIOLOGY);
+		register_specialist(OTHER_DOCTOR, CARDIOLOGY);
+		consent_to_cardiology(PATIENT);
+		assert_ok!(PatientModule::set_doctor_blocked(RuntimeOrigin::signed(PATIENT), DOCTOR, true));
+		request(DOCTOR, PATIENT);
+
+		assert_ok!(PatientModule::consent_to_specialty(
+			RuntimeOrigin::signed(PATIENT),
+			CARDIOLOGY,
+			None
+		));
+		request(OTHER_DOCTOR, PATIENT);
+
+		assert!(!auto_approved());
+		assert!(PatientModule::specialty_consent(PATIENT).is_empty());
+		assert_eq!(RequestMap::<Test>::get(PATIENT).into_inner(), vec![DOCTOR, OTHER_DOCTOR]);
+	});
+}
+
+#[test]
+fn specialty_consents_are_bounded() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		for specialty in [*b"cardiolo", *b"dermatol"] {
+			assert_ok!(PatientModule::consent_to_specialty(
+				RuntimeOrigin::signed(PATIENT),
+				specialty,
+				Some(Scope::ReadWrite)
+			));
+		}
+
+		assert_noop!(
+			PatientModule::consent_to_specialty(
+				RuntimeOrigin::signed(PATIENT),
+				*b"neurolog",
+				Some(Scope::ReadWrite)
+			),
+			Error::<Test>::TooManySpecialtyConsents
+		);
+		assert_ok!(PatientModule::consent_to_specialty(
+			RuntimeOrigin::signed(PATIENT),
+			CARDIOLOGY,
+			Some(Scope::ReadOnly)
+		));
+	});
+}
+
+#[test]
+fn failed_auto_approval_leaves_the_request_queued() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		for doctor in 3..7 {
+			register_doctor(doctor);
+			approve(doctor, PATIENT);
+		}
+		register_specialist(DOCTOR, CARDIOLOGY);
+		consent_to_cardiology(PATIENT);
+
+		request(DOCTOR, PATIENT);
+
+		assert!(!auto_approved());
+		assert_eq!(RequestMap::<Test>::get(PATIENT).into_inner(), vec![DOCTOR]);
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).len(), 4);
+		assert_eq!(PendingRequestCount::<Test>::get(), 1);
+	});
+}
+
+#[test]
+fn auto_approval_pending_acceptance_is_not_reported_as_granted() {
+	new_test_ext().execute_with(|| {
+		TwoSidedConsent::set(true);
+		register_patient(PATIENT);
+		register_specialist(DOCTOR, CARDIOLOGY);
+		consent_to_cardiology(PATIENT);
+
+		request(DOCTOR, PATIENT);
+
+		assert!(!auto_approved());
+		assert!(PendingDoctorAcceptance::<Test>::contains_key(PATIENT, DOCTOR));
+		assert!(AprovedRequestMap::<Test>::get(PATIENT).is_empty());
+	});
+}
+
+#[test]
+fn approve_normalizes_a_request_held_next_to_an_approval() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		RequestMap::<Test>::insert(PATIENT, accounts(vec![DOCTOR, DOCTOR]));
+		pallet_doctor::RequestMap::<Test>::insert(DOCTOR, accounts(vec![PATIENT]));
+		PendingRequestCount::<Test>::put(2);
+		assert!(!PatientModule::lists_disjoint(&PATIENT));
+
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+
+		assert!(PatientModule::lists_disjoint(&PATIENT));
+		assert!(RequestMap::<Test>::get(PATIENT).is_empty());
+		assert!(pallet_doctor::RequestMap::<Test>::get(DOCTOR).is_empty());
+		assert_eq!(PendingRequestCount::<Test>::get(), 0);
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).into_inner(), vec![DOCTOR]);
+		assert_eq!(PatientModule::total_active_approvals(), 1);
+	});
+}
+
+#[test]
+fn request_seq_changes_when_requests_are_added_or_removed() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+
+		request(DOCTOR, PATIENT);
+		assert_eq!(PatientModule::request_seq(PATIENT), 1);
+
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		assert_eq!(PatientModule::request_seq(PATIENT), 2);
+
+		request(OTHER_DOCTOR, PATIENT);
+		assert_ok!(PatientModule::clear_all_requests(RuntimeOrigin::signed(PATIENT)));
+		assert_eq!(PatientModule::request_seq(PATIENT), 4);
+
+		assert_ok!(PatientModule::clear_all_requests(RuntimeOrigin::signed(PATIENT)));
+		assert_eq!(PatientModule::request_seq(PATIENT), 4);
+	});
+}
+
+#[test]
+fn request_seq_ignores_unrelated_operations() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		let seq = PatientModule::request_seq(PATIENT);
+
+		update(DOCTOR, PATIENT, 1);
+		assert_ok!(PatientModule::set_default_scope(
+			RuntimeOrigin::signed(PATIENT),
+			Scope::ReadOnly
+		));
+		request(DOCTOR, OTHER_PATIENT);
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+
+		assert_eq!(PatientModule::request_seq(PATIENT), seq);
+	});
+}
+
+#[test]
+fn merge_bumps_the_request_seq_of_both_patients() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		request(DOCTOR, PATIENT);
+
+		assert_ok!(merge(PATIENT, OTHER_PATIENT, false));
+
+		assert_eq!(PatientModule::request_seq(OTHER_PATIENT), 1);
+		assert!(!RequestSeq::<Test>::contains_key(PATIENT));
+	});
+}
+
+#[test]
+fn approved_by_scope_filters_the_approved_doctors() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		for doctor in [DOCTOR, OTHER_DOCTOR, 3] {
+			register_doctor(doctor);
+			approve(doctor, PATIENT);
+		}
+		assert_ok!(PatientModule::set_approval_scope(
+			RuntimeOrigin::signed(PATIENT),
+			OTHER_DOCTOR,
+			Scope::ReadOnly
+		));
+		// Approvals without stored terms count as read-write.
+		ApprovalDetails::<Test>::remove(PATIENT, 3);
+
+		assert_eq!(PatientModule::approved_by_scope(PATIENT, Scope::ReadWrite), vec![DOCTOR, 3]);
+		assert_eq!(PatientModule::approved_by_scope(PATIENT, Scope::ReadOnly), vec![OTHER_DOCTOR]);
+		assert!(PatientModule::approved_by_scope(OTHER_PATIENT, Scope::ReadOnly).is_empty());
+	});
+}
+
+/// Approves `doctor` for `patient` with an approval expiring at `expires_at`.
+fn approve_until(doctor: u64, patient: u64, expires_at: u64) {
+	request(doctor, patient);
+	assert_ok!(PatientModule::approve_request(
+		RuntimeOrigin::signed(patient),
+		doctor,
+		Scope::ReadWrite,
+		Some(expires_at)
+	));
+}
+
+#[test]
+fn renewal_is_due_within_the_renewal_period_and_extends_the_expiry() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve_until(DOCTOR, PATIENT, 20);
+
+		assert_noop!(
+			PatientModule::renew_approval(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			Error::<Test>::RenewalNotDue
+		);
+
+		System::set_block_number(15);
+		assert_ok!(PatientModule::renew_approval(RuntimeOrigin::signed(DOCTOR), PATIENT));
+		System::assert_last_event(
+			Event::RenewalRequested { requester: DOCTOR, patient_account_id: PATIENT }.into(),
+		);
+		assert_eq!(PatientModule::pending_renewal(PATIENT, DOCTOR), Some(15));
+
+		assert_ok!(PatientModule::confirm_renewal(RuntimeOrigin::signed(PATIENT), DOCTOR));
+		System::assert_last_event(
+			Event::ApprovalRenewed {
+				requester: DOCTOR,
+				patient_account_id: PATIENT,
+				expires_at: 25,
+			}
+			.into(),
+		);
+		assert_eq!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().expires_at, Some(25));
+		assert_noop!(
+			PatientModule::confirm_renewal(RuntimeOrigin::signed(PATIENT), DOCTOR),
+			Error::<Test>::NoRenewal
+		);
+	});
+}
+
+#[test]
+fn expired_or_missing_approvals_cannot_be_renewed() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve_until(DOCTOR, PATIENT, 20);
+		approve(OTHER_DOCTOR, PATIENT);
+
+		System::set_block_number(20);
+		assert_noop!(
+			PatientModule::renew_approval(RuntimeOrigin::signed(DOCTOR), PATIENT),
+			Error::<Test>::NotApproved
+		);
+		assert_noop!(
+			PatientModule::renew_approval(RuntimeOrigin::signed(OTHER_DOCTOR), PATIENT),
+			Error::<Test>::ApprovalNotExpiring
+		);
+		assert_noop!(
+			PatientModule::renew_approval(RuntimeOrigin::signed(DOCTOR), OTHER_PATIENT),
+			Error::<Test>::NotApproved
+		);
+	});
+}
+
+#[test]
+fn revoking_an_approval_drops_its_pending_renewal() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve_until(DOCTOR, PATIENT, 5);
+		assert_ok!(PatientModule::renew_approval(RuntimeOrigin::signed(DOCTOR), PATIENT));
+
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+
+		assert_eq!(PatientModule::pending_renewal(PATIENT, DOCTOR), None);
+		assert_noop!(
+			PatientModule::confirm_renewal(RuntimeOrigin::signed(PATIENT), DOCTOR),
+			Error::<Test>::NoRenewal
+		);
+	});
+}
+
+#[test]
+fn approvals_stop_at_the_cap_until_a_doctor_is_revoked() {
+	new_test_ext().execute_with(|| {
+		MaxApprovalsPerPatient::set(2);
+		register_patient(PATIENT);
+		for doctor in [DOCTOR, OTHER_DOCTOR, 3] {
+			register_doctor(doctor);
+		}
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		request(3, PATIENT);
+
+		assert_noop!(
+			PatientModule::approve_request(
+				RuntimeOrigin::signed(PATIENT),
+				3,
+				Scope::ReadWrite,
+				None
+			),
+			Error::<Test>::ApprovalCapReached
+		);
+
+		assert_ok!(PatientModule::force_revoke_access(
+			RuntimeOrigin::signed(COMPLIANCE),
+			PATIENT,
+			DOCTOR
+		));
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			3,
+			Scope::ReadWrite,
+			None
+		));
+	});
+}
+
+#[test]
+fn patient_cap_lowers_but_never_raises_the_limit() {
+	new_test_ext().execute_with(|| {
+		MaxApprovalsPerPatient::set(2);
+		register_patient(PATIENT);
+		for doctor in [DOCTOR, OTHER_DOCTOR, 3] {
+			register_doctor(doctor);
+			request(doctor, PATIENT);
+		}
+
+		assert_ok!(PatientModule::set_approval_cap(RuntimeOrigin::signed(PATIENT), Some(1)));
+		System::assert_last_event(
+			Event::ApprovalCapChanged { patient_account_id: PATIENT, cap: Some(1) }.into(),
+		);
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		assert_noop!(
+			PatientModule::approve_request(
+				RuntimeOrigin::signed(PATIENT),
+				OTHER_DOCTOR,
+				Scope::ReadWrite,
+				None
+			),
+			Error::<Test>::ApprovalCapReached
+		);
+
+		assert_ok!(PatientModule::set_approval_cap(RuntimeOrigin::signed(PATIENT), Some(10)));
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			OTHER_DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		assert_noop!(
+			PatientModule::approve_request(
+				RuntimeOrigin::signed(PATIENT),
+				3,
+				Scope::ReadWrite,
+				None
+			),
+			Error::<Test>::ApprovalCapReached
+		);
+
+		assert_ok!(PatientModule::set_approval_cap(RuntimeOrigin::signed(PATIENT), None));
+		assert_eq!(PatientModule::approval_cap(PATIENT), None);
+		assert_noop!(
+			PatientModule::set_approval_cap(RuntimeOrigin::signed(OTHER_PATIENT), Some(1)),
+			Error::<Test>::NoPatient
+		);
+	});
+}
+
+#[test]
+fn merge_respects_the_target_approval_cap() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, OTHER_PATIENT);
+		assert_ok!(PatientModule::set_approval_cap(RuntimeOrigin::signed(OTHER_PATIENT), Some(1)));
+
+		assert_noop!(merge(PATIENT, OTHER_PATIENT, false), Error::<Test>::ApprovalCapReached);
+
+		assert_ok!(PatientModule::set_approval_cap(RuntimeOrigin::signed(OTHER_PATIENT), Some(2)));
+		assert_ok!(merge(PATIENT, OTHER_PATIENT, false));
+		assert_eq!(AprovedRequestMap::<Test>::get(OTHER_PATIENT).len(), 2);
+	});
+}
+
+#[test]
+fn registration_and_edits_emit_distinct_events() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		System::assert_has_event(Event::PatientRegistered { patient_account_id: PATIENT }.into());
+
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		System::reset_events();
+		update(DOCTOR, PATIENT, 1);
+
+		assert!(matches!(
+			last_event(),
+			Event::DataUpdated { requester: DOCTOR, patient_account_id: PATIENT, .. }
+		));
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::PatientModule(Event::PatientRegistered { .. })
+		)));
+	});
+}
+
+#[test]
+fn is_request_pending_follows_the_request_queue() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+
+		request(DOCTOR, PATIENT);
+		assert!(PatientModule::is_request_pending(PATIENT, DOCTOR));
+
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		assert!(!PatientModule::is_request_pending(PATIENT, DOCTOR));
+		assert!(PatientModule::is_approved(PATIENT, DOCTOR));
+
+		assert!(!PatientModule::is_request_pending(PATIENT, OTHER_DOCTOR));
+		assert!(!PatientModule::is_request_pending(OTHER_PATIENT, DOCTOR));
+	});
+}
+
+#[test]
+fn deregistration_ends_requests_acceptances_and_approvals() {
+	new_test_ext().execute_with(|| {
+		let third_patient = 3;
+		for who in [PATIENT, OTHER_PATIENT, third_patient] {
+			register_patient(who);
+		}
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+
+		approve(DOCTOR, PATIENT);
+		approve(OTHER_DOCTOR, PATIENT);
+		request(DOCTOR, OTHER_PATIENT);
+		assert_ok!(PatientModule::set_care_team(
+			RuntimeOrigin::root(),
+			1,
+			accounts(vec![OTHER_PATIENT]),
+			accounts(vec![DOCTOR, OTHER_DOCTOR])
+		));
+
+		TwoSidedConsent::set(true);
+		approve(DOCTOR, third_patient);
+		assert!(PendingDoctorAcceptance::<Test>::contains_key(third_patient, DOCTOR));
+
+		assert_ok!(DoctorModule::deregister(RuntimeOrigin::signed(DOCTOR)));
+
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).into_inner(), vec![OTHER_DOCTOR]);
+		assert!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).is_none());
+		assert!(RequestMap::<Test>::get(OTHER_PATIENT).is_empty());
+		assert!(RequestedAt::<Test>::get(OTHER_PATIENT, DOCTOR).is_none());
+		assert!(!PendingDoctorAcceptance::<Test>::contains_key(third_patient, DOCTOR));
+		assert!(pallet_doctor::RequestMap::<Test>::get(DOCTOR).is_empty());
+		assert_eq!(PendingRequestCount::<Test>::get(), 0);
+		assert_eq!(TotalActiveApprovals::<Test>::get(), 1);
+		assert_eq!(Revocations::get(), vec![(PATIENT, DOCTOR)]);
+
+		assert!(ClinicianTeams::<Test>::get(DOCTOR).is_empty());
+		assert_eq!(TeamMembers::<Test>::get(1).into_inner(), vec![OTHER_DOCTOR]);
+		assert!(!PatientModule::is_approved(OTHER_PATIENT, DOCTOR));
+		assert!(PatientModule::is_approved(OTHER_PATIENT, OTHER_DOCTOR));
+	});
+}
+
+#[test]
+fn prune_dangling_approvals_pages_through_patients() {
+	new_test_ext().execute_with(|| {
+		let third_doctor = 13;
+		let patients: Vec<u64> = (1..=6).collect();
+		for who in patients.iter() {
+			register_patient(*who);
+		}
+		for who in [DOCTOR, OTHER_DOCTOR, third_doctor] {
+			register_doctor(who);
+		}
+		for who in patients.iter() {
+			approve(if *who <= 4 { DOCTOR } else { third_doctor }, *who);
+		}
+		approve(OTHER_DOCTOR, PATIENT);
+
+		// Left behind by deregistrations from before approvals were cleaned up.
+		pallet_doctor::DataMap::<Test>::remove(DOCTOR);
+		pallet_doctor::DataMap::<Test>::remove(third_doctor);
+
+		assert_noop!(
+			PatientModule::prune_dangling_approvals(RuntimeOrigin::signed(1), 4),
+			BadOrigin
+		);
+
+		assert_ok!(PatientModule::prune_dangling_approvals(RuntimeOrigin::root(), 10));
+		assert!(PatientModule::prune_cursor().is_some());
+		let pruned = || {
+			System::events()
+				.into_iter()
+				.filter(|record| {
+					matches!(
+						record.event,
+						RuntimeEvent::PatientModule(Event::DanglingApprovalPruned { .. })
+					)
+				})
+				.count()
+		};
+		assert_eq!(pruned(), 4);
+
+		assert_ok!(PatientModule::prune_dangling_approvals(RuntimeOrigin::root(), 10));
+		assert!(PatientModule::prune_cursor().is_none());
+		assert_eq!(pruned(), 6);
+
+		for who in patients.iter() {
+			let doctor_ids = AprovedRequestMap::<Test>::get(who);
+			assert!(!doctor_ids.contains(&DOCTOR) && !doctor_ids.contains(&third_doctor));
+		}
+		assert!(pallet_doctor::AprovedRequestMap::<Test>::get(DOCTOR).is_empty());
+		assert!(pallet_doctor::AprovedRequestMap::<Test>::get(third_doctor).is_empty());
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).into_inner(), vec![OTHER_DOCTOR]);
+		assert_eq!(TotalActiveApprovals::<Test>::get(), 1);
+
+		// Nothing is left to prune.
+		assert_ok!(PatientModule::prune_dangling_approvals(RuntimeOrigin::root(), 10));
+		assert_eq!(pruned(), 6);
+	});
+}
+
+#[test]
+fn delegate_writes_on_the_authority_of_an_approved_doctor() {
+	new_test_ext().execute_with(|| {
+		let nurse = 30;
+		let stranger = 31;
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		let write = |who: u64, byte: u8, on_behalf_of: Option<u64>| {
+			PatientModule::update_patient_data(
+				RuntimeOrigin::signed(who),
+				PATIENT,
+				Some(hash(byte)),
+				None,
+				None,
+				[0u8; 16],
+				None,
+				None,
+				on_behalf_of,
+			)
+		};
+
+		assert_noop!(
+			PatientModule::set_delegated_updater(RuntimeOrigin::signed(stranger), nurse, true),
+			pallet_doctor::Error::<Test>::NotRegistered
+		);
+		assert_ok!(PatientModule::set_delegated_updater(
+			RuntimeOrigin::signed(DOCTOR),
+			nurse,
+			true
+		));
+		assert_eq!(
+			last_event(),
+			Event::DelegatedUpdaterChanged {
+				doctor_account_id: DOCTOR,
+				delegate: nurse,
+				authorized: true
+			}
+		);
+
+		System::set_block_number(3);
+		assert_ok!(write(nurse, 5, Some(DOCTOR)));
+		assert_eq!(PatientModule::patient_data(PATIENT).unwrap().data_hash, Some(hash(5)));
+		assert_eq!(
+			last_event(),
+			Event::DelegatedUpdate {
+				delegate: nurse,
+				doctor_account_id: DOCTOR,
+				patient_account_id: PATIENT,
+			}
+		);
+		let entry = AccessLog::<Test>::get(PATIENT)[0].clone();
+		assert_eq!((entry.signer, entry.on_behalf_of, entry.at), (nurse, Some(DOCTOR), 3));
+
+		// Only the Doctor's own delegates may write for them, and only while authorized.
+		assert_noop!(write(stranger, 6, Some(DOCTOR)), Error::<Test>::NotDelegate);
+		assert_ok!(PatientModule::set_delegated_updater(
+			RuntimeOrigin::signed(DOCTOR),
+			nurse,
+			false
+		));
+		assert_noop!(write(nurse, 6, Some(DOCTOR)), Error::<Test>::NotDelegate);
+	});
+}
+
+#[test]
+fn delegate_of_a_doctor_without_access_cannot_write() {
+	new_test_ext().execute_with(|| {
+		let nurse = 30;
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		assert_ok!(PatientModule::set_delegated_updater(
+			RuntimeOrigin::signed(DOCTOR),
+			nurse,
+			true
+		));
+
+		assert_noop!(
+			PatientModule::update_patient_data(
+				RuntimeOrigin::signed(nurse),
+				PATIENT,
+				Some(hash(5)),
+				None,
+				None,
+				[0u8; 16],
+				None,
+				None,
+				Some(DOCTOR),
+			),
+			Error::<Test>::NotApproved
+		);
+	});
+}
+
+#[test]
+fn deregistration_withdraws_the_doctor_delegates() {
+	new_test_ext().execute_with(|| {
+		let nurse = 30;
+		register_doctor(DOCTOR);
+		assert_ok!(PatientModule::set_delegated_updater(
+			RuntimeOrigin::signed(DOCTOR),
+			nurse,
+			true
+		));
+		assert_eq!(PatientModule::delegated_updaters(DOCTOR).into_inner(), vec![nurse]);
+
+		assert_ok!(DoctorModule::deregister(RuntimeOrigin::signed(DOCTOR)));
+
+		assert!(!DelegatedUpdaters::<Test>::contains_key(DOCTOR));
+	});
+}
+
+#[test]
+fn runtime_upgrade_translates_the_access_log() {
+	new_test_ext().execute_with(|| {
+		let hash = <Test as frame_system::Config>::Hash::default();
+		let old: Vec<(u64, u64, _)> = vec![(DOCTOR, 2u64, hash)];
+		unhashed::put_raw(&AccessLog::<Test>::hashed_key_for(PATIENT), &old.encode());
+
+		StorageVersion::new(8).put::<PatientModule>();
+		PatientModule::on_runtime_upgrade();
+
+		assert_eq!(
+			AccessLog::<Test>::get(PATIENT).into_inner(),
+			vec![AccessLogEntry { signer: DOCTOR, on_behalf_of: None, at: 2, hash }]
+		);
+		assert_eq!(
+			StorageVersion::get::<PatientModule>(),
+			PatientModule::current_storage_version()
+		);
+	});
+}
+
+#[test]
+fn protocol_stats_reports_the_maintained_totals() {
+	new_test_ext().execute_with(|| {
+		let roles = 5;
+		assert_eq!(PatientModule::protocol_stats(), ProtocolStats { roles, ..Default::default() });
+
+		for who in [PATIENT, OTHER_PATIENT] {
+			register_patient(who);
+		}
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		approve(DOCTOR, PATIENT);
+		approve(DOCTOR, OTHER_PATIENT);
+		request(OTHER_DOCTOR, PATIENT);
+
+		assert_eq!(
+			PatientModule::protocol_stats(),
+			ProtocolStats {
+				patients: 2,
+				doctors: 2,
+				roles,
+				active_approvals: 2,
+				pending_requests: 1,
+			}
+		);
+
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+		assert_eq!(PatientModule::protocol_stats().active_approvals, 1);
+	});
+}
+
+fn set_max_duration(patient: u64, max_duration: Option<u64>) {
+	assert_ok!(PatientModule::set_max_approval_duration(
+		RuntimeOrigin::signed(patient),
+		max_duration
+	));
+}
+
+#[test]
+fn approval_within_the_max_duration_keeps_its_expiry() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		assert_noop!(
+			PatientModule::set_max_approval_duration(
+				RuntimeOrigin::signed(OTHER_PATIENT),
+				Some(10)
+			),
+			Error::<Test>::NoPatient
+		);
+		set_max_duration(PATIENT, Some(10));
+		assert_eq!(
+			last_event(),
+			Event::MaxApprovalDurationSet { patient_account_id: PATIENT, max_duration: Some(10) }
+		);
+
+		approve_until(DOCTOR, PATIENT, 8);
+
+		assert_eq!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().expires_at, Some(8));
+		assert!(matches!(
+			last_event(),
+			Event::RequestApproved { requester: DOCTOR, expires_at: Some(8), .. }
+		));
+	});
+}
+
+#[test]
+fn over_long_approval_is_clamped_to_the_max_duration() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		set_max_duration(PATIENT, Some(10));
+
+		approve_until(DOCTOR, PATIENT, 100);
+		assert_eq!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().expires_at, Some(11));
+		assert!(matches!(last_event(), Event::RequestApproved { expires_at: Some(11), .. }));
+
+		// An approval without expiry is past any ceiling.
+		System::set_block_number(4);
+		approve(OTHER_DOCTOR, PATIENT);
+		assert_eq!(
+			ApprovalDetails::<Test>::get(PATIENT, OTHER_DOCTOR).unwrap().expires_at,
+			Some(14)
+		);
+
+		set_max_duration(PATIENT, None);
+		assert!(PatientModule::max_approval_duration(PATIENT).is_none());
+	});
+}
+
+#[test]
+fn over_long_approval_is_rejected_when_not_clamping() {
+	new_test_ext().execute_with(|| {
+		ClampApprovalDuration::set(false);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		set_max_duration(PATIENT, Some(10));
+		request(DOCTOR, PATIENT);
+
+		let approve = |expires_at| {
+			PatientModule::approve_request(
+				RuntimeOrigin::signed(PATIENT),
+				DOCTOR,
+				Scope::ReadWrite,
+				expires_at,
+			)
+		};
+		assert_noop!(approve(Some(12)), Error::<Test>::DurationTooLong);
+		assert_noop!(approve(None), Error::<Test>::DurationTooLong);
+
+		assert_ok!(approve(Some(11)));
+		assert_eq!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().expires_at, Some(11));
+	});
+}
+
+#[test]
+fn accepted_approval_keeps_the_clamped_expiry() {
+	new_test_ext().execute_with(|| {
+		TwoSidedConsent::set(true);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		set_max_duration(PATIENT, Some(5));
+
+		approve_until(DOCTOR, PATIENT, 100);
+		assert_eq!(
+			PendingDoctorAcceptance::<Test>::get(PATIENT, DOCTOR),
+			Some((1, Scope::ReadWrite, Some(6)))
+		);
+
+		System::set_block_number(3);
+		assert_ok!(PatientModule::accept_patient(RuntimeOrigin::signed(DOCTOR), PATIENT));
+
+		assert_eq!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().expires_at, Some(6));
+	});
+}
+
+#[test]
+fn renewal_is_clamped_but_never_shortens_the_approval() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve_until(DOCTOR, PATIENT, 20);
+		let renew = || {
+			assert_ok!(PatientModule::renew_approval(RuntimeOrigin::signed(DOCTOR), PATIENT));
+			assert_ok!(PatientModule::confirm_renewal(RuntimeOrigin::signed(PATIENT), DOCTOR));
+			ApprovalDetails::<Test>::get(PATIENT, DOCTOR).unwrap().expires_at
+		};
+
+		// Renewing at block 16 adds `RenewalPeriod` to 20, capped at 16 + 7.
+		System::set_block_number(16);
+		set_max_duration(PATIENT, Some(7));
+		assert_eq!(renew(), Some(23));
+
+		// A ceiling of 19 + 2 lies before the current expiry of 23.
+		System::set_block_number(19);
+		set_max_duration(PATIENT, Some(2));
+		assert_eq!(renew(), Some(23));
+		System::assert_last_event(
+			Event::ApprovalRenewed {
+				requester: DOCTOR,
+				patient_account_id: PATIENT,
+				expires_at: 23,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn runtime_upgrade_adds_no_expiry_to_pending_acceptances() {
+	new_test_ext().execute_with(|| {
+		let key = PendingDoctorAcceptance::<Test>::hashed_key_for(PATIENT, DOCTOR);
+		unhashed::put_raw(&key, &(3u64, Scope::ReadOnly).encode());
+
+		StorageVersion::new(9).put::<PatientModule>();
+		PatientModule::on_runtime_upgrade();
+
+		assert_eq!(
+			PendingDoctorAcceptance::<Test>::get(PATIENT, DOCTOR),
+			Some((3, Scope::ReadOnly, None))
+		);
+		assert_eq!(
+			StorageVersion::get::<PatientModule>(),
+			PatientModule::current_storage_version()
+		);
+	});
+}
+
+#[test]
+fn repair_consistency_removes_one_sided_approvals() {
+	new_test_ext().execute_with(|| {
+		let third_doctor = 13;
+		register_patient(PATIENT);
+		for who in [DOCTOR, OTHER_DOCTOR, third_doctor] {
+			register_doctor(who);
+			approve(who, PATIENT);
+		}
+
+		// `DOCTOR` lost the Patient on its side, `OTHER_DOCTOR` is only left on its side.
+		pallet_doctor::AprovedRequestMap::<Test>::insert(DOCTOR, accounts(vec![]));
+		AprovedRequestMap::<Test>::insert(PATIENT, accounts(vec![DOCTOR, third_doctor]));
+
+		assert_noop!(
+			PatientModule::repair_consistency(RuntimeOrigin::signed(PATIENT), PATIENT),
+			BadOrigin
+		);
+		assert_ok!(PatientModule::repair_consistency(RuntimeOrigin::root(), PATIENT));
+		System::assert_last_event(
+			Event::ConsistencyRepaired { patient_account_id: PATIENT, fixed: 2 }.into(),
+		);
+
+		assert_eq!(AprovedRequestMap::<Test>::get(PATIENT).into_inner(), vec![third_doctor]);
+		assert!(ApprovalDetails::<Test>::get(PATIENT, DOCTOR).is_none());
+		assert!(ApprovalDetails::<Test>::get(PATIENT, OTHER_DOCTOR).is_none());
+		assert!(pallet_doctor::AprovedRequestMap::<Test>::get(OTHER_DOCTOR).is_empty());
+		assert_eq!(
+			pallet_doctor::AprovedRequestMap::<Test>::get(third_doctor).into_inner(),
+			vec![PATIENT]
+		);
+
+		// Both sides agree again, nothing is left to repair.
+		assert_ok!(PatientModule::repair_consistency(RuntimeOrigin::root(), PATIENT));
+		System::assert_last_event(
+			Event::ConsistencyRepaired { patient_account_id: PATIENT, fixed: 0 }.into(),
+		);
+	});
+}
+
+fn leave_feedback(patient: u64, doctor: u64, byte: u8) {
+	assert_ok!(PatientModule::leave_feedback(RuntimeOrigin::signed(patient), doctor, hash(byte)));
+}
+
+#[test]
+fn approved_patient_leaves_and_replaces_feedback() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		approve(DOCTOR, OTHER_PATIENT);
+
+		leave_feedback(PATIENT, DOCTOR, 1);
+		assert_eq!(
+			last_event(),
+			Event::FeedbackLeft {
+				doctor_account_id: DOCTOR,
+				patient_account_id: PATIENT,
+				feedback_hash: hash(1),
+			}
+		);
+		leave_feedback(PATIENT, DOCTOR, 2);
+		leave_feedback(OTHER_PATIENT, DOCTOR, 3);
+
+		assert_eq!(PatientModule::doctor_feedback(DOCTOR, PATIENT), Some(hash(2)));
+		assert_eq!(PatientModule::feedback_count(DOCTOR), 2);
+	});
+}
+
+#[test]
+fn feedback_needs_a_current_or_logged_relationship() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+
+		request(DOCTOR, PATIENT);
+		assert_noop!(
+			PatientModule::leave_feedback(RuntimeOrigin::signed(PATIENT), DOCTOR, hash(1)),
+			Error::<Test>::NotApproved
+		);
+
+		// A Doctor whose view is logged stays eligible once the approval is gone.
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		approve(OTHER_DOCTOR, PATIENT);
+		assert_ok!(PatientModule::attest_view(
+			RuntimeOrigin::signed(DOCTOR),
+			PATIENT,
+			hash(9),
+			1,
+			TestSignature(DOCTOR, (PATIENT, hash(9), 1u64).encode())
+		));
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+
+		leave_feedback(PATIENT, DOCTOR, 1);
+		assert_noop!(
+			PatientModule::leave_feedback(RuntimeOrigin::signed(PATIENT), OTHER_DOCTOR, hash(1)),
+			Error::<Test>::NotApproved
+		);
+		assert_eq!(PatientModule::feedback_count(DOCTOR), 1);
+		assert_eq!(PatientModule::feedback_count(OTHER_DOCTOR), 0);
+	});
+}
+
+#[test]
+fn merge_moves_feedback_unless_the_target_left_its_own() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		for doctor in [DOCTOR, OTHER_DOCTOR] {
+			approve(doctor, PATIENT);
+			leave_feedback(PATIENT, doctor, 1);
+		}
+		approve(DOCTOR, OTHER_PATIENT);
+		leave_feedback(OTHER_PATIENT, DOCTOR, 2);
+
+		assert_ok!(merge(PATIENT, OTHER_PATIENT, false));
+
+		assert_eq!(PatientModule::doctor_feedback(DOCTOR, OTHER_PATIENT), Some(hash(2)));
+		assert_eq!(PatientModule::doctor_feedback(OTHER_DOCTOR, OTHER_PATIENT), Some(hash(1)));
+		assert!(!DoctorFeedback::<Test>::contains_key(DOCTOR, PATIENT));
+		assert!(FeedbackGiven::<Test>::iter_prefix(PATIENT).next().is_none());
+		assert_eq!(PatientModule::feedback_count(DOCTOR), 1);
+		assert_eq!(PatientModule::feedback_count(OTHER_DOCTOR), 1);
+	});
+}
+
+#[test]
+fn approvals_and_revocations_are_limited_per_window() {
+	new_test_ext().execute_with(|| {
+		MaxApprovalActionsPerWindow::set(3);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		register_doctor(OTHER_DOCTOR);
+		request(DOCTOR, PATIENT);
+		request(OTHER_DOCTOR, PATIENT);
+
+		// Window opened at block 1, lasting 10 blocks.
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			DOCTOR,
+			Scope::ReadWrite,
+			None
+		));
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+		System::set_block_number(10);
+		assert_ok!(PatientModule::approve_request_default(
+			RuntimeOrigin::signed(PATIENT),
+			OTHER_DOCTOR
+		));
+		assert_eq!(PatientModule::approval_action_count(PATIENT), (1, 3));
+
+		assert_noop!(
+			PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)),
+			Error::<Test>::TooManyApprovalActions
+		);
+
+		System::set_block_number(11);
+		assert_ok!(PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)));
+		assert_eq!(PatientModule::approval_action_count(PATIENT), (11, 1));
+	});
+}
+
+#[test]
+fn approval_action_limit_is_kept_per_patient() {
+	new_test_ext().execute_with(|| {
+		MaxApprovalActionsPerWindow::set(1);
+		register_patient(PATIENT);
+		register_patient(OTHER_PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+
+		assert_noop!(
+			PatientModule::revoke_all_access(RuntimeOrigin::signed(PATIENT)),
+			Error::<Test>::TooManyApprovalActions
+		);
+		approve(DOCTOR, OTHER_PATIENT);
+	});
+}
+
+#[test]
+fn approved_doctor_profiles_join_approvals_with_doctor_records() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		for (doctor, profile) in
+			[(DOCTOR, Some(hash(1))), (OTHER_DOCTOR, Some(hash(2))), (13, None)]
+		{
+			assert_ok!(DoctorModule::register(RuntimeOrigin::signed(doctor), profile));
+			assert_ok!(AccessModule::assign(RuntimeOrigin::root(), doctor, roles::DOCTOR));
+		}
+		approve(OTHER_DOCTOR, PATIENT);
+		approve(DOCTOR, PATIENT);
+		request(13, PATIENT);
+
+		assert_eq!(
+			PatientModule::approved_doctor_profiles(PATIENT),
+			vec![(OTHER_DOCTOR, Some(hash(2))), (DOCTOR, Some(hash(1)))]
+		);
+
+		assert_ok!(PatientModule::approve_request(
+			RuntimeOrigin::signed(PATIENT),
+			13,
+			Scope::ReadOnly,
+			None
+		));
+		assert_eq!(PatientModule::approved_doctor_profiles(PATIENT)[2], (13, None));
+		assert!(PatientModule::approved_doctor_profiles(OTHER_PATIENT).is_empty());
+	});
+}
+
+#[test]
+fn unchanged_update_is_skipped_without_a_write() {
+	new_test_ext().execute_with(|| {
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		update(DOCTOR, PATIENT, 1);
+		let events = System::events().len();
+
+		System::set_block_number(3);
+		update(DOCTOR, PATIENT, 1);
+
+		assert_eq!(System::events().len(), events);
+		assert_eq!(PatientModule::last_update_block(PATIENT), Some(1));
+		assert_eq!(PatientModule::update_count(PATIENT), 1);
+
+		// Changing any field is a write.
+		assert_ok!(PatientModule::update_patient_data(
+			RuntimeOrigin::signed(DOCTOR),
+			PATIENT,
+			Some(hash(1)),
+			None,
+			None,
+			[1u8; 16],
+			None,
+			None,
+			None,
+		));
+		assert!(System::events().len() > events);
+		assert_eq!(PatientModule::update_count(PATIENT), 2);
+	});
+}
+
+#[test]
+fn unchanged_update_is_rejected_when_configured() {
+	new_test_ext().execute_with(|| {
+		RejectUnchangedUpdates::set(true);
+		register_patient(PATIENT);
+		register_doctor(DOCTOR);
+		approve(DOCTOR, PATIENT);
+		update(DOCTOR, PATIENT, 1);
+
+		assert_noop!(try_update(DOCTOR, PATIENT, 1), Error::<Test>::NoChange);
+		update(DOCTOR, PATIENT, 2);
+	});
+}
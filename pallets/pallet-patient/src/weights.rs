@@ -0,0 +1,83 @@
+//! Weights of the Patient pallet dispatchables.
+//!
+//! `request_patient_data` and `approve_request` write to the Doctor pallet through
+//! `pallet_doctor::Pallet::add_request` and `add_approved_request`. Their weights here are the
+//! Patient side storage accesses plus the matching `pallet_doctor::WeightInfo` helper weight, so
+//! the cross-pallet writes are paid for.
+//!
+//! Both are weighed on their most expensive path, the one the benchmarks run: the request fills
+//! the Patient's request list and the approval crosses `ApprovalAlertThreshold`.
+//! `DataAccessRoles` is taken to hold a single role. Until the benchmarks are run on reference
+//! hardware with `benchmark pallet`, the weights count storage accesses only.
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+use pallet_doctor::WeightInfo as DoctorWeightInfo;
+
+/// Weight functions needed by the Patient pallet.
+pub trait WeightInfo {
+	fn request_patient_data() -> Weight;
+	fn approve_request() -> Weight;
+}
+
+/// Weights derived from the storage accesses of each call, including the Doctor pallet helpers
+/// configured for the runtime.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: crate::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: PatientModule DisabledCalls (r:1 w:0)
+	// Storage: AccessModule Roles (r:1 w:0)
+	// Storage: AccessModule MemberRoles (r:1 w:0)
+	// Storage: PatientModule DataMap (r:1 w:0)
+	// Storage: PatientModule Frozen (r:1 w:0)
+	// Storage: PatientModule Private (r:1 w:0)
+	// Storage: PatientModule RequestMap (r:1 w:1)
+	// Storage: PatientModule AprovedRequestMap (r:1 w:0)
+	// Storage: PatientModule PendingDoctorAcceptance (r:1 w:0)
+	// Storage: PatientModule RequestedAt (r:0 w:1)
+	// Storage: PatientModule PendingRequestCount (r:1 w:1)
+	// Storage: PatientModule RecentEvents (r:1 w:1)
+	// Storage: DoctorModule DataMap (r:1 w:0)
+	// Storage: PatientModule SpecialtyConsent (r:1 w:0)
+	// Storage: PatientModule BlockList (r:1 w:0)
+	// Plus `pallet_doctor::WeightInfo::add_request`
+	// Plus `approve_request` for a Doctor of a pre-consented specialty
+	fn request_patient_data() -> Weight {
+		T::DbWeight::get()
+			.reads_writes(14, 4)
+			.saturating_add(<T as pallet_doctor::Config>::WeightInfo::add_request())
+			.saturating_add(Self::approve_request())
+	}
+	// Storage: PatientModule DisabledCalls (r:1 w:0)
+	// Storage: PatientModule Frozen (r:1 w:0)
+	// Storage: PatientModule DataMap (r:1 w:0)
+	// Storage: PatientModule BlockList (r:1 w:0)
+	// Storage: PatientModule RequestMap (r:1 w:1)
+	// Storage: PatientModule PendingRequestCount (r:1 w:1)
+	// Storage: PatientModule AprovedRequestMap (r:1 w:1)
+	// Storage: PatientModule ApprovalDetails (r:0 w:1)
+	// Storage: PatientModule ApprovalReceipts (r:0 w:1)
+	// Storage: PatientModule RequestedAt (r:1 w:0)
+	// Storage: PatientModule ApprovalCap (r:1 w:0)
+	// Storage: PatientModule MaxApprovalDuration (r:1 w:0)
+	// Storage: PatientModule ApprovalActionCount (r:1 w:1)
+	// Storage: PatientModule TotalActiveApprovals (r:1 w:1)
+	// Storage: PatientModule LastAlertedCount (r:1 w:1)
+	// Storage: PatientModule RecentEvents (r:1 w:1)
+	// Storage: DoctorModule DataMap (r:1 w:0)
+	// Plus `pallet_doctor::WeightInfo::add_approved_request`
+	fn approve_request() -> Weight {
+		T::DbWeight::get()
+			.reads_writes(15, 9)
+			.saturating_add(<T as pallet_doctor::Config>::WeightInfo::add_approved_request())
+	}
+}
+
+impl WeightInfo for () {
+	fn request_patient_data() -> Weight {
+		Weight::zero()
+	}
+	fn approve_request() -> Weight {
+		Weight::zero()
+	}
+}
@@ -106,7 +106,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 	transaction_version: 1,
@@ -278,17 +278,99 @@ impl pallet_sudo::Config for Runtime {
 
 impl pallet_access::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type MaxMetadataLength = frame_support::pallet_prelude::ConstU32<100>;
+	type MaxRoles = frame_support::pallet_prelude::ConstU32<100>;
+	type MaxRotationBatch = frame_support::pallet_prelude::ConstU32<100>;
+	type MaxRolesPerAccount = frame_support::pallet_prelude::ConstU32<16>;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type RoleEventTopics = frame_support::traits::ConstBool<true>;
+}
+
+parameter_types! {
+	pub const PrivacyAdminRole: [u8; 32] = pallet_access::roles::PRIVACY_ADMIN;
+	pub const DeregisterApproverRole: [u8; 32] = pallet_access::roles::PRIVACY_ADMIN;
+	pub const VerificationAdminRole: [u8; 32] = pallet_access::roles::PRIVACY_ADMIN;
+	pub const VerifierRole: [u8; 32] = pallet_access::roles::COMPLIANCE;
+	pub const ComplianceRole: [u8; 32] = pallet_access::roles::COMPLIANCE;
+	pub const PatientRole: [u8; 32] = pallet_access::roles::PATIENT;
+	pub RequireCidPrefix: Vec<u8> = Vec::new();
+}
+
+/// Whether the access pallet has moved the chain's roles onto the well known role ids. Chains
+/// set up before them keep their role checks until then.
+pub struct RolesMigrated;
+impl frame_support::traits::Get<bool> for RolesMigrated {
+	fn get() -> bool {
+		use frame_support::traits::GetStorageVersion;
+
+		AccessModule::on_chain_storage_version() >= 2
+	}
+}
+
+/// Roles allowed to read and write patient data: `roles::DOCTOR` once the roles are migrated,
+/// the zero role staff held before.
+pub struct DataAccessRoles;
+impl frame_support::traits::Get<Vec<[u8; 32]>> for DataAccessRoles {
+	fn get() -> Vec<[u8; 32]> {
+		if <RolesMigrated as frame_support::traits::Get<bool>>::get() {
+			vec![pallet_access::roles::DOCTOR]
+		} else {
+			vec![pallet_access::RESERVED_ROLE]
+		}
+	}
 }
 
 impl pallet_patient::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_patient::weights::SubstrateWeight<Runtime>;
 	type MaxLoincCodeLength = frame_support::pallet_prelude::ConstU32<1000>;
+	type MaxBatch = frame_support::pallet_prelude::ConstU32<100>;
+	type TwoSidedConsent = frame_support::traits::ConstBool<false>;
+	type MaxRecentEvents = frame_support::pallet_prelude::ConstU32<20>;
+	type PrivacyAdminRole = PrivacyAdminRole;
+	type ComplianceRole = ComplianceRole;
+	type AllowPatientSelfRegistration = frame_support::traits::ConstBool<true>;
+	// The patient role only exists once the roles are migrated.
+	type AutoAssignPatientRole = RolesMigrated;
+	type PatientRole = PatientRole;
+	type UpdateCooldown = frame_support::pallet_prelude::ConstU32<10>;
+	type ApprovalActionWindow = frame_support::pallet_prelude::ConstU32<HOURS>;
+	type MaxApprovalActionsPerWindow = frame_support::pallet_prelude::ConstU32<20>;
+	type ApprovalDelay = frame_support::pallet_prelude::ConstU32<0>;
+	type MaxApprovalsPerPatient = frame_support::pallet_prelude::ConstU32<100>;
+	type ApprovalAlertThreshold = frame_support::pallet_prelude::ConstU32<20>;
+	type MaxSeenKeys = frame_support::pallet_prelude::ConstU32<16>;
+	type UnfreezeQuorum = frame_support::pallet_prelude::ConstU32<2>;
+	type DataAccessRoles = DataAccessRoles;
+	type RequireAllDataAccessRoles = frame_support::traits::ConstBool<true>;
+	type RequireCidPrefix = RequireCidPrefix;
+	type RejectDuplicateHashes = frame_support::traits::ConstBool<false>;
+	type RejectUnchangedUpdates = frame_support::traits::ConstBool<false>;
+	type EmitViewEvents = frame_support::traits::ConstBool<true>;
+	type ClampApprovalDuration = frame_support::traits::ConstBool<true>;
+	type MaxLogLength = frame_support::pallet_prelude::ConstU32<100>;
+	type MaxAllowedPrefixes = frame_support::pallet_prelude::ConstU32<16>;
+	type MaxDelegates = frame_support::pallet_prelude::ConstU32<8>;
+	type MaxSpecialtyConsents = frame_support::pallet_prelude::ConstU32<8>;
+	type MaxTeamsPerClinician = frame_support::pallet_prelude::ConstU32<8>;
+	type RetentionPeriod = frame_support::pallet_prelude::ConstU32<{ 30 * DAYS }>;
+	type MaxAttestationAge = frame_support::pallet_prelude::ConstU32<HOURS>;
+	type RenewalPeriod = frame_support::pallet_prelude::ConstU32<{ 30 * DAYS }>;
+	type OnAccessRevoked = ();
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
 }
 
 impl pallet_doctor::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_doctor::weights::SubstrateWeight<Runtime>;
 	type MaxHashLength = frame_support::pallet_prelude::ConstU32<100>;
 	type MaxListLength = frame_support::pallet_prelude::ConstU32<100>;
+	type RequireRoleToDeregister = frame_support::traits::ConstBool<false>;
+	type DeregisterApproverRole = DeregisterApproverRole;
+	type VerificationAdminRole = VerificationAdminRole;
+	type VerifierRole = VerifierRole;
+	type OnDoctorDeregistered = PatientModule;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -357,7 +439,8 @@ mod benches {
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
-		[pallet_template, TemplateModule]
+		[pallet_doctor, DoctorModule]
+		[pallet_patient, PatientModule]
 	);
 }
 
@@ -509,6 +592,133 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_access_runtime_api::AccessApi<Block, pallet_access::RoleMetadata<Runtime>> for Runtime {
+		fn role_metadata(role: [u8; 32]) -> Option<pallet_access::RoleMetadata<Runtime>> {
+			AccessModule::role_metadata(role)
+		}
+
+		fn empty_roles() -> Vec<[u8; 32]> {
+			AccessModule::empty_roles()
+		}
+
+		fn all_roles() -> Vec<[u8; 32]> {
+			AccessModule::all_roles().into_inner()
+		}
+
+		fn role_exists(role: [u8; 32]) -> bool {
+			AccessModule::role_exists(role)
+		}
+	}
+
+	impl pallet_patient_runtime_api::PatientApi<
+		Block,
+		AccountId,
+		BlockNumber,
+		pallet_patient::PatientExport<Runtime>,
+		Hash,
+	> for Runtime {
+		fn recent_events(
+			patient_account_id: AccountId,
+		) -> Vec<(BlockNumber, pallet_patient::EventKind)> {
+			PatientModule::recent_events(patient_account_id).into_inner()
+		}
+
+		fn writable_patients(doctor_account_id: AccountId) -> Vec<AccountId> {
+			PatientModule::writable_patients(doctor_account_id)
+		}
+
+		fn export_patient(
+			patient_account_id: AccountId,
+		) -> Option<pallet_patient::PatientExport<Runtime>> {
+			PatientModule::export_patient(patient_account_id)
+		}
+
+		fn which_registered(accounts: Vec<AccountId>) -> Option<Vec<bool>> {
+			PatientModule::which_registered(accounts)
+		}
+
+		fn has_unacknowledged(patient_account_id: AccountId) -> bool {
+			PatientModule::has_unacknowledged(patient_account_id)
+		}
+
+		fn approval_details(
+			patient_account_id: AccountId,
+			doctor_account_id: AccountId,
+		) -> Option<(pallet_patient::Scope, Option<BlockNumber>)> {
+			PatientModule::approval_details(patient_account_id, doctor_account_id)
+		}
+
+		fn doctor_load(doctor_account_id: AccountId) -> pallet_patient::DoctorLoad {
+			PatientModule::doctor_load(doctor_account_id)
+		}
+
+		fn accessible_patients(account: AccountId) -> Vec<AccountId> {
+			PatientModule::accessible_patients(account)
+		}
+
+		fn is_approved(patient_account_id: AccountId, account: AccountId) -> bool {
+			PatientModule::is_approved(patient_account_id, account)
+		}
+
+		fn is_request_pending(patient_account_id: AccountId, doctor_account_id: AccountId) -> bool {
+			PatientModule::is_request_pending(patient_account_id, doctor_account_id)
+		}
+
+		fn care_team_patients_page(team_id: u32, start: u32, len: u32) -> Vec<AccountId> {
+			PatientModule::care_team_patients_page(team_id, start, len)
+		}
+
+		fn care_team_size(team_id: u32) -> u32 {
+			PatientModule::care_team_size(team_id)
+		}
+
+		fn has_changed_since(patient_account_id: AccountId, block: BlockNumber) -> bool {
+			PatientModule::has_changed_since(patient_account_id, block)
+		}
+
+		fn previous_hash(patient_account_id: AccountId) -> Option<Vec<u8>> {
+			PatientModule::previous_hash(patient_account_id).map(|hash| hash.into_inner())
+		}
+
+		fn approval_edges(patients: Vec<AccountId>) -> Vec<(AccountId, AccountId)> {
+			PatientModule::approval_edges(patients)
+		}
+
+		fn expiring_doctors(within_blocks: BlockNumber, limit: u32) -> Vec<(AccountId, BlockNumber)> {
+			DoctorModule::expiring_doctors(within_blocks, limit)
+		}
+
+		fn consent_fingerprint(patient_account_id: AccountId) -> Hash {
+			PatientModule::consent_fingerprint(patient_account_id)
+		}
+
+		fn request_seq(patient_account_id: AccountId) -> u32 {
+			PatientModule::request_seq(patient_account_id)
+		}
+
+		fn approved_by_scope(
+			patient_account_id: AccountId,
+			scope: pallet_patient::Scope,
+		) -> Vec<AccountId> {
+			PatientModule::approved_by_scope(patient_account_id, scope)
+		}
+
+		fn protocol_stats() -> pallet_patient::ProtocolStats {
+			PatientModule::protocol_stats()
+		}
+
+		fn approved_doctor_profiles(
+			patient_account_id: AccountId,
+		) -> Vec<(AccountId, Option<Vec<u8>>)> {
+			PatientModule::approved_doctor_profiles(patient_account_id)
+				.into_iter()
+				.map(|(doctor_account_id, profile)| {
+					(doctor_account_id, profile.map(|hash| hash.into_inner()))
+				})
+				.collect()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (